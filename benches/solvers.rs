@@ -0,0 +1,39 @@
+//! Compares the solving algorithms on the classic puzzles of the library.
+//! Run with `cargo bench`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use sudoku::{
+    difficulty::Difficulty,
+    library::PUZZLES,
+    solver::{count_solutions, Algorithm},
+};
+
+fn solvers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve");
+    for puzzle in PUZZLES.iter().filter(|puzzle| puzzle.is_classic()) {
+        let cells = puzzle.cells();
+        for algorithm in Algorithm::ALL {
+            // naive backtracking takes seconds a solve on the hard ones
+            if algorithm == Algorithm::Naive && puzzle.difficulty > Difficulty::Medium {
+                continue;
+            }
+            let id = BenchmarkId::new(format!("{algorithm:?}"), puzzle.name);
+            group.bench_with_input(id, &cells, |b, cells| b.iter(|| algorithm.solve(cells)));
+        }
+    }
+    group.finish();
+}
+
+fn uniqueness(c: &mut Criterion) {
+    let mut group = c.benchmark_group("count_solutions");
+    for puzzle in PUZZLES.iter().filter(|puzzle| puzzle.is_classic()) {
+        let cells = puzzle.cells();
+        group.bench_with_input(puzzle.name, &cells, |b, cells| {
+            b.iter(|| count_solutions(cells, 2))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, solvers, uniqueness);
+criterion_main!(benches);
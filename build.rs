@@ -0,0 +1,16 @@
+fn main() {
+    #[cfg(feature = "ffi")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        println!("cargo:rerun-if-env-changed=SUDOKU_WRITE_HEADER");
+        let bindings = cbindgen::generate(&crate_dir).expect("could not generate the C header");
+        // The header is written into OUT_DIR, and only refreshes the copy
+        // checked in under include/ when asked to
+        bindings.write_to_file(format!("{}/sudoku.h", std::env::var("OUT_DIR").unwrap()));
+        if std::env::var_os("SUDOKU_WRITE_HEADER").is_some() {
+            bindings.write_to_file(format!("{crate_dir}/include/sudoku.h"));
+        }
+    }
+}
@@ -0,0 +1,69 @@
+#ifndef SUDOKU_H
+#define SUDOKU_H
+
+/* Generated by cbindgen from src/ffi.rs. Don't edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum SudokuDifficulty {
+  SUDOKU_DIFFICULTY_EASY,
+  SUDOKU_DIFFICULTY_MEDIUM,
+  SUDOKU_DIFFICULTY_HARD,
+  SUDOKU_DIFFICULTY_EXPERT,
+} SudokuDifficulty;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Read a grid written in any of the usual shapes, or return null if it
+ * can't be read.
+ *
+ * # Safety
+ *
+ * `text` must be a null-terminated string, and `len` null or writable.
+ */
+uint8_t *sudoku_parse(const char *text, size_t *len);
+
+/**
+ * The first solution of a grid of `len` cells, of the same length, or null
+ * if it has none.
+ *
+ * # Safety
+ *
+ * `cells` must point to `len` readable bytes.
+ */
+uint8_t *sudoku_solve(const uint8_t *cells, size_t len);
+
+/**
+ * A new puzzle with a unique solution, 4 or 9 cells a side, the same one
+ * for the same seed. `difficulty` is one of the `SudokuDifficulty` values.
+ * Null if there are no puzzles of that size or difficulty.
+ *
+ * # Safety
+ *
+ * `len` must be null or writable.
+ */
+uint8_t *sudoku_generate(size_t size, uint32_t difficulty, uint64_t seed, size_t *len);
+
+/**
+ * Give back a grid from `sudoku_parse`, `sudoku_solve` or
+ * `sudoku_generate`. Null is ignored.
+ *
+ * # Safety
+ *
+ * `cells` must be null, or a grid from this library of `len` cells that
+ * hasn't been freed yet.
+ */
+void sudoku_free(uint8_t *cells, size_t len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SUDOKU_H */
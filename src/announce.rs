@@ -0,0 +1,195 @@
+//! Plain-text descriptions of the game for terminal screen readers, which
+//! can't make sense of the drawn grid
+
+use crate::{
+    game::{Game, GameEvent, GameStatus},
+    grid::{value_symbol, GridPosition},
+    timer::format_duration,
+};
+
+/// A cell in words, like "row 4 column 7"
+fn cell_name(position: GridPosition) -> String {
+    format!("row {} column {}", position.1 + 1, position.0 + 1)
+}
+
+fn values_text(values: impl IntoIterator<Item = usize>) -> String {
+    values
+        .into_iter()
+        .map(|value| value_symbol(value).to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The selected cell in words, like "Row 4 column 7, empty, candidates 2 5 9"
+pub fn describe_selection(game: &Game) -> String {
+    let position = game.selected;
+    let mut text = cell_name(position);
+    text[..1].make_ascii_uppercase();
+    match game.get_cell(position).unwrap_or(0) {
+        0 => text.push_str(", empty"),
+        value if game.is_given(position) => {
+            text.push_str(&format!(", given {}", value_symbol(value)))
+        }
+        value => text.push_str(&format!(", {}", value_symbol(value))),
+    }
+    if game.conflicts.contains(&position) {
+        text.push_str(", conflicting");
+    }
+    if let Some(notes) = game.notes(position).filter(|notes| !notes.is_empty()) {
+        text.push_str(&format!(
+            ", candidates {}",
+            values_text(notes.iter().copied())
+        ));
+    }
+    text
+}
+
+/// What the announcer remembers of the game before an action, to put what
+/// the action did into words
+pub struct Snapshot {
+    selected: GridPosition,
+    notes: Vec<usize>,
+    events: usize,
+    mistakes: usize,
+    paused: bool,
+    status: GameStatus,
+    notes_mode: bool,
+}
+
+impl Snapshot {
+    pub fn take(game: &Game, notes_mode: bool) -> Self {
+        Self {
+            selected: game.selected,
+            notes: selected_notes(game),
+            events: game.events_recorded(),
+            mistakes: game.mistakes,
+            paused: game.is_paused(),
+            status: game.status(),
+            notes_mode,
+        }
+    }
+
+    /// What changed since the snapshot, or `None` if nothing did beyond
+    /// moving the selection, which the selection line already describes
+    pub fn announce(&self, game: &Game, notes_mode: bool) -> Option<String> {
+        if game.status() == GameStatus::Won && self.status != GameStatus::Won {
+            return Some(format!("Solved in {}", format_duration(game.elapsed())));
+        }
+        if game.is_paused() != self.paused {
+            return Some(
+                if game.is_paused() {
+                    "Paused"
+                } else {
+                    "Resumed"
+                }
+                .to_string(),
+            );
+        }
+        if game.events_recorded() != self.events {
+            return game.events().back().map(describe_event);
+        }
+        if game.mistakes > self.mistakes {
+            return Some(format!("Rejected, {} mistakes", game.mistakes));
+        }
+        if let Some(position) = game.flashed() {
+            return Some(format!("Rejected, {} conflicts", cell_name(position)));
+        }
+        if notes_mode != self.notes_mode {
+            let mode = if notes_mode { "notes" } else { "values" };
+            return Some(format!("Entering {mode}"));
+        }
+        let notes = selected_notes(game);
+        if game.selected == self.selected && notes != self.notes {
+            return Some(match notes.is_empty() {
+                true => "Candidates cleared".to_string(),
+                false => format!("Candidates {}", values_text(notes)),
+            });
+        }
+        None
+    }
+}
+
+fn selected_notes(game: &Game) -> Vec<usize> {
+    game.notes(game.selected)
+        .map(|notes| notes.iter().copied().collect())
+        .unwrap_or_default()
+}
+
+/// A history event in words, like "Placed 5 in row 4 column 7"
+pub fn describe_event(event: &GameEvent) -> String {
+    match event {
+        GameEvent::Entry(entry) if entry.value == 0 => {
+            format!("Cleared {}", cell_name(entry.position))
+        }
+        GameEvent::Entry(entry) => format!(
+            "Placed {} in {}",
+            value_symbol(entry.value),
+            cell_name(entry.position)
+        ),
+        GameEvent::Undo(entry) => format!("Undid {}", cell_name(entry.position)),
+        GameEvent::Redo(entry) => format!("Redid {}", cell_name(entry.position)),
+        GameEvent::Partner(entry) if entry.value == 0 => {
+            format!("Other player cleared {}", cell_name(entry.position))
+        }
+        GameEvent::Partner(entry) => format!(
+            "Other player placed {} in {}",
+            value_symbol(entry.value),
+            cell_name(entry.position)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game() -> Game {
+        Game::new(vec![
+            4, 6, 7, 1, 0, 0, 8, 0, 5, // row 0
+            9, 1, 2, 8, 3, 5, 6, 0, 7, // row 1
+            0, 8, 5, 6, 4, 7, 1, 9, 2, // row 2
+            2, 9, 6, 3, 5, 1, 4, 7, 0, // row 3
+            7, 0, 8, 9, 2, 0, 3, 5, 1, // row 4
+            5, 3, 1, 4, 0, 8, 9, 2, 6, // row 5
+            0, 7, 3, 0, 6, 4, 5, 1, 0, // row 6
+            6, 2, 4, 5, 1, 9, 7, 8, 3, // row 7
+            1, 5, 9, 7, 8, 3, 0, 6, 4, // row 8
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn selection() {
+        let mut game = game();
+        assert_eq!(describe_selection(&game), "Row 1 column 1, given 4");
+        game.selected = (4, 0);
+        game.toggle_note_at_selected(2).unwrap();
+        game.toggle_note_at_selected(9).unwrap();
+        assert_eq!(
+            describe_selection(&game),
+            "Row 1 column 5, empty, candidates 2 9"
+        );
+    }
+
+    #[test]
+    fn announce_entry() {
+        let mut game = game();
+        game.selected = (4, 0);
+        let before = Snapshot::take(&game, false);
+        assert_eq!(before.announce(&game, false), None);
+        game.add_entry_at_selected(9).unwrap();
+        assert_eq!(
+            before.announce(&game, false).as_deref(),
+            Some("Placed 9 in row 1 column 5")
+        );
+        assert_eq!(
+            before.announce(&game, true).as_deref(),
+            Some("Placed 9 in row 1 column 5")
+        );
+        let before = Snapshot::take(&game, false);
+        assert_eq!(
+            before.announce(&game, true).as_deref(),
+            Some("Entering notes")
+        );
+    }
+}
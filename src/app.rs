@@ -1,50 +1,166 @@
 use crate::{
-    game::{Direction, Game},
-    solver::Solver,
+    broadcast::Broadcast,
+    config::Config,
+    coop::Coop,
+    game::Game,
+    keymap::Action,
+    net::Peer,
+    paths::Paths,
+    progress::PackProgress,
+    race::Race,
+    save::SavedGame,
+    screen::{
+        game::GameScreen, help::HelpScreen, menu::MenuScreen, splash::SplashScreen,
+        watch::WatchScreen, Context, Screen, Transition,
+    },
+    stats::Stats,
+    toast::Toast,
     tui,
 };
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{self, Event, KeyEvent, KeyEventKind};
 use ratatui::{
     layout::Rect,
     prelude::*,
-    widgets::{block::Title, Block, Borders, Paragraph},
+    widgets::{Clear, Paragraph},
 };
-
-enum Window {
-    Game { game: Game },
-    Solver { solver: Solver },
-    Menu,
-}
+use std::time::{Duration, Instant};
 
 pub struct App {
-    window: Window,
+    /// Open screens, with the one receiving input on top
+    screens: Vec<Box<dyn Screen>>,
+    context: Context,
     exit: bool,
 }
 
-const DUMMY_CELLS: [usize; 81] = [
-    4, 6, 7, 1, 0, 0, 8, 0, 5, // row 0
-    9, 1, 2, 8, 3, 5, 6, 0, 7, // row 1
-    0, 8, 5, 6, 4, 7, 1, 9, 2, // row 2
-    2, 9, 6, 3, 5, 1, 4, 7, 0, // row 3
-    7, 0, 8, 9, 2, 0, 3, 5, 1, // row 4
-    5, 3, 1, 4, 0, 8, 9, 2, 6, // row 5
-    0, 7, 3, 0, 6, 4, 5, 1, 0, // row 6
-    6, 2, 4, 5, 1, 9, 7, 8, 3, // row 7
-    1, 5, 9, 7, 8, 3, 0, 6, 4, // row 8
-];
+impl Default for App {
+    fn default() -> Self {
+        Self::new(Paths::platform())
+    }
+}
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new(paths: Paths) -> Self {
+        // anything that can't be written is reported when it's saved
+        let _ = paths.create_dirs();
+        let config_path = paths.config();
+        let mut config_error = None;
+        let (config, status) = match Config::load(&config_path) {
+            Ok(config) if config_path.exists() => (
+                config,
+                format!("Settings loaded from {}", config_path.display()),
+            ),
+            // write the defaults out on first run, so there's a file to edit
+            Ok(config) => match config.save(&config_path) {
+                Ok(()) => (
+                    config,
+                    format!("Default settings written to {}", config_path.display()),
+                ),
+                Err(_) => (config, "Using default settings".to_string()),
+            },
+            // left as it is, so a typo doesn't cost the rest of the settings
+            Err(error) => {
+                let status = format!(
+                    "Could not read {}: {error}. Using default settings",
+                    config_path.display()
+                );
+                config_error = Some(error.to_string());
+                (Config::default(), status)
+            }
+        };
+        let toast = config_error.is_some().then(|| Toast::new(status.clone()));
         Self {
-            window: Window::Menu,
+            screens: vec![
+                Box::new(MenuScreen::default()),
+                Box::new(SplashScreen::new(status)),
+            ],
+            context: Context {
+                config,
+                config_error,
+                area: Rect::default(),
+                toast,
+                streak: 0,
+                saved: SavedGame::load(paths.save()).ok().flatten(),
+                progress: PackProgress::load(paths.progress()).unwrap_or_default(),
+                stats: Stats::load(paths.stats()).unwrap_or_default(),
+                paths,
+                broadcast: None,
+            },
             exit: false,
         }
     }
 
+    /// Start straight in a game on `game`, skipping the title, once the
+    /// player agrees to play it if it doesn't have exactly one solution.
+    /// Quitting the game goes back to the menu.
+    pub fn with_game(paths: Paths, game: Game) -> Self {
+        let mut app = Self::new(paths);
+        app.screens.pop();
+        let transition = GameScreen::start_checked(game, &app.context, 0);
+        app.apply(transition);
+        app
+    }
+
+    /// Start straight in a race against another player on `game`
+    pub fn with_race(paths: Paths, game: Game, race: Race) -> Self {
+        let mut app = Self::new(paths);
+        app.screens.pop();
+        let screen = GameScreen::racing(game, race, &app.context);
+        app.screens.push(Box::new(screen));
+        app
+    }
+
+    /// Start straight in a game on `game` shared with another player
+    pub fn with_coop(paths: Paths, game: Game, coop: Coop) -> Self {
+        let mut app = Self::new(paths);
+        app.screens.pop();
+        let screen = GameScreen::cooperating(game, coop, &app.context);
+        app.screens.push(Box::new(screen));
+        app
+    }
+
+    /// Start straight in watching the game broadcast from the other end
+    /// of `peer`
+    pub fn watching(paths: Paths, peer: Peer) -> Self {
+        let mut app = Self::new(paths);
+        app.screens.pop();
+        app.screens.push(Box::new(WatchScreen::new(peer)));
+        app
+    }
+
+    /// Send every game played to spectators
+    pub fn broadcasting(mut self, broadcast: Broadcast) -> Self {
+        self.context.broadcast = Some(broadcast);
+        self
+    }
+
     pub fn run(&mut self, terminal: &mut tui::Tui) -> std::io::Result<()> {
+        let mut last_tick = Instant::now();
         while !self.exit {
             terminal.draw(|frame| self.render_frame(frame))?;
-            self.handle_events()?;
+            let tick_rate = self.top().tick_rate();
+            let timeout = tick_rate.saturating_sub(last_tick.elapsed());
+            if event::poll(timeout)? {
+                // handle everything already queued before drawing again, so
+                // a held key isn't slowed down to one event per frame
+                self.handle_event(event::read()?);
+                while !self.exit && event::poll(Duration::ZERO)? {
+                    self.handle_event(event::read()?);
+                }
+            }
+            if last_tick.elapsed() >= tick_rate {
+                let top = self.screens.last_mut().expect("the menu is never popped");
+                let transition = top.on_tick(&mut self.context);
+                self.apply(transition);
+                let reduced_motion = self.context.config.reduced_motion;
+                if !reduced_motion && self.context.toast.as_ref().is_some_and(Toast::is_expired) {
+                    self.context.toast = None;
+                }
+                last_tick = Instant::now();
+            }
+        }
+        // leaving the app leaves every screen, so an open game is saved
+        while let Some(mut screen) = self.screens.pop() {
+            screen.on_exit(&mut self.context);
         }
         Ok(())
     }
@@ -53,151 +169,111 @@ impl App {
         frame.render_widget(self, frame.size());
     }
 
-    fn handle_events(&mut self) -> std::io::Result<()> {
-        match event::read()? {
-            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+    fn top(&self) -> &dyn Screen {
+        self.screens
+            .last()
+            .expect("the menu is never popped")
+            .as_ref()
+    }
+
+    fn handle_event(&mut self, event: Event) {
+        let transition = match event {
+            // terminals that report key repeats separately still mean them
+            // as presses
+            Event::Key(key_event)
+                if matches!(key_event.kind, KeyEventKind::Press | KeyEventKind::Repeat) =>
+            {
                 self.handle_key_event(key_event)
             }
-            _ => {}
+            // the next draw resizes the buffers and lays every screen out
+            // again, falling back to a notice if the terminal is too small
+            Event::Resize(width, height) => {
+                self.context.area = Rect::new(0, 0, width, height);
+                Transition::None
+            }
+            Event::Mouse(mouse_event) => {
+                let top = self.screens.last_mut().expect("the menu is never popped");
+                top.handle_mouse(mouse_event, &mut self.context)
+            }
+            _ => Transition::None,
         };
-        Ok(())
+        self.apply(transition);
     }
 
-    fn handle_key_event(&mut self, key_event: KeyEvent) {
-        match self.window {
-            Window::Game { ref mut game } => {
-                match key_event.code {
-                    KeyCode::Char('q') => self.open_menu_window(),
-                    // move cursor
-                    KeyCode::Char('l') | KeyCode::Right => game.move_selected(Direction::Right),
-                    KeyCode::Char('h') | KeyCode::Left => game.move_selected(Direction::Left),
-                    KeyCode::Char('k') | KeyCode::Up => game.move_selected(Direction::Up),
-                    KeyCode::Char('j') | KeyCode::Down => game.move_selected(Direction::Down),
-                    // insert number
-                    KeyCode::Char('0') | KeyCode::Backspace => game.add_entry_at_selected(0),
-                    KeyCode::Char('1') => game.add_entry_at_selected(1),
-                    KeyCode::Char('2') => game.add_entry_at_selected(2),
-                    KeyCode::Char('3') => game.add_entry_at_selected(3),
-                    KeyCode::Char('4') => game.add_entry_at_selected(4),
-                    KeyCode::Char('5') => game.add_entry_at_selected(5),
-                    KeyCode::Char('6') => game.add_entry_at_selected(6),
-                    KeyCode::Char('7') => game.add_entry_at_selected(7),
-                    KeyCode::Char('8') => game.add_entry_at_selected(8),
-                    KeyCode::Char('9') => game.add_entry_at_selected(9),
-                    // other controls
-                    KeyCode::Char('u') => {
-                        let _ = game.undo_entry();
-                    }
-                    _ => {}
-                }
-            }
-            Window::Solver { ref mut solver } => match key_event.code {
-                KeyCode::Char('q') => self.open_menu_window(),
-                KeyCode::Char('n') => solver.next(),
-                _ => {}
-            },
-            Window::Menu => match key_event.code {
-                KeyCode::Char('q') => self.exit(),
-                KeyCode::Char('g') => self.open_game_window(),
-                KeyCode::Char('s') => self.open_solver_window(),
-                _ => {}
-            },
+    fn handle_key_event(&mut self, key_event: KeyEvent) -> Transition {
+        if self.context.config.reduced_motion {
+            // the last message stayed up until now
+            self.context.toast = None;
         }
-    }
-
-    fn open_solver_window(&mut self) {
-        self.window = Window::Solver {
-            solver: Solver::new(Game::new(Vec::from(DUMMY_CELLS)).unwrap()),
+        let help = self.context.config.keys.action(key_event, &[Action::Help]);
+        if help.is_some() && !self.top().is_overlay() {
+            return Transition::Push(Box::new(HelpScreen));
         }
+        let top = self.screens.last_mut().expect("the menu is never popped");
+        top.handle_key(key_event, &mut self.context)
     }
 
-    fn open_game_window(&mut self) {
-        self.window = Window::Game {
-            game: Game::new(Vec::from(DUMMY_CELLS)).unwrap(),
-        };
-    }
-
-    fn open_menu_window(&mut self) {
-        self.window = Window::Menu;
+    fn apply(&mut self, transition: Transition) {
+        match transition {
+            Transition::None => {}
+            Transition::Push(screen) => self.screens.push(screen),
+            Transition::Pop(count) => {
+                // the menu at the bottom stays open
+                let keep = self.screens.len().saturating_sub(count).max(1);
+                for mut screen in self.screens.drain(keep..) {
+                    screen.on_exit(&mut self.context);
+                }
+            }
+            Transition::Replace(screen) => {
+                if let Some(mut replaced) = self.screens.pop() {
+                    replaced.on_exit(&mut self.context);
+                }
+                self.screens.push(screen);
+            }
+            Transition::Swap(count, screen) => {
+                let keep = self.screens.len().saturating_sub(count).max(1);
+                for mut screen in self.screens.drain(keep..) {
+                    screen.on_exit(&mut self.context);
+                }
+                self.screens.push(screen);
+            }
+            Transition::Quit => self.exit = true,
+        }
     }
 
-    fn exit(&mut self) {
-        self.exit = true;
+    /// Index of the lowest screen that is drawn, with only overlays above it
+    fn first_visible(&self) -> usize {
+        self.screens
+            .iter()
+            .rposition(|screen| !screen.is_overlay())
+            .unwrap_or(0)
     }
 }
 
 impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        match &self.window {
-            Window::Game { game } => {
-                let title = Title::from(" Sudoku Game".bold());
-                let instructions = Title::from(Line::from(vec![
-                    " Move selection ".into(),
-                    "<h>/<j>/<k>/<l>".blue().bold(),
-                    " Insert number ".into(),
-                    "<1-9>".blue().bold(),
-                    " Clear cell ".into(),
-                    "<0>/<BackSpace>".blue().bold(),
-                    " Undo ".into(),
-                    "<u> ".blue().bold(),
-                    " Quit to menu ".into(),
-                    "<q> ".blue().bold(),
-                ]));
-                let block = Block::default()
-                    .title(title.alignment(Alignment::Center))
-                    .title(instructions.alignment(Alignment::Center))
-                    .title_position(ratatui::widgets::block::Position::Bottom)
-                    .borders(Borders::ALL);
-                let layout = Layout::new(
-                    layout::Direction::Vertical,
-                    [Constraint::Percentage(80), Constraint::Percentage(20)],
-                )
-                .split(area);
-                game.render(layout[0], buf);
-                block.render(layout[1], buf);
-            }
-            Window::Solver { solver } => {
-                let title = Title::from(" Sudoku Solver".bold());
-                let instructions = Title::from(Line::from(vec![
-                    " Next ".into(),
-                    "<n>".blue().bold(),
-                    " Quit to menu ".into(),
-                    "<q> ".blue().bold(),
-                ]));
-                let block = Block::default()
-                    .title(title.alignment(Alignment::Center))
-                    .title(instructions.alignment(Alignment::Center))
-                    .title_position(ratatui::widgets::block::Position::Bottom)
-                    .borders(Borders::ALL);
-                let layout = Layout::new(
-                    layout::Direction::Vertical,
-                    [Constraint::Percentage(80), Constraint::Percentage(20)],
-                )
-                .split(area);
-                solver.render(layout[0], buf);
-                block.render(layout[1], buf);
-            }
-            Window::Menu => {
-                let title = Title::from(" Sudoku Main Menu ".bold());
-                let instructions = Title::from(Line::from(vec![
-                    " Game ".into(),
-                    "<g>".blue().bold(),
-                    " Solver ".into(),
-                    "<s>".blue().bold(),
-                    " Quit ".into(),
-                    "<q> ".blue().bold(),
-                ]));
-                let block = Block::default()
-                    .title(title.alignment(Alignment::Center))
-                    .title(instructions.alignment(Alignment::Center))
-                    .title_position(ratatui::widgets::block::Position::Bottom)
-                    .borders(Borders::ALL);
-
-                Paragraph::new("TODO add different starting grids to select")
-                    .centered()
-                    .block(block)
-                    .render(area, buf);
-            }
+        self.context.area = area;
+        let visible = &self.screens[self.first_visible()..];
+        let (min_width, min_height) = visible[0].min_size();
+        if area.width < min_width || area.height < min_height {
+            Clear.render(area, buf);
+            let message = format!("Terminal too small (need {min_width}x{min_height})");
+            Paragraph::new(vec![
+                Line::from(message),
+                Line::from(format!("currently {}x{}", area.width, area.height)),
+            ])
+            .centered()
+            .render(tui::centered_rect(area, area.width, 2), buf);
+            return;
+        }
+        for screen in visible {
+            screen.render(area, buf, &self.context);
+        }
+        if let Some(toast) = &self.context.toast {
+            toast.render(area, buf);
+        }
+        if self.context.config.charset.is_ascii() {
+            tui::to_ascii(buf);
         }
     }
 }
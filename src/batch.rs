@@ -0,0 +1,147 @@
+use crate::{format::text, solver};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+/// How one puzzle of a batch went
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solved {
+    /// Line of the batch file the puzzle was on, counting from 1
+    pub line: usize,
+    pub outcome: Outcome,
+    /// Values the solver tried
+    pub steps: usize,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Solution(Vec<usize>),
+    Unsolvable,
+    /// The line isn't a puzzle, with the reason why
+    Invalid(String),
+}
+
+impl Display for Solved {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.line)?;
+        match &self.outcome {
+            Outcome::Solution(cells) => {
+                for value in cells {
+                    write!(f, "{value}")?;
+                }
+                write!(f, " ({:.2?})", self.elapsed)
+            }
+            Outcome::Unsolvable => write!(f, "no solution ({:.2?})", self.elapsed),
+            Outcome::Invalid(reason) => write!(f, "invalid puzzle: {reason}"),
+        }
+    }
+}
+
+/// Solve every puzzle in `input`, one per line, spread across all cores
+/// with the `parallel` feature. Blank lines and lines starting with `#` are
+/// skipped.
+pub fn solve_lines(input: &str) -> Vec<Solved> {
+    let lines: Vec<(usize, &str)> = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    #[cfg(feature = "parallel")]
+    let lines = lines.into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let lines = lines.into_iter();
+    lines
+        .map(|(line, puzzle)| {
+            let start = Instant::now();
+            let (outcome, steps) = match text::parse(puzzle) {
+                Ok(cells) => match solver::solve_cells_with_steps(&cells) {
+                    (Some(solution), steps) => (Outcome::Solution(solution), steps),
+                    (None, steps) => (Outcome::Unsolvable, steps),
+                },
+                Err(error) => (Outcome::Invalid(error.to_string()), 0),
+            };
+            Solved {
+                line,
+                outcome,
+                steps,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect()
+}
+
+/// Totals over a batch, leaving out lines that aren't puzzles from the
+/// timings
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub unsolvable: usize,
+    pub invalid: usize,
+    pub mean: Duration,
+    pub max: Duration,
+}
+
+impl Summary {
+    pub fn new(results: &[Solved]) -> Self {
+        let timed: Vec<Duration> = results
+            .iter()
+            .filter(|solved| !matches!(solved.outcome, Outcome::Invalid(_)))
+            .map(|solved| solved.elapsed)
+            .collect();
+        let count_of = |f: fn(&Outcome) -> bool| results.iter().filter(|s| f(&s.outcome)).count();
+        Self {
+            count: results.len(),
+            unsolvable: count_of(|outcome| *outcome == Outcome::Unsolvable),
+            invalid: count_of(|outcome| matches!(outcome, Outcome::Invalid(_))),
+            mean: match timed.len() {
+                0 => Duration::ZERO,
+                n => timed.iter().sum::<Duration>() / n as u32,
+            },
+            max: timed.into_iter().max().unwrap_or_default(),
+        }
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} puzzles, {} unsolvable, {} invalid; mean {:.2?}, max {:.2?}",
+            self.count, self.unsolvable, self.invalid, self.mean, self.max
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solves_batches() {
+        let input = "# two puzzles and a typo\n\
+            530070000600195000098000060800060003400803001700020006060000280000419005000080079\n\
+            \n\
+            550070000600195000098000060800060003400803001700020006060000280000419005000080079\n\
+            53007000x\n";
+        let results = solve_lines(input);
+        assert_eq!(
+            results.iter().map(|solved| solved.line).collect::<Vec<_>>(),
+            [2, 4, 5]
+        );
+        assert!(matches!(results[0].outcome, Outcome::Solution(_)));
+        assert_eq!(results[1].outcome, Outcome::Unsolvable);
+        assert!(matches!(results[2].outcome, Outcome::Invalid(_)));
+
+        let summary = Summary::new(&results);
+        assert_eq!(
+            (summary.count, summary.unsolvable, summary.invalid),
+            (3, 1, 1)
+        );
+        assert!(summary.max >= summary.mean);
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{
+    game::{Game, GameEvent, GameStatus},
+    net::Message,
+};
+use std::{
+    io::{self, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+/// How long sending to a spectator may take before they're dropped
+const WRITE_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// What the game hands to the thread writing to spectators
+enum Outgoing {
+    /// A new spectator, with the board to show them first
+    Join(TcpStream, Message),
+    Send(Message),
+}
+
+/// A game's events sent to anyone watching, as the same lines of JSON the
+/// network games use. Spectators get the whole board as they join and
+/// whenever the game starts over, and each entry as it's made. Writing
+/// happens on a thread of its own, so a slow spectator never holds up the
+/// game.
+pub struct Broadcast {
+    /// Spectators who connected since the last update
+    joined: Arc<Mutex<Vec<TcpStream>>>,
+    outgoing: Sender<Outgoing>,
+    spectators: Arc<AtomicUsize>,
+    /// The board as spectators last saw it
+    givens: Vec<usize>,
+    values: Vec<usize>,
+    /// Events of the game already sent
+    seen: usize,
+    solved: bool,
+}
+
+impl Broadcast {
+    /// Listen for spectators, taking them on a thread of their own
+    pub fn listen(listener: TcpListener) -> Self {
+        let joined = Arc::new(Mutex::new(vec![]));
+        let queue = Arc::clone(&joined);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // a spectator who stops reading is dropped, not waited on
+                let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+                queue
+                    .lock()
+                    .expect("no thread panics holding it")
+                    .push(stream);
+            }
+        });
+        let (outgoing, messages) = mpsc::channel();
+        let spectators = Arc::new(AtomicUsize::new(0));
+        let count = Arc::clone(&spectators);
+        thread::spawn(move || {
+            let mut streams = vec![];
+            // runs until the broadcast is dropped
+            for message in messages {
+                match message {
+                    Outgoing::Join(mut stream, board) => {
+                        if send(&mut stream, &board).is_ok() {
+                            streams.push(stream);
+                        }
+                    }
+                    Outgoing::Send(message) => {
+                        streams.retain_mut(|stream| send(stream, &message).is_ok())
+                    }
+                }
+                count.store(streams.len(), Ordering::Relaxed);
+            }
+        });
+        Self {
+            joined,
+            outgoing,
+            spectators,
+            givens: vec![],
+            values: vec![],
+            seen: 0,
+            solved: false,
+        }
+    }
+
+    /// Send what happened in the game since the last update
+    pub fn update(&mut self, game: &Game) {
+        let board = Message::Board {
+            givens: game.givens(),
+            values: game.values(),
+            millis: game.elapsed().as_millis() as u64,
+        };
+        if game.givens() != self.givens {
+            // a different game, so there's nothing to send but the board
+            self.givens = game.givens();
+            self.values = game.values();
+            self.seen = game.events_recorded();
+            self.solved = false;
+            self.send(&board);
+        }
+        let joined: Vec<TcpStream> = self
+            .joined
+            .lock()
+            .expect("no thread panics holding it")
+            .drain(..)
+            .collect();
+        for stream in joined {
+            let _ = self.outgoing.send(Outgoing::Join(stream, board.clone()));
+        }
+        let events: Vec<GameEvent> = game.events_since(self.seen).copied().collect();
+        for event in events {
+            let (position, value) = match event {
+                GameEvent::Entry(entry) | GameEvent::Redo(entry) | GameEvent::Partner(entry) => {
+                    (entry.position, entry.value)
+                }
+                GameEvent::Undo(entry) => (entry.position, entry.previous_value),
+            };
+            self.values[position.1 * game.size() + position.0] = value;
+            self.send(&Message::Entry {
+                position,
+                value,
+                by_host: !matches!(event, GameEvent::Partner(_)),
+            });
+        }
+        self.seen = game.events_recorded();
+        // restarting changes the board without any events
+        if self.values != game.values() {
+            self.values = game.values();
+            self.solved = false;
+            self.send(&board);
+        }
+        if game.status() == GameStatus::Won && !self.solved {
+            self.solved = true;
+            let millis = game.elapsed().as_millis() as u64;
+            self.send(&Message::Solved { millis });
+        }
+    }
+
+    /// Send to every spectator, letting go of those who've left
+    fn send(&self, message: &Message) {
+        let _ = self.outgoing.send(Outgoing::Send(message.clone()));
+    }
+
+    /// Spectators watching, as of the last message written to them
+    pub fn spectator_count(&self) -> usize {
+        self.spectators.load(Ordering::Relaxed)
+    }
+}
+
+fn send(stream: &mut TcpStream, message: &Message) -> io::Result<()> {
+    let mut line = serde_json::to_string(message).expect("messages serialize");
+    line.push('\n');
+    stream.write_all(line.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::Peer;
+
+    #[test]
+    fn sends_the_game_to_spectators() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let mut broadcast = Broadcast::listen(listener);
+        let givens = vec![1, 0, 0, 0, 0, 0, 1, 0, 0, 1, 0, 0, 0, 0, 0, 1];
+        let mut game = Game::new(givens.clone()).unwrap();
+        game.add_entry((1, 0), 2).unwrap();
+
+        let mut spectator = Peer::connect(address).unwrap();
+        let mut received = vec![];
+        for _ in 0..100 {
+            thread::sleep(Duration::from_millis(10));
+            broadcast.update(&game);
+            if broadcast.spectator_count() == 1 {
+                break;
+            }
+        }
+        game.add_entry((2, 0), 3).unwrap();
+        broadcast.update(&game);
+        for _ in 0..100 {
+            thread::sleep(Duration::from_millis(10));
+            received.extend(std::iter::from_fn(|| spectator.try_recv()));
+            if received.len() == 2 {
+                break;
+            }
+        }
+        let Message::Board { values, .. } = &received[0] else {
+            panic!("expected the board first, got {:?}", received[0]);
+        };
+        assert_eq!(values[..4], [1, 2, 0, 0]);
+        assert_eq!(
+            received[1],
+            Message::Entry {
+                position: (2, 0),
+                value: 3,
+                by_host: true
+            }
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use crate::format::{COLLECTION_EXTENSIONS, EXTENSIONS};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrowserEntry {
+    Parent,
+    /// A way into the puzzle pack directory from anywhere else
+    Packs(PathBuf),
+    Directory(PathBuf),
+    File(PathBuf),
+}
+
+impl BrowserEntry {
+    pub fn label(&self) -> String {
+        let name = |path: &Path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+        match self {
+            BrowserEntry::Parent => "../".to_string(),
+            BrowserEntry::Packs(_) => "[puzzle packs]".to_string(),
+            BrowserEntry::Directory(path) => format!("{}/", name(path)),
+            BrowserEntry::File(path) => name(path),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FileBrowser {
+    pub dir: PathBuf,
+    /// Directory of puzzle packs, listed at the top everywhere else
+    pub packs: Option<PathBuf>,
+    pub entries: Vec<BrowserEntry>,
+    pub selected: usize,
+    pub error: Option<String>,
+}
+
+impl FileBrowser {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let mut browser = Self {
+            dir: dir.into(),
+            packs: None,
+            entries: vec![],
+            selected: 0,
+            error: None,
+        };
+        browser.refresh();
+        browser
+    }
+
+    pub fn with_packs(mut self, packs: PathBuf) -> Self {
+        self.packs = Some(packs);
+        self.refresh();
+        self
+    }
+
+    fn refresh(&mut self) {
+        self.selected = 0;
+        self.error = None;
+        match read_entries(&self.dir) {
+            Ok(mut entries) => {
+                if let Some(packs) = self.packs.clone().filter(|packs| *packs != self.dir) {
+                    entries.insert(1, BrowserEntry::Packs(packs));
+                }
+                self.entries = entries;
+            }
+            Err(error) => {
+                self.entries = vec![BrowserEntry::Parent];
+                self.error = Some(error.to_string());
+            }
+        }
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn move_down(&mut self) {
+        self.selected = (self.selected + 1).min(self.entries.len().saturating_sub(1));
+    }
+
+    /// Open the selected entry, changing directory or returning the chosen file
+    pub fn enter(&mut self) -> Option<PathBuf> {
+        match self.entries.get(self.selected)?.clone() {
+            BrowserEntry::Parent => {
+                if let Some(parent) = self.dir.parent() {
+                    self.dir = parent.to_path_buf();
+                    self.refresh();
+                }
+                None
+            }
+            BrowserEntry::Packs(path) | BrowserEntry::Directory(path) => {
+                self.dir = path;
+                self.refresh();
+                None
+            }
+            BrowserEntry::File(path) => Some(path),
+        }
+    }
+}
+
+fn read_entries(dir: &Path) -> io::Result<Vec<BrowserEntry>> {
+    let mut directories = vec![];
+    let mut files = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            directories.push(path);
+        } else if path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                EXTENSIONS.contains(&extension) || COLLECTION_EXTENSIONS.contains(&extension)
+            })
+        {
+            files.push(path);
+        }
+    }
+    directories.sort();
+    files.sort();
+    Ok([BrowserEntry::Parent]
+        .into_iter()
+        .chain(directories.into_iter().map(BrowserEntry::Directory))
+        .chain(files.into_iter().map(BrowserEntry::File))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_directories_and_puzzle_files() {
+        let dir = std::env::temp_dir().join(format!("sudoku-browser-{}", std::process::id()));
+        fs::create_dir_all(dir.join("pack")).unwrap();
+        fs::write(dir.join("b.txt"), "").unwrap();
+        fs::write(dir.join("a.sdk"), "").unwrap();
+        fs::write(dir.join("notes.md"), "").unwrap();
+
+        let mut browser = FileBrowser::new(&dir);
+        assert_eq!(
+            browser.entries,
+            vec![
+                BrowserEntry::Parent,
+                BrowserEntry::Directory(dir.join("pack")),
+                BrowserEntry::File(dir.join("a.sdk")),
+                BrowserEntry::File(dir.join("b.txt")),
+            ]
+        );
+        browser.move_down();
+        assert_eq!(browser.enter(), None);
+        assert_eq!(browser.dir, dir.join("pack"));
+        assert_eq!(browser.entries, vec![BrowserEntry::Parent]);
+        assert_eq!(browser.enter(), None);
+        browser.move_down();
+        browser.move_down();
+        assert_eq!(browser.enter(), Some(dir.join("a.sdk")));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
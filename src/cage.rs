@@ -0,0 +1,197 @@
+use crate::grid::{position_label, GridPosition};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec,
+    vec::Vec,
+};
+use core::fmt::Display;
+use serde::{Deserialize, Serialize};
+
+/// A killer cage: its cells hold distinct values adding up to `sum`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cage {
+    pub sum: usize,
+    pub cells: Vec<GridPosition>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CageError {
+    InvalidLayoutSize,
+    /// The layout has a different number of cages than there are sums
+    SumCountMismatch,
+    /// The cells don't join up side to side
+    Disconnected,
+    /// No distinct values in the cells add up to the sum
+    ImpossibleSum,
+    /// A cell in more than one cage
+    Overlapping(GridPosition),
+    /// A cell left out of every cage
+    Uncovered(GridPosition),
+    /// The sums add up to this, rather than to every value in the grid
+    WrongTotal(usize),
+}
+
+impl Display for CageError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CageError::InvalidLayoutSize => write!(f, "the layout doesn't fit the grid"),
+            CageError::SumCountMismatch => write!(f, "there isn't one sum for each cage"),
+            CageError::Disconnected => write!(f, "the cells don't join up"),
+            CageError::ImpossibleSum => write!(f, "no digits in the cells add up to the sum"),
+            CageError::Overlapping(position) => {
+                write!(f, "{} is already in a cage", position_label(*position))
+            }
+            CageError::Uncovered(position) => {
+                write!(f, "{} isn't in a cage", position_label(*position))
+            }
+            CageError::WrongTotal(total) => write!(f, "the sums add up to {total}"),
+        }
+    }
+}
+
+impl Cage {
+    /// The top-left cell, where the sum is drawn
+    pub fn anchor(&self) -> GridPosition {
+        *self
+            .cells
+            .iter()
+            .min_by_key(|(x, y)| (y, x))
+            .expect("cages are never empty")
+    }
+
+    /// Whether the cage could be part of a grid `size` cells wide: its cells
+    /// join up side to side, and as many distinct values add up to its sum
+    pub fn check(&self, size: usize) -> Result<(), CageError> {
+        let Some(&first) = self.cells.first() else {
+            return Err(CageError::ImpossibleSum);
+        };
+        let mut reached = BTreeSet::from([first]);
+        let mut next = vec![first];
+        while let Some((x, y)) = next.pop() {
+            for &cell in &self.cells {
+                if x.abs_diff(cell.0) + y.abs_diff(cell.1) == 1 && reached.insert(cell) {
+                    next.push(cell);
+                }
+            }
+        }
+        if reached.len() != self.cells.len() {
+            return Err(CageError::Disconnected);
+        }
+        let count = self.cells.len();
+        let least = count * (count + 1) / 2;
+        let most = (size + 1 - count.min(size)..=size).sum();
+        match count <= size && (least..=most).contains(&self.sum) {
+            true => Ok(()),
+            false => Err(CageError::ImpossibleSum),
+        }
+    }
+}
+
+/// Check that `cages` cover every cell of a grid `size` cells wide exactly
+/// once, with sums that add up to every value in the grid
+pub fn check_tiling(cages: &[Cage], size: usize) -> Result<(), CageError> {
+    let mut covered = BTreeSet::new();
+    for cage in cages {
+        cage.check(size)?;
+        if let Some(&cell) = cage.cells.iter().find(|&&cell| !covered.insert(cell)) {
+            return Err(CageError::Overlapping(cell));
+        }
+    }
+    let cells = (0..size * size).map(|i| (i % size, i / size));
+    if let Some(cell) = cells.into_iter().find(|cell| !covered.contains(cell)) {
+        return Err(CageError::Uncovered(cell));
+    }
+    let total = cages.iter().map(|cage| cage.sum).sum();
+    match total == size * size * (size + 1) / 2 {
+        true => Ok(()),
+        false => Err(CageError::WrongTotal(total)),
+    }
+}
+
+/// Parse cages from a layout with one character per cell, row by row, where
+/// cells sharing a character share a cage, and the cage sums in the order
+/// each cage first appears in the layout
+pub fn parse_cages(layout: &str, sums: &[usize], size: usize) -> Result<Vec<Cage>, CageError> {
+    if layout.chars().count() != size * size {
+        return Err(CageError::InvalidLayoutSize);
+    }
+    let mut indices = BTreeMap::new();
+    let mut cells: Vec<Vec<GridPosition>> = vec![];
+    for (i, id) in layout.chars().enumerate() {
+        let index = *indices.entry(id).or_insert_with(|| {
+            cells.push(vec![]);
+            cells.len() - 1
+        });
+        cells[index].push((i % size, i / size));
+    }
+    if cells.len() != sums.len() {
+        return Err(CageError::SumCountMismatch);
+    }
+    Ok(cells
+        .into_iter()
+        .zip(sums)
+        .map(|(cells, &sum)| Cage { sum, cells })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let layout = "aabcdbcdeffgehhg";
+        let cages = parse_cages(layout, &[3, 5, 7, 4, 5, 6, 5, 5], 4).unwrap();
+        assert_eq!(cages.len(), 8);
+        assert_eq!(
+            cages[1],
+            Cage {
+                sum: 5,
+                cells: vec![(2, 0), (1, 1)]
+            }
+        );
+        assert_eq!(cages[1].anchor(), (2, 0));
+        assert_eq!(
+            parse_cages(layout, &[3, 5], 4),
+            Err(CageError::SumCountMismatch)
+        );
+        assert_eq!(
+            parse_cages("aab", &[3, 5], 4),
+            Err(CageError::InvalidLayoutSize)
+        );
+    }
+
+    #[test]
+    fn check() {
+        let cage = |sum, cells: &[GridPosition]| Cage {
+            sum,
+            cells: cells.to_vec(),
+        };
+        assert_eq!(cage(7, &[(0, 0), (0, 1), (1, 1)]).check(4), Ok(()));
+        assert_eq!(
+            cage(7, &[(0, 0), (1, 1)]).check(4),
+            Err(CageError::Disconnected)
+        );
+        assert_eq!(
+            cage(8, &[(0, 0), (1, 0)]).check(4),
+            Err(CageError::ImpossibleSum)
+        );
+        assert_eq!(
+            cage(10, &[(0, 0), (1, 0), (2, 0), (3, 0), (3, 1)]).check(4),
+            Err(CageError::ImpossibleSum)
+        );
+
+        let layout = "aabbcddecfgehfgg";
+        let mut cages = parse_cages(layout, &[3, 7, 5, 5, 5, 4, 7, 4], 4).unwrap();
+        assert_eq!(check_tiling(&cages, 4), Ok(()));
+        cages[0].sum = 4;
+        assert_eq!(check_tiling(&cages, 4), Err(CageError::WrongTotal(41)));
+        let last = cages.pop().unwrap();
+        assert_eq!(
+            check_tiling(&cages, 4),
+            Err(CageError::Uncovered(last.cells[0]))
+        );
+        cages.push(cage(3, &[(0, 0)]));
+        assert_eq!(check_tiling(&cages, 4), Err(CageError::Overlapping((0, 0))));
+    }
+}
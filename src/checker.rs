@@ -1,9 +1,14 @@
+use crate::cage::Cage;
+use crate::constraint::{Constraint, Dot};
 use crate::grid::*;
-use std::collections::HashSet;
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
 
 #[derive(Debug)]
 pub struct Checker {
-    values: HashSet<usize>,
+    values: BTreeSet<usize>,
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -12,10 +17,16 @@ pub struct CheckerResult {
     pub valid: bool,
 }
 
+impl Default for Checker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Checker {
     pub fn new() -> Self {
         Self {
-            values: HashSet::new(),
+            values: BTreeSet::new(),
         }
     }
 
@@ -29,10 +40,69 @@ impl Checker {
                 acc
             }
         });
-        return CheckerResult {
+        CheckerResult {
             complete: !self.values.contains(&0),
             valid,
+        }
+    }
+
+    pub fn find_conflicts(&mut self, subsection: &GridSubsectionValues) -> Vec<GridPosition> {
+        let cells: Vec<(GridPosition, usize)> =
+            subsection.grid_subsection.zip(*subsection).collect();
+        self.values.clear();
+        let mut duplicates = BTreeSet::new();
+        for &(_, value) in &cells {
+            if value != 0 && !self.values.insert(value) {
+                duplicates.insert(value);
+            }
+        }
+        cells
+            .into_iter()
+            .filter(|(_, value)| duplicates.contains(value))
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    /// Check the values in a cage's cells: distinct, and adding up to its sum
+    /// once filled, without going over it before then
+    pub fn check_cage(&mut self, cage: &Cage, values: &[usize]) -> CheckerResult {
+        self.values.clear();
+        let distinct = values
+            .iter()
+            .all(|&value| value == 0 || self.values.insert(value));
+        let complete = !values.contains(&0);
+        let total: usize = values.iter().sum();
+        let valid = distinct && total <= cage.sum && (!complete || total == cage.sum);
+        CheckerResult { complete, valid }
+    }
+
+    /// Check the values in a constraint's cells, in the order of
+    /// `Constraint::cells`, as far as they're filled in
+    pub fn check_constraint(&mut self, constraint: &Constraint, values: &[usize]) -> CheckerResult {
+        let complete = !values.contains(&0);
+        let valid = match constraint {
+            Constraint::Arrow { .. } => {
+                let (circle, path) = (values[0], &values[1..]);
+                // each empty cell on the path adds at least 1
+                let least: usize = path.iter().map(|&value| value.max(1)).sum();
+                circle == 0 || least <= circle && (!complete || least == circle)
+            }
+            Constraint::Kropki { dot, .. } => {
+                let (a, b) = (values[0].min(values[1]), values[0].max(values[1]));
+                match dot {
+                    _ if a == 0 => true,
+                    Dot::White => b - a == 1,
+                    Dot::Black => b == 2 * a,
+                }
+            }
+            // nothing goes under a 1, whatever goes in the other cell
+            Constraint::Inequality { .. } => match (values[0], values[1]) {
+                (_, 1) => false,
+                (0, _) | (_, 0) => true,
+                (less, greater) => less < greater,
+            },
         };
+        CheckerResult { complete, valid }
     }
 
     pub fn check_subsections(
@@ -51,6 +121,131 @@ impl Checker {
     }
 }
 
+/// The conflicts on a grid, kept up to date one changed cell at a time so
+/// that an entry only looks at the cell's own subsections, not the board
+#[derive(Debug, Default, Clone)]
+pub struct ConflictTracker {
+    /// Also track the variant's extra regions, like the diagonals of an
+    /// X-Sudoku
+    regions: Regions,
+    /// Pairs of cells holding the same value in each subsection
+    pairs: BTreeSet<(GridSubsectionType, GridPosition, GridPosition)>,
+    /// How many of the pairs each cell is part of
+    cells: BTreeMap<GridPosition, usize>,
+    /// How many of the pairs lie in each subsection
+    subsections: BTreeMap<GridSubsectionType, usize>,
+    empty: usize,
+}
+
+impl ConflictTracker {
+    /// Track the conflicts already on `grid`
+    pub fn new(grid: &Grid, regions: Regions) -> Self {
+        let mut tracker = Self {
+            regions,
+            ..Self::default()
+        };
+        let mut subsections = grid.get_all_subsection_values();
+        subsections.extend(regions.subsections(grid));
+        for subsection in subsections {
+            let subsection_type = subsection.grid_subsection.subsection_type;
+            let cells: Vec<(GridPosition, usize)> =
+                subsection.grid_subsection.zip(subsection).collect();
+            for (i, &(position, value)) in cells.iter().enumerate() {
+                for &(other, _) in cells[i + 1..].iter().filter(|cell| cell.1 == value) {
+                    if value != 0 {
+                        tracker.insert(subsection_type, position, other);
+                    }
+                }
+            }
+        }
+        tracker.empty = grid.values().iter().filter(|&&value| value == 0).count();
+        tracker
+    }
+
+    /// Take in the change of the cell at `position` from `previous` to the
+    /// value it now holds on `grid`
+    pub fn update(&mut self, grid: &Grid, position: GridPosition, previous: usize) {
+        let value = grid.get_cell(position).unwrap();
+        if value == previous {
+            return;
+        }
+        match (previous, value) {
+            (0, _) => self.empty -= 1,
+            (_, 0) => self.empty += 1,
+            _ => {}
+        }
+        for subsection in self.subsections_of(grid, position) {
+            let subsection_type = subsection.grid_subsection.subsection_type;
+            for (other, other_value) in subsection.grid_subsection.zip(subsection) {
+                if other == position || other_value == 0 {
+                    continue;
+                }
+                if other_value == previous {
+                    self.remove(subsection_type, position, other);
+                } else if other_value == value {
+                    self.insert(subsection_type, position, other);
+                }
+            }
+        }
+    }
+
+    /// Whether every cell holds a value
+    pub fn is_complete(&self) -> bool {
+        self.empty == 0
+    }
+
+    /// Whether no subsection holds a value twice
+    pub fn is_valid(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Subsections holding a value twice
+    pub fn invalid_subsections(&self) -> impl Iterator<Item = GridSubsectionType> + '_ {
+        self.subsections.keys().copied()
+    }
+
+    /// Cells holding the same value as another in one of their subsections
+    pub fn conflicts(&self) -> impl Iterator<Item = GridPosition> + '_ {
+        self.cells.keys().copied()
+    }
+
+    fn subsections_of<'a>(
+        &self,
+        grid: &'a Grid,
+        position: GridPosition,
+    ) -> Vec<GridSubsectionValues<'a>> {
+        let mut subsections = grid.get_subsections_vaules_for_cell(position).to_vec();
+        subsections.extend(self.regions.subsections_of(grid, position));
+        subsections
+    }
+
+    fn insert(&mut self, subsection: GridSubsectionType, a: GridPosition, b: GridPosition) {
+        if self.pairs.insert((subsection, a.min(b), a.max(b))) {
+            *self.subsections.entry(subsection).or_default() += 1;
+            *self.cells.entry(a).or_default() += 1;
+            *self.cells.entry(b).or_default() += 1;
+        }
+    }
+
+    fn remove(&mut self, subsection: GridSubsectionType, a: GridPosition, b: GridPosition) {
+        if self.pairs.remove(&(subsection, a.min(b), a.max(b))) {
+            decrement(&mut self.subsections, subsection);
+            decrement(&mut self.cells, a);
+            decrement(&mut self.cells, b);
+        }
+    }
+}
+
+/// Count one fewer for `key`, dropping it once none are left
+fn decrement<K: Ord>(counts: &mut BTreeMap<K, usize>, key: K) {
+    if let Some(count) = counts.get_mut(&key) {
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(&key);
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -126,6 +321,39 @@ mod test {
         );
     }
 
+    #[test]
+    fn find_conflicts() {
+        let mut checker = Checker::new();
+        let grid = Grid::new(vec![
+            7, 2, 7, 4, 9, 3, 8, 1, 5, // row 0
+            3, 9, 5, 7, 2, 8, 9, 4, 6, // row 1
+            6, 8, 9, 6, 5, 1, 2, 3, 7, // row 2
+            8, 5, 2, 1, 4, 7, 6, 9, 3, // row 3
+            6, 7, 3, 9, 8, 5, 1, 2, 4, // row 4
+            9, 4, 1, 3, 6, 2, 7, 5, 8, // row 5
+            1, 9, 4, 8, 3, 6, 5, 7, 2, // row 6
+            5, 6, 7, 2, 1, 4, 3, 8, 0, // row 7
+            2, 3, 8, 5, 7, 9, 4, 0, 0, // row 8
+        ])
+        .unwrap();
+        assert_eq!(
+            checker.find_conflicts(&grid.get_subsection_values(GridSubsectionType::Row(0))),
+            vec![(0, 0), (2, 0)]
+        );
+        assert_eq!(
+            checker.find_conflicts(&grid.get_subsection_values(GridSubsectionType::Column(0))),
+            vec![(0, 2), (0, 4)]
+        );
+        assert_eq!(
+            checker.find_conflicts(&grid.get_subsection_values(GridSubsectionType::Square(0, 0))),
+            vec![(0, 0), (2, 0), (1, 1), (2, 2)]
+        );
+        assert_eq!(
+            checker.find_conflicts(&grid.get_subsection_values(GridSubsectionType::Row(8))),
+            vec![]
+        );
+    }
+
     #[test]
     fn check_subsections_invalid() {
         let mut checker = Checker::new();
@@ -196,4 +424,115 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn check_cage() {
+        let mut checker = Checker::new();
+        let cage = Cage {
+            sum: 10,
+            cells: vec![(0, 0), (1, 0), (2, 0)],
+        };
+        let result = |valid, complete| CheckerResult { valid, complete };
+        assert_eq!(checker.check_cage(&cage, &[1, 0, 0]), result(true, false));
+        assert_eq!(checker.check_cage(&cage, &[2, 3, 5]), result(true, true));
+        assert_eq!(checker.check_cage(&cage, &[1, 2, 3]), result(false, true));
+        assert_eq!(checker.check_cage(&cage, &[4, 0, 4]), result(false, false));
+        assert_eq!(checker.check_cage(&cage, &[9, 2, 0]), result(false, false));
+    }
+
+    #[test]
+    fn check_arrow() {
+        let mut checker = Checker::new();
+        let arrow = Constraint::Arrow {
+            circle: (0, 0),
+            path: vec![(1, 0), (2, 0)],
+        };
+        let result = |valid, complete| CheckerResult { valid, complete };
+        let mut check = |values| checker.check_constraint(&arrow, values);
+        assert_eq!(check(&[0, 9, 9]), result(true, false));
+        assert_eq!(check(&[6, 4, 2]), result(true, true));
+        assert_eq!(check(&[6, 4, 1]), result(false, true));
+        assert_eq!(check(&[6, 5, 0]), result(true, false));
+        assert_eq!(check(&[6, 6, 0]), result(false, false));
+    }
+
+    #[test]
+    fn check_kropki() {
+        let mut checker = Checker::new();
+        let dot = |dot| Constraint::Kropki {
+            dot,
+            cells: [(0, 0), (1, 0)],
+        };
+        let (white, black) = (dot(Dot::White), dot(Dot::Black));
+        assert!(checker.check_constraint(&white, &[5, 0]).valid);
+        assert!(checker.check_constraint(&white, &[5, 4]).valid);
+        assert!(!checker.check_constraint(&white, &[2, 4]).valid);
+        assert!(checker.check_constraint(&black, &[2, 4]).valid);
+        assert!(checker.check_constraint(&black, &[4, 2]).valid);
+        assert!(!checker.check_constraint(&black, &[4, 3]).valid);
+    }
+
+    #[test]
+    fn check_inequality() {
+        let mut checker = Checker::new();
+        let sign = Constraint::Inequality {
+            less: (0, 0),
+            greater: (0, 1),
+        };
+        let result = |valid, complete| CheckerResult { valid, complete };
+        assert_eq!(
+            checker.check_constraint(&sign, &[9, 0]),
+            result(true, false)
+        );
+        assert_eq!(
+            checker.check_constraint(&sign, &[0, 1]),
+            result(false, false)
+        );
+        assert_eq!(checker.check_constraint(&sign, &[3, 4]), result(true, true));
+        assert_eq!(
+            checker.check_constraint(&sign, &[4, 3]),
+            result(false, true)
+        );
+    }
+
+    #[test]
+    fn conflict_tracker() {
+        let mut grid = Grid::new(vec![
+            1, 0, 0, 0, // row 0
+            0, 0, 3, 0, // row 1
+            0, 0, 0, 0, // row 2
+            0, 0, 0, 4, // row 3
+        ])
+        .unwrap();
+        let regions = Regions {
+            diagonals: true,
+            windows: false,
+        };
+        let mut tracker = ConflictTracker::new(&grid, regions);
+        assert!(tracker.is_valid() && !tracker.is_complete());
+        let changes = [((1, 1), 1), ((3, 0), 3), ((1, 1), 4), ((3, 0), 0)];
+        for (position, value) in changes {
+            let previous = grid.set_cell(position, value).unwrap();
+            tracker.update(&grid, position, previous);
+            let fresh = ConflictTracker::new(&grid, regions);
+            assert_eq!(
+                tracker.conflicts().collect::<Vec<_>>(),
+                fresh.conflicts().collect::<Vec<_>>()
+            );
+            assert_eq!(
+                tracker.invalid_subsections().collect::<Vec<_>>(),
+                fresh.invalid_subsections().collect::<Vec<_>>()
+            );
+            assert_eq!(tracker.is_complete(), fresh.is_complete());
+        }
+        // 4 twice on the main diagonal, in different boxes
+        assert_eq!(
+            tracker.invalid_subsections().collect::<Vec<_>>(),
+            vec![GridSubsectionType::Diagonal]
+        );
+        assert_eq!(
+            tracker.conflicts().collect::<Vec<_>>(),
+            vec![(1, 1), (3, 3)]
+        );
+    }
 }
@@ -0,0 +1,33 @@
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ClipboardError {
+    /// Built without the `clipboard` feature
+    Unsupported,
+    /// The system clipboard couldn't be reached or held no text
+    Unavailable(String),
+}
+
+impl Display for ClipboardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClipboardError::Unsupported => write!(f, "built without clipboard support"),
+            ClipboardError::Unavailable(reason) => write!(f, "clipboard unavailable: {reason}"),
+        }
+    }
+}
+
+/// The text on the system clipboard
+#[cfg(feature = "clipboard")]
+pub fn read_text() -> Result<String, ClipboardError> {
+    let unavailable = |error: arboard::Error| ClipboardError::Unavailable(error.to_string());
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(unavailable)
+}
+
+/// The text on the system clipboard
+#[cfg(not(feature = "clipboard"))]
+pub fn read_text() -> Result<String, ClipboardError> {
+    Err(ClipboardError::Unsupported)
+}
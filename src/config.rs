@@ -0,0 +1,342 @@
+use crate::{
+    difficulty::Difficulty,
+    keymap::Keymap,
+    theme::{Appearance, ColorSupport, Theme},
+};
+use serde::{Deserialize, Serialize};
+use std::{env, fmt::Display, fs, io, path::Path, time::Duration};
+
+pub const CONFIG_FILE: &str = "config.toml";
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub theme: Theme,
+    pub colorblind_mode: bool,
+    /// Ring the terminal bell when input is rejected
+    pub bell: bool,
+    /// Moving the selection past an edge of the grid wraps to the other side
+    pub wrap_cursor: bool,
+    /// Digits pick the number to place, and selecting a cell places it
+    pub number_first: bool,
+    pub charset: Charset,
+    /// Difficulty picked at first on the new game screen
+    pub default_difficulty: Difficulty,
+    /// Start games with every candidate pencilled in, and rub a value out of
+    /// the marks around it when it's placed
+    pub auto_notes: bool,
+    /// Reject values already in the cell's row, column or box
+    pub strict_entry: bool,
+    pub assist: Assist,
+    pub mistake_limit: MistakeLimit,
+    pub autosave: Autosave,
+    pub idle_pause: IdlePause,
+    /// Describe the selected cell and announce every action in plain text
+    /// under the grid, for terminal screen readers
+    pub screen_reader: bool,
+    /// Draw no animations: rejected cells and messages stay up until the
+    /// next key, and the solver runs straight to the end
+    pub reduced_motion: bool,
+    /// Where the online pack is fetched from: a URL answering with puzzles
+    /// as JSON, or as text with one puzzle per line
+    pub online_source: Option<String>,
+    /// Key bindings, written out only where they differ from the defaults
+    #[serde(skip_serializing_if = "Keymap::is_default")]
+    pub keys: Keymap,
+}
+
+/// Which characters the interface is drawn with
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Charset {
+    /// ASCII unless the locale says the terminal speaks UTF-8
+    #[default]
+    Auto,
+    Unicode,
+    Ascii,
+}
+
+impl Charset {
+    pub const ALL: [Charset; 3] = [Charset::Auto, Charset::Unicode, Charset::Ascii];
+
+    pub fn is_ascii(self) -> bool {
+        match self {
+            Charset::Auto => !locale_is_utf8(),
+            Charset::Unicode => false,
+            Charset::Ascii => true,
+        }
+    }
+}
+
+impl Display for Charset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Charset::Auto => "auto",
+            Charset::Unicode => "unicode",
+            Charset::Ascii => "ascii",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// How much the game points out which digits are ruled out, short of hints
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Assist {
+    #[default]
+    Off,
+    /// Dim the digits and pencil marks the selected cell can't take
+    Digits,
+    /// As well, grey out the cells the armed digit can't go in
+    Cells,
+}
+
+impl Assist {
+    pub const ALL: [Assist; 3] = [Assist::Off, Assist::Digits, Assist::Cells];
+}
+
+impl Display for Assist {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Assist::Off => "off",
+            Assist::Digits => "digits",
+            Assist::Cells => "digits and cells",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Mistakes that end a game, or 0 for no limit
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MistakeLimit(pub usize);
+
+impl MistakeLimit {
+    /// The choices the settings screen cycles through
+    pub const CHOICES: [MistakeLimit; 4] = [
+        MistakeLimit(0),
+        MistakeLimit(3),
+        MistakeLimit(5),
+        MistakeLimit(10),
+    ];
+
+    pub fn limit(self) -> Option<usize> {
+        (self.0 > 0).then_some(self.0)
+    }
+}
+
+impl Display for MistakeLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "off"),
+            limit => write!(f, "{limit}"),
+        }
+    }
+}
+
+/// When unfinished games are saved to be resumed later
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Autosave {
+    /// Whenever the game is left, including on quitting
+    #[default]
+    OnExit,
+    Off,
+}
+
+impl Display for Autosave {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Autosave::OnExit => "on exit",
+            Autosave::Off => "off",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Seconds without input before the game clock stops, so only time spent
+/// solving counts, or 0 to never stop it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct IdlePause(pub u64);
+
+impl IdlePause {
+    /// The choices the settings screen cycles through
+    pub const CHOICES: [IdlePause; 5] = [
+        IdlePause(0),
+        IdlePause(30),
+        IdlePause(60),
+        IdlePause(120),
+        IdlePause(300),
+    ];
+
+    pub fn duration(self) -> Option<Duration> {
+        (self.0 > 0).then(|| Duration::from_secs(self.0))
+    }
+}
+
+impl Default for IdlePause {
+    fn default() -> Self {
+        Self(120)
+    }
+}
+
+impl Display for IdlePause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "off"),
+            seconds if seconds % 60 == 0 => write!(f, "after {} min", seconds / 60),
+            seconds => write!(f, "after {seconds} s"),
+        }
+    }
+}
+
+/// Whether the first locale variable that is set names UTF-8, assuming it
+/// does when none are, as on most modern terminals
+fn locale_is_utf8() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .is_none_or(|value| {
+            let value = value.to_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        })
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(error) => write!(f, "{error}"),
+            ConfigError::Parse(error) => write!(f, "{}", error.message().trim_end()),
+            ConfigError::Serialize(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Config {
+    /// How the interface is drawn, with the theme swapped for one the
+    /// terminal can show if it reports fewer colors
+    pub fn appearance(&self) -> Appearance {
+        let theme = self.theme.for_terminal(ColorSupport::detect());
+        Appearance {
+            theme,
+            // without colors, cells can only be told apart by attributes
+            colorblind: self.colorblind_mode || theme == Theme::MONOCHROME,
+            ascii: self.charset.is_ascii(),
+            reduced_motion: self.reduced_motion,
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Self::parse(&contents),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, ConfigError> {
+        toml::from_str(contents).map_err(ConfigError::Parse)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ConfigError> {
+        let contents = toml::to_string_pretty(self).map_err(ConfigError::Serialize)?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_theme() {
+        let config = Config::parse(
+            r#"
+            [theme]
+            given = "yellow"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.theme.given, ratatui::style::Color::Yellow);
+        assert_eq!(config.theme.entry, Theme::DARK.entry);
+    }
+
+    #[test]
+    fn parse_charset() {
+        let config = Config::parse(r#"charset = "ascii""#).unwrap();
+        assert!(config.charset.is_ascii());
+        assert_eq!(Config::parse("").unwrap().charset, Charset::Auto);
+        assert!(!Charset::Unicode.is_ascii());
+    }
+
+    #[test]
+    fn parse_gameplay() {
+        let config = Config::parse(
+            r#"
+            default_difficulty = "expert"
+            strict_entry = true
+            assist = "cells"
+            mistake_limit = 3
+            autosave = "off"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.default_difficulty, Difficulty::Expert);
+        assert!(config.strict_entry && !config.auto_notes);
+        assert_eq!(config.assist, Assist::Cells);
+        assert_eq!(config.mistake_limit.limit(), Some(3));
+        assert_eq!(Config::default().mistake_limit.limit(), None);
+        assert_eq!(config.autosave, Autosave::Off);
+        assert_eq!(Config::default().autosave, Autosave::OnExit);
+        assert_eq!(Config::default().assist, Assist::Off);
+    }
+
+    #[test]
+    fn describe_parse_errors() {
+        let error = Config::parse("bell = maybe").unwrap_err();
+        assert!(matches!(error, ConfigError::Parse(_)));
+        assert!(!error.to_string().is_empty());
+        assert!(!error.to_string().contains('\n'));
+    }
+
+    #[test]
+    fn round_trip() {
+        let config = Config {
+            theme: Theme::SOLARIZED,
+            colorblind_mode: true,
+            bell: true,
+            wrap_cursor: true,
+            number_first: true,
+            charset: Charset::Ascii,
+            default_difficulty: Difficulty::Hard,
+            auto_notes: true,
+            strict_entry: true,
+            assist: Assist::Digits,
+            mistake_limit: MistakeLimit(5),
+            autosave: Autosave::Off,
+            idle_pause: IdlePause(30),
+            screen_reader: true,
+            reduced_motion: true,
+            online_source: Some("https://example.com/puzzles".to_string()),
+            keys: toml::from_str(r#"undo = ["Ctrl-z"]"#).unwrap(),
+        };
+        let contents = toml::to_string_pretty(&config).unwrap();
+        assert_eq!(Config::parse(&contents).unwrap(), config);
+    }
+}
@@ -0,0 +1,186 @@
+//! Rules between particular cells on top of the rows, columns and boxes,
+//! like the arrows of arrow sudoku, the dots of kropki sudoku or the
+//! inequality signs of greater-than sudoku
+
+use crate::grid::{parse_position, GridPosition};
+use alloc::{vec, vec::Vec};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Constraint {
+    /// The digits along the path add up to the digit in the circle the
+    /// arrow starts from
+    Arrow {
+        circle: GridPosition,
+        path: Vec<GridPosition>,
+    },
+    /// A dot on the border between two cells side by side
+    Kropki { dot: Dot, cells: [GridPosition; 2] },
+    /// A sign on the border between two cells side by side, pointing at
+    /// the one with the smaller digit
+    Inequality {
+        less: GridPosition,
+        greater: GridPosition,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Dot {
+    /// The digits are one apart
+    White,
+    /// One digit is twice the other
+    Black,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ConstraintError {
+    UnknownKind,
+    InvalidPosition,
+    /// Too few cells, or cells that don't follow on from each other
+    InvalidCells,
+}
+
+impl Constraint {
+    /// Every cell the constraint involves, the circle first for an arrow
+    pub fn cells(&self) -> Vec<GridPosition> {
+        match self {
+            Constraint::Arrow { circle, path } => {
+                let mut cells = vec![*circle];
+                cells.extend(path);
+                cells
+            }
+            Constraint::Kropki { cells, .. } => cells.to_vec(),
+            Constraint::Inequality { less, greater } => vec![*less, *greater],
+        }
+    }
+
+    /// The same constraint on the cells `f` moves its cells to, as when the
+    /// grid is turned or flipped
+    pub fn map_cells(&self, f: impl Fn(GridPosition) -> GridPosition) -> Self {
+        match self {
+            Constraint::Arrow { circle, path } => Constraint::Arrow {
+                circle: f(*circle),
+                path: path.iter().map(|&cell| f(cell)).collect(),
+            },
+            Constraint::Kropki { dot, cells } => Constraint::Kropki {
+                dot: *dot,
+                cells: cells.map(f),
+            },
+            Constraint::Inequality { less, greater } => Constraint::Inequality {
+                less: f(*less),
+                greater: f(*greater),
+            },
+        }
+    }
+
+    /// Parse a constraint written as its kind and then its cells, like
+    /// `arrow r1c1 r1c2 r2c3` for an arrow from a circle in r1c1, or
+    /// `white r4c4 r4c5` and `black r4c4 r5c4` for kropki dots, or
+    /// `less r1c1 r1c2` for a smaller digit in r1c1 than in r1c2
+    pub fn parse(text: &str, size: usize) -> Result<Self, ConstraintError> {
+        let mut words = text.split_whitespace();
+        let kind = words.next().ok_or(ConstraintError::UnknownKind)?;
+        let cells = words
+            .map(|word| parse_position(word, size).ok_or(ConstraintError::InvalidPosition))
+            .collect::<Result<Vec<_>, _>>()?;
+        match kind {
+            "arrow" => {
+                let [circle, ..] = cells[..] else {
+                    return Err(ConstraintError::InvalidCells);
+                };
+                let follows = cells.windows(2).all(|pair| touching(pair[0], pair[1]));
+                if cells.len() < 2 || !follows {
+                    return Err(ConstraintError::InvalidCells);
+                }
+                Ok(Constraint::Arrow {
+                    circle,
+                    path: cells[1..].to_vec(),
+                })
+            }
+            "white" | "black" | "less" | "greater" => {
+                let [a, b] = cells[..] else {
+                    return Err(ConstraintError::InvalidCells);
+                };
+                if a.0.abs_diff(b.0) + a.1.abs_diff(b.1) != 1 {
+                    return Err(ConstraintError::InvalidCells);
+                }
+                Ok(match kind {
+                    "white" => Constraint::Kropki {
+                        dot: Dot::White,
+                        cells: [a, b],
+                    },
+                    "black" => Constraint::Kropki {
+                        dot: Dot::Black,
+                        cells: [a, b],
+                    },
+                    "less" => Constraint::Inequality {
+                        less: a,
+                        greater: b,
+                    },
+                    _ => Constraint::Inequality {
+                        less: b,
+                        greater: a,
+                    },
+                })
+            }
+            _ => Err(ConstraintError::UnknownKind),
+        }
+    }
+}
+
+/// Whether two different cells meet at a side or a corner
+fn touching(a: GridPosition, b: GridPosition) -> bool {
+    a != b && a.0.abs_diff(b.0) <= 1 && a.1.abs_diff(b.1) <= 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Constraint::parse("arrow r1c1 r1c2 r2c3", 9),
+            Ok(Constraint::Arrow {
+                circle: (0, 0),
+                path: vec![(1, 0), (2, 1)],
+            })
+        );
+        assert_eq!(
+            Constraint::parse("arrow r1c1 r1c3", 9),
+            Err(ConstraintError::InvalidCells)
+        );
+        assert_eq!(
+            Constraint::parse("arrow r1c1", 9),
+            Err(ConstraintError::InvalidCells)
+        );
+        assert_eq!(
+            Constraint::parse("arrow r1c1 r0c2", 9),
+            Err(ConstraintError::InvalidPosition)
+        );
+        assert_eq!(
+            Constraint::parse("black r2c1 r1c1", 9),
+            Ok(Constraint::Kropki {
+                dot: Dot::Black,
+                cells: [(0, 1), (0, 0)],
+            })
+        );
+        assert_eq!(
+            Constraint::parse("white r1c1 r2c2", 9),
+            Err(ConstraintError::InvalidCells)
+        );
+        assert_eq!(
+            Constraint::parse("greater r1c1 r1c2", 9),
+            Ok(Constraint::Inequality {
+                less: (1, 0),
+                greater: (0, 0),
+            })
+        );
+        assert_eq!(
+            Constraint::parse("thermo r1c1 r1c2", 9),
+            Err(ConstraintError::UnknownKind)
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{
+    game::{Game, GameEvent},
+    grid::GridPosition,
+    net::{Message, Peer},
+};
+use std::collections::HashMap;
+
+/// Two players filling in the same board, each entry shown in the color of
+/// whoever made it.
+///
+/// When both change a cell at once, the order the host takes the entries in
+/// is final: the host passes every entry from the guest back, and the guest
+/// holds off on a cell until the last of its own entries there has come
+/// back, by which time it has the value the host settled on.
+pub struct Coop {
+    peer: Peer,
+    host: bool,
+    /// The guest's entries on their way through the host, counted by cell
+    pending: HashMap<GridPosition, usize>,
+    /// Events of the game already sent, or taken from the other player
+    seen: usize,
+}
+
+impl Coop {
+    pub fn new(peer: Peer, host: bool) -> Self {
+        Self {
+            peer,
+            host,
+            pending: HashMap::new(),
+            seen: 0,
+        }
+    }
+
+    /// Send the player's new entries and take the other player's
+    pub fn update(&mut self, game: &mut Game) {
+        let entries: Vec<(GridPosition, usize)> = game
+            .events_since(self.seen)
+            .filter_map(|event| match *event {
+                GameEvent::Entry(entry) | GameEvent::Redo(entry) => {
+                    Some((entry.position, entry.value))
+                }
+                GameEvent::Undo(entry) => Some((entry.position, entry.previous_value)),
+                GameEvent::Partner(_) => None,
+            })
+            .collect();
+        for (position, value) in entries {
+            self.peer.send(&Message::Entry {
+                position,
+                value,
+                by_host: self.host,
+            });
+            if !self.host {
+                *self.pending.entry(position).or_default() += 1;
+            }
+        }
+        while let Some(message) = self.peer.try_recv() {
+            let Message::Entry {
+                position,
+                value,
+                by_host,
+            } = message
+            else {
+                continue;
+            };
+            if self.host {
+                self.peer.send(&message);
+                // an entry on a given is from a different puzzle, and dropped
+                let _ = game.add_partner_entry(position, value);
+            } else if !by_host {
+                // one of the player's own, back from the host
+                if let Some(count) = self.pending.get_mut(&position) {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.pending.remove(&position);
+                    }
+                }
+            } else if !self.pending.contains_key(&position) {
+                let _ = game.add_partner_entry(position, value);
+            }
+        }
+        self.seen = game.events_recorded();
+    }
+
+    pub fn partner_left(&self) -> bool {
+        self.peer.is_closed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{net::TcpListener, thread, time::Duration};
+
+    fn pair() -> (Coop, Coop) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let guest = thread::spawn(move || Peer::connect(address).unwrap());
+        let host = Peer::accept(&listener).unwrap();
+        (
+            Coop::new(host, true),
+            Coop::new(guest.join().unwrap(), false),
+        )
+    }
+
+    /// Trade messages until both boards settle
+    fn settle(host: &mut Coop, host_game: &mut Game, guest: &mut Coop, guest_game: &mut Game) {
+        for _ in 0..20 {
+            thread::sleep(Duration::from_millis(10));
+            host.update(host_game);
+            guest.update(guest_game);
+        }
+    }
+
+    #[test]
+    fn shares_the_board() {
+        let givens = vec![1, 0, 0, 0, 0, 0, 1, 0, 0, 1, 0, 0, 0, 0, 0, 1];
+        let (mut host, mut guest) = pair();
+        let mut host_game = Game::new(givens.clone()).unwrap();
+        let mut guest_game = Game::new(givens).unwrap();
+
+        host_game.add_entry((1, 0), 2).unwrap();
+        guest_game.add_entry((2, 0), 3).unwrap();
+        settle(&mut host, &mut host_game, &mut guest, &mut guest_game);
+        assert_eq!(host_game.values(), guest_game.values());
+        assert!(host_game.is_partner_entry((2, 0)));
+        assert!(!host_game.is_partner_entry((1, 0)));
+        assert!(guest_game.is_partner_entry((1, 0)));
+
+        // both change the same cell before hearing of the other's change
+        host_game.add_entry((3, 0), 4).unwrap();
+        guest_game.add_entry((3, 0), 2).unwrap();
+        settle(&mut host, &mut host_game, &mut guest, &mut guest_game);
+        assert_eq!(host_game.values(), guest_game.values());
+
+        guest_game.undo_entry();
+        settle(&mut host, &mut host_game, &mut guest, &mut guest_game);
+        assert_eq!(host_game.values(), guest_game.values());
+    }
+}
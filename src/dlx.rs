@@ -0,0 +1,204 @@
+//! Solving by dancing links: sudoku as an exact cover problem, where every
+//! cell, and every value in each row, column and box, is covered by exactly
+//! one placement
+
+use crate::grid::Grid;
+use alloc::{vec, vec::Vec};
+
+/// Circular doubly linked lists over a sparse matrix of placements against
+/// constraints. Node 0 is the root, followed by one header per constraint.
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// The constraint header each node sits under
+    header: Vec<usize>,
+    /// Nodes left under each header
+    size: Vec<usize>,
+    /// The cell index and value each node places
+    placement: Vec<(usize, usize)>,
+}
+
+impl Links {
+    fn new(constraints: usize) -> Self {
+        let count = constraints + 1;
+        let mut links = Self {
+            left: (0..count).map(|i| (i + count - 1) % count).collect(),
+            right: (0..count).map(|i| (i + 1) % count).collect(),
+            up: (0..count).collect(),
+            down: (0..count).collect(),
+            header: (0..count).collect(),
+            size: vec![0; count],
+            placement: vec![(0, 0); count],
+        };
+        // the root isn't a constraint to cover
+        links.size[0] = usize::MAX;
+        links
+    }
+
+    /// Add the placement of `value` in cell `cell`, meeting `constraints`
+    fn add_row(&mut self, cell: usize, value: usize, constraints: [usize; 4]) {
+        let first = self.left.len();
+        for (i, &constraint) in constraints.iter().enumerate() {
+            let header = constraint + 1;
+            let node = first + i;
+            self.left.push(first + (i + 3) % 4);
+            self.right.push(first + (i + 1) % 4);
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            let last = self.up[header];
+            self.down[last] = node;
+            self.up[header] = node;
+            self.header.push(header);
+            self.size[header] += 1;
+            self.placement.push((cell, value));
+        }
+    }
+
+    fn cover(&mut self, header: usize) {
+        self.left[self.right[header]] = self.left[header];
+        self.right[self.left[header]] = self.right[header];
+        let mut row = self.down[header];
+        while row != header {
+            let mut node = self.right[row];
+            while node != row {
+                self.up[self.down[node]] = self.up[node];
+                self.down[self.up[node]] = self.down[node];
+                self.size[self.header[node]] -= 1;
+                node = self.right[node];
+            }
+            row = self.down[row];
+        }
+    }
+
+    fn uncover(&mut self, header: usize) {
+        let mut row = self.up[header];
+        while row != header {
+            let mut node = self.left[row];
+            while node != row {
+                self.size[self.header[node]] += 1;
+                self.up[self.down[node]] = node;
+                self.down[self.up[node]] = node;
+                node = self.left[node];
+            }
+            row = self.up[row];
+        }
+        self.left[self.right[header]] = header;
+        self.right[self.left[header]] = header;
+    }
+
+    /// The uncovered constraint with the fewest placements left, or `None`
+    /// once every constraint is covered
+    fn fewest(&self) -> Option<usize> {
+        let mut best = None;
+        let mut header = self.right[0];
+        while header != 0 {
+            if best.is_none_or(|best| self.size[header] < self.size[best]) {
+                best = Some(header);
+            }
+            header = self.right[header];
+        }
+        best
+    }
+
+    /// Pick placements until every constraint is covered, leaving the nodes
+    /// picked in `solution`
+    fn search(&mut self, solution: &mut Vec<usize>) -> bool {
+        let Some(header) = self.fewest() else {
+            return true;
+        };
+        self.cover(header);
+        let mut row = self.down[header];
+        while row != header {
+            solution.push(row);
+            let mut node = self.right[row];
+            while node != row {
+                self.cover(self.header[node]);
+                node = self.right[node];
+            }
+            if self.search(solution) {
+                return true;
+            }
+            let mut node = self.left[row];
+            while node != row {
+                self.uncover(self.header[node]);
+                node = self.left[node];
+            }
+            solution.pop();
+            row = self.down[row];
+        }
+        self.uncover(header);
+        false
+    }
+}
+
+/// Solve a flat list of cells (0 for empty) with dancing links
+pub fn solve_cells(cells: &[usize]) -> Option<Vec<usize>> {
+    let grid = Grid::new(cells.to_vec()).ok()?;
+    let (size, box_size) = (grid.size(), grid.sub_square_size());
+    let area = size * size;
+    let mut links = Links::new(4 * area);
+    for (cell, &given) in cells.iter().enumerate() {
+        let (x, y) = (cell % size, cell / size);
+        let square = (y / box_size) * box_size + x / box_size;
+        let values = match given {
+            0 => 1..=size,
+            value => value..=value,
+        };
+        for value in values {
+            let v = value - 1;
+            links.add_row(
+                cell,
+                value,
+                [
+                    cell,
+                    area + y * size + v,
+                    2 * area + x * size + v,
+                    3 * area + square * size + v,
+                ],
+            );
+        }
+    }
+    let mut solution = Vec::with_capacity(area);
+    if !links.search(&mut solution) {
+        return None;
+    }
+    let mut solved = cells.to_vec();
+    for node in solution {
+        let (cell, value) = links.placement[node];
+        solved[cell] = value;
+    }
+    Some(solved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver;
+
+    #[test]
+    fn solves_like_backtracking() {
+        let cells = [
+            5, 3, 0, 0, 7, 0, 0, 0, 0, // row 0
+            6, 0, 0, 1, 9, 5, 0, 0, 0, // row 1
+            0, 9, 8, 0, 0, 0, 0, 6, 0, // row 2
+            8, 0, 0, 0, 6, 0, 0, 0, 3, // row 3
+            4, 0, 0, 8, 0, 3, 0, 0, 1, // row 4
+            7, 0, 0, 0, 2, 0, 0, 0, 6, // row 5
+            0, 6, 0, 0, 0, 0, 2, 8, 0, // row 6
+            0, 0, 0, 4, 1, 9, 0, 0, 5, // row 7
+            0, 0, 0, 0, 8, 0, 0, 7, 9, // row 8
+        ];
+        assert_eq!(solve_cells(&cells), solver::solve_cells(&cells));
+        assert!(solve_cells(&cells).is_some());
+    }
+
+    #[test]
+    fn clashing_givens() {
+        let mut cells = vec![0; 16];
+        cells[0] = 1;
+        cells[3] = 1;
+        assert_eq!(solve_cells(&cells), None);
+    }
+}
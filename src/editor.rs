@@ -0,0 +1,316 @@
+//! Puzzles entered by hand: givens typed into a blank grid and killer cages
+//! drawn over it, with duplicates shown as they're made and the puzzle
+//! checked for a unique solution when asked
+
+use crate::{
+    cage::{check_tiling, Cage, CageError},
+    format::sdk::SdkGame,
+    game::Game,
+    grid::{GridError, GridPosition, Regions},
+    logic::{self, Rating},
+    solver::{count_solutions_in, solve_cells_with_order},
+};
+use std::fmt::Display;
+
+/// What a check of the puzzle found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// Two givens share a row, column or box
+    Conflicting,
+    NoSolution,
+    MultipleSolutions,
+    /// Exactly one solution, graded by how a person would solve it
+    Unique(Rating),
+    /// Killer cages that don't tile the grid
+    InvalidCages(CageError),
+    /// Killer cages that tile the grid, around givens that leave more than
+    /// one solution without the cage sums, which the solver doesn't use
+    Tiled,
+}
+
+impl Verdict {
+    pub fn check(givens: &[usize]) -> Self {
+        Self::check_in(givens, Regions::default())
+    }
+
+    /// Check a puzzle whose variant adds `regions` to the rows, columns and
+    /// boxes
+    pub fn check_in(givens: &[usize], regions: Regions) -> Self {
+        let Ok(game) = Game::new(givens.to_vec()) else {
+            return Verdict::Conflicting;
+        };
+        if !game.with_regions(regions).is_valid() {
+            return Verdict::Conflicting;
+        }
+        match count_solutions_in(givens, regions, 2) {
+            0 => Verdict::NoSolution,
+            1 => logic::rate_in(givens, regions).map_or(Verdict::NoSolution, Verdict::Unique),
+            _ => Verdict::MultipleSolutions,
+        }
+    }
+
+    /// Check a killer puzzle: its cages must tile the grid, and its givens
+    /// are checked as above, then against the cage sums
+    pub fn check_killer(givens: &[usize], regions: Regions, cages: &[Cage]) -> Self {
+        let size = givens.len().isqrt();
+        if let Err(error) = check_tiling(cages, size) {
+            return Verdict::InvalidCages(error);
+        }
+        let fits_cages = |cells: &[usize]| {
+            Game::new(cells.to_vec()).is_ok_and(|game| game.with_cages(cages.to_vec()).is_valid())
+        };
+        match Self::check_in(givens, regions) {
+            Verdict::Conflicting => Verdict::Conflicting,
+            _ if !fits_cages(givens) => Verdict::Conflicting,
+            Verdict::MultipleSolutions => Verdict::Tiled,
+            Verdict::Unique(rating) => {
+                let order: Vec<usize> = (1..=size).collect();
+                match solve_cells_with_order(givens, regions, &order) {
+                    Some(solution) if fits_cages(&solution) => Verdict::Unique(rating),
+                    _ => Verdict::NoSolution,
+                }
+            }
+            verdict => verdict,
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Conflicting => write!(f, "Some givens clash"),
+            Verdict::NoSolution => write!(f, "0 solutions"),
+            Verdict::MultipleSolutions => write!(f, "Multiple solutions"),
+            Verdict::Unique(rating) => write!(f, "Unique solution, rated {}", rating.difficulty),
+            Verdict::InvalidCages(error) => write!(f, "Cages: {error}"),
+            Verdict::Tiled => write!(f, "Cages tile the grid, solutions not counted"),
+        }
+    }
+}
+
+pub struct Editor {
+    /// The givens so far, entered as the game's values so they can be
+    /// changed and undone
+    pub game: Game,
+    /// The last check, until the givens or cages change
+    verdict: Option<Verdict>,
+    /// Cells picked for the next cage
+    draft: Vec<GridPosition>,
+}
+
+impl Editor {
+    /// A blank grid `size` cells wide
+    pub fn new(size: usize) -> Result<Self, GridError> {
+        Ok(Self {
+            game: Game::new(vec![0; size * size])?,
+            verdict: None,
+            draft: vec![],
+        })
+    }
+
+    pub fn givens(&self) -> Vec<usize> {
+        self.game.values()
+    }
+
+    pub fn given_count(&self) -> usize {
+        self.givens().iter().filter(|&&value| value != 0).count()
+    }
+
+    /// Set the selected cell, 0 to clear it
+    pub fn set(&mut self, value: usize) -> Result<(), GridError> {
+        let position = self.game.selected;
+        if self.game.get_cell(position)? != value {
+            self.game.add_entry(position, value)?;
+            self.verdict = None;
+        }
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> bool {
+        self.verdict = None;
+        self.game.undo_entry().is_some()
+    }
+
+    pub fn redo(&mut self) -> bool {
+        self.verdict = None;
+        self.game.redo_entry().is_some()
+    }
+
+    pub fn check(&mut self) -> &Verdict {
+        self.verdict.get_or_insert_with(|| match self.game.cages() {
+            [] => Verdict::check(&self.game.values()),
+            cages => Verdict::check_killer(&self.game.values(), Regions::default(), cages),
+        })
+    }
+
+    pub fn verdict(&self) -> Option<&Verdict> {
+        self.verdict.as_ref()
+    }
+
+    pub fn draft(&self) -> &[GridPosition] {
+        &self.draft
+    }
+
+    /// Pick the selected cell for the next cage, or put it back
+    pub fn toggle_draft(&mut self) -> Result<(), CageError> {
+        let position = self.game.selected;
+        if self.cage_index(position).is_some() {
+            return Err(CageError::Overlapping(position));
+        }
+        match self.draft.iter().position(|&cell| cell == position) {
+            Some(i) => {
+                self.draft.remove(i);
+            }
+            None => self.draft.push(position),
+        }
+        Ok(())
+    }
+
+    /// Make a cage of the picked cells, adding up to `sum`
+    pub fn add_cage(&mut self, sum: usize) -> Result<(), CageError> {
+        let cage = Cage {
+            sum,
+            cells: self.draft.clone(),
+        };
+        cage.check(self.game.size())?;
+        let mut cages = self.game.cages().to_vec();
+        cages.push(cage);
+        self.game.set_cages(cages);
+        self.draft.clear();
+        self.verdict = None;
+        Ok(())
+    }
+
+    /// Take away the cage around the selected cell
+    pub fn remove_cage(&mut self) -> bool {
+        let Some(i) = self.cage_index(self.game.selected) else {
+            return false;
+        };
+        let mut cages = self.game.cages().to_vec();
+        cages.remove(i);
+        self.game.set_cages(cages);
+        self.verdict = None;
+        true
+    }
+
+    fn cage_index(&self, position: GridPosition) -> Option<usize> {
+        self.game
+            .cages()
+            .iter()
+            .position(|cage| cage.cells.contains(&position))
+    }
+
+    /// A game on the puzzle, with what's been entered as its givens
+    pub fn puzzle(&self) -> Result<Game, GridError> {
+        let mut game = Game::new(self.givens())?.with_cages(self.game.cages().to_vec());
+        if let Some(Verdict::Unique(rating)) = &self.verdict {
+            game.difficulty = Some(rating.difficulty);
+        }
+        Ok(game)
+    }
+
+    /// The puzzle as an .sdk file
+    pub fn to_sdk(&self) -> String {
+        SdkGame {
+            givens: self.givens(),
+            state: None,
+            notes: vec![],
+            cages: self.game.cages().to_vec(),
+        }
+        .write()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::PUZZLES;
+
+    #[test]
+    fn checks_entered_puzzles() {
+        let mut editor = Editor::new(9).unwrap();
+        editor.set(5).unwrap();
+        editor.game.selected = (1, 0);
+        editor.set(5).unwrap();
+        assert!(editor.game.conflicts.contains(&(0, 0)));
+        assert_eq!(editor.check(), &Verdict::Conflicting);
+        editor.set(0).unwrap();
+        assert_eq!(editor.verdict(), None);
+        assert_eq!(editor.check(), &Verdict::MultipleSolutions);
+
+        let cells = PUZZLES[0].cells();
+        for (i, &value) in cells.iter().enumerate() {
+            editor.game.selected = (i % 9, i / 9);
+            editor.set(value).unwrap();
+        }
+        assert_eq!(
+            editor.given_count(),
+            cells.iter().filter(|&&v| v != 0).count()
+        );
+        assert!(matches!(editor.check(), Verdict::Unique(_)));
+        let game = editor.puzzle().unwrap();
+        assert_eq!(game.givens(), cells);
+        assert!(game.difficulty.is_some());
+        assert!(editor.to_sdk().starts_with("[Puzzle]\n"));
+    }
+
+    #[test]
+    fn draws_cages() {
+        let mut editor = Editor::new(4).unwrap();
+        let pick = |editor: &mut Editor, cells: &[GridPosition]| {
+            for &cell in cells {
+                editor.game.selected = cell;
+                editor.toggle_draft().unwrap();
+            }
+        };
+        pick(&mut editor, &[(0, 0), (1, 1)]);
+        assert_eq!(editor.add_cage(3), Err(CageError::Disconnected));
+        pick(&mut editor, &[(1, 1), (1, 0)]);
+        editor.add_cage(3).unwrap();
+        assert_eq!(editor.draft(), &[]);
+        assert_eq!(editor.toggle_draft(), Err(CageError::Overlapping((1, 0))));
+        assert_eq!(
+            editor.check(),
+            &Verdict::InvalidCages(CageError::Uncovered((2, 0)))
+        );
+
+        // a layout read row by row, like the cage tests
+        let layout = "aabbcddecfgehfgg";
+        let sums = [7, 7, 5, 5, 5, 4, 7, 4];
+        editor.remove_cage();
+        for (cage, sum) in "abcdefgh".chars().zip(sums) {
+            let cells: Vec<GridPosition> = layout
+                .chars()
+                .enumerate()
+                .filter(|&(_, c)| c == cage)
+                .map(|(i, _)| (i % 4, i / 4))
+                .collect();
+            pick(&mut editor, &cells);
+            editor.add_cage(sum).unwrap();
+        }
+        assert_eq!(
+            editor.check(),
+            &Verdict::InvalidCages(CageError::WrongTotal(44))
+        );
+        editor.game.selected = (0, 0);
+        editor.remove_cage();
+        pick(&mut editor, &[(0, 0), (1, 0)]);
+        editor.add_cage(3).unwrap();
+        assert_eq!(editor.check(), &Verdict::Tiled);
+        editor.set(4).unwrap();
+        assert_eq!(editor.check(), &Verdict::Conflicting);
+        assert!(editor.to_sdk().contains("[Cages]\n"));
+        assert_eq!(editor.puzzle().unwrap().cages().len(), 8);
+    }
+
+    #[test]
+    fn checks_variant_puzzles() {
+        let diagonal = PUZZLES.iter().find(|puzzle| puzzle.diagonals).unwrap();
+        let cells = diagonal.cells();
+        assert_eq!(Verdict::check(&cells), Verdict::MultipleSolutions);
+        assert!(matches!(
+            Verdict::check_in(&cells, Regions::DIAGONAL),
+            Verdict::Unique(_)
+        ));
+    }
+}
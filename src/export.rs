@@ -0,0 +1,260 @@
+use crate::grid::{value_symbol, Grid, GridPosition};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write,
+};
+
+/// The box side and grid side of a square grid's cells
+fn sizes(cells: &[usize]) -> (usize, usize) {
+    let size = cells.len().isqrt();
+    (size.isqrt(), size)
+}
+
+fn symbol(value: usize) -> char {
+    match value {
+        0 => ' ',
+        value => value_symbol(value),
+    }
+}
+
+/// A ruled line across the grid, drawn with `left`, `fill`, the `cell`
+/// joint between cells, the `bold` joint between boxes, and `right`
+fn rule(size: usize, box_size: usize, [left, fill, cell, bold, right]: [char; 5]) -> String {
+    let mut line = String::from(left);
+    for x in 0..size {
+        line.extend([fill; 3]);
+        line.push(match x + 1 {
+            end if end == size => right,
+            end if end % box_size == 0 => bold,
+            _ => cell,
+        });
+    }
+    line.push('\n');
+    line
+}
+
+/// The puzzle drawn with box-drawing characters, roomy enough to write in,
+/// with double lines around the boxes
+pub fn to_text(cells: &[usize]) -> String {
+    let (box_size, size) = sizes(cells);
+    let mut output = rule(size, box_size, ['╔', '═', '╤', '╦', '╗']);
+    for (y, row) in cells.chunks(size).enumerate() {
+        if y > 0 {
+            output += &match y % box_size {
+                0 => rule(size, box_size, ['╠', '═', '╪', '╬', '╣']),
+                _ => rule(size, box_size, ['╟', '─', '┼', '╫', '╢']),
+            };
+        }
+        output.push('║');
+        for (x, &value) in row.iter().enumerate() {
+            let edge = match (x + 1) % box_size {
+                0 => '║',
+                _ => '│',
+            };
+            output += &format!(" {} {edge}", symbol(value));
+        }
+        output.push('\n');
+    }
+    output + &rule(size, box_size, ['╚', '═', '╧', '╩', '╝'])
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A standalone page with the puzzle as a table, sized to print on one
+/// sheet
+pub fn to_html(cells: &[usize], title: &str) -> String {
+    let (box_size, size) = sizes(cells);
+    let title = escape_html(title);
+    let mut rows = String::new();
+    for (y, row) in cells.chunks(size).enumerate() {
+        rows += "<tr>";
+        for (x, &value) in row.iter().enumerate() {
+            let mut classes = vec![];
+            if x % box_size == 0 && x > 0 {
+                classes.push("left");
+            }
+            if y % box_size == 0 && y > 0 {
+                classes.push("top");
+            }
+            if value != 0 {
+                classes.push("given");
+            }
+            match classes.is_empty() {
+                true => rows += "<td>",
+                false => rows += &format!("<td class=\"{}\">", classes.join(" ")),
+            }
+            if value != 0 {
+                rows.push(value_symbol(value));
+            }
+            rows += "</td>";
+        }
+        rows += "</tr>\n";
+    }
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; text-align: center; }}
+table {{ border-collapse: collapse; border: 3px solid black; margin: 2em auto; }}
+td {{ width: 1.6em; height: 1.6em; border: 1px solid gray; font-size: 24pt; text-align: center; }}
+td.left {{ border-left: 3px solid black; }}
+td.top {{ border-top: 3px solid black; }}
+td.given {{ font-weight: bold; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<table>
+{rows}</table>
+</body>
+</html>
+"#
+    )
+}
+
+/// How `to_svg` draws a grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    /// Side of a cell, in pixels
+    pub cell_size: u32,
+    /// Pencil marks, drawn small in their spot of an empty cell
+    pub notes: HashMap<GridPosition, BTreeSet<usize>>,
+    /// Cells to shade, such as the ones a hint is drawn from
+    pub highlighted: Vec<GridPosition>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 40,
+            notes: HashMap::new(),
+            highlighted: vec![],
+        }
+    }
+}
+
+/// The grid as a standalone SVG image: givens in bold, other values in blue
+/// and box borders thicker than cell borders
+pub fn to_svg(grid: &Grid, options: &SvgOptions) -> String {
+    let (size, box_size) = (grid.size(), grid.sub_square_size());
+    let cell = options.cell_size as usize;
+    let side = cell * size;
+    // half the thickest line sticks out past the grid, so leave room for it
+    let margin = 2;
+    let view = side + 2 * margin;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{view}\" height=\"{view}\" \
+         viewBox=\"-{margin} -{margin} {view} {view}\" font-family=\"sans-serif\">\n\
+         <rect width=\"{side}\" height=\"{side}\" fill=\"white\"/>\n"
+    );
+    for &(x, y) in &options.highlighted {
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{cell}\" height=\"{cell}\" fill=\"#ffe9a8\"/>",
+            x * cell,
+            y * cell
+        );
+    }
+    for y in 0..size {
+        for x in 0..size {
+            let value = grid.get_cell((x, y)).unwrap();
+            let (left, top) = (x * cell, y * cell);
+            if value != 0 {
+                let (weight, color) = match grid.is_readonly((x, y)).unwrap() {
+                    true => ("bold", "black"),
+                    false => ("normal", "#1f5fbf"),
+                };
+                let _ = writeln!(
+                    svg,
+                    "<text x=\"{}\" y=\"{}\" font-size=\"{}\" font-weight=\"{weight}\" \
+                     fill=\"{color}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+                    left + cell / 2,
+                    top + cell / 2,
+                    cell * 3 / 5,
+                    value_symbol(value)
+                );
+                continue;
+            }
+            let Some(notes) = options.notes.get(&(x, y)) else {
+                continue;
+            };
+            // each mark goes in its own spot of a box-shaped layout in the cell
+            let spot = cell / box_size;
+            for &note in notes {
+                let (column, row) = ((note - 1) % box_size, (note - 1) / box_size);
+                let _ = writeln!(
+                    svg,
+                    "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"gray\" \
+                     text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+                    left + column * spot + spot / 2,
+                    top + row * spot + spot / 2,
+                    spot * 4 / 5,
+                    value_symbol(note)
+                );
+            }
+        }
+    }
+    for i in 0..=size {
+        let width = match i % box_size {
+            0 => 3,
+            _ => 1,
+        };
+        let at = i * cell;
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{at}\" y1=\"0\" x2=\"{at}\" y2=\"{side}\" stroke=\"black\" stroke-width=\"{width}\"/>\n\
+             <line x1=\"0\" y1=\"{at}\" x2=\"{side}\" y2=\"{at}\" stroke=\"black\" stroke-width=\"{width}\"/>"
+        );
+    }
+    svg + "</svg>\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_text_and_html() {
+        let cells = [1, 0, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 0, 2];
+        let text = to_text(&cells);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[0], "╔═══╤═══╦═══╤═══╗");
+        assert_eq!(lines[1], "║ 1 │   ║   │   ║");
+        assert_eq!(lines[2], "╟───┼───╫───┼───╢");
+        assert_eq!(lines[4], "╠═══╪═══╬═══╪═══╣");
+        assert_eq!(lines[8], "╚═══╧═══╩═══╧═══╝");
+
+        let html = to_html(&cells, "Mini <1>");
+        assert!(html.contains("<title>Mini &lt;1&gt;</title>"));
+        assert_eq!(html.matches("<tr>").count(), 4);
+        assert!(html.contains("<tr><td class=\"given\">1</td><td></td><td class=\"left\"></td>"));
+    }
+
+    #[test]
+    fn draws_svg() {
+        let mut grid = Grid::new(vec![1, 0, 0, 0, 0, 0, 3, 0, 0, 4, 0, 0, 0, 0, 0, 2]).unwrap();
+        grid.set_cell((1, 0), 2).unwrap();
+        let options = SvgOptions {
+            notes: HashMap::from([((2, 0), BTreeSet::from([3, 4]))]),
+            highlighted: vec![(0, 0)],
+            ..SvgOptions::default()
+        };
+        let svg = to_svg(&grid, &options);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"164\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("font-weight=\"bold\"").count(), 4);
+        assert_eq!(svg.matches("fill=\"#1f5fbf\"").count(), 1);
+        assert_eq!(svg.matches("fill=\"gray\"").count(), 2);
+        assert_eq!(svg.matches("fill=\"#ffe9a8\"").count(), 1);
+        assert_eq!(svg.matches("stroke-width=\"3\"").count(), 6);
+    }
+}
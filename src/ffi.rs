@@ -0,0 +1,173 @@
+use crate::{
+    difficulty::Difficulty, format::lenient, generator, rng::Rng, solver, variant::Variant,
+};
+use std::{
+    ffi::{c_char, CStr},
+    ptr, slice,
+};
+
+// Grids are passed as every cell, row by row, one byte each with 0 for an
+// empty cell. Grids handed out are owned by the caller until given back to
+// `sudoku_free`.
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SudokuDifficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl From<SudokuDifficulty> for Difficulty {
+    fn from(difficulty: SudokuDifficulty) -> Self {
+        match difficulty {
+            SudokuDifficulty::Easy => Difficulty::Easy,
+            SudokuDifficulty::Medium => Difficulty::Medium,
+            SudokuDifficulty::Hard => Difficulty::Hard,
+            SudokuDifficulty::Expert => Difficulty::Expert,
+        }
+    }
+}
+
+impl TryFrom<u32> for SudokuDifficulty {
+    type Error = ();
+
+    // Difficulties cross the boundary as plain integers, since a C caller
+    // can pass any value where the enum is expected
+    fn try_from(value: u32) -> Result<Self, ()> {
+        match value {
+            0 => Ok(SudokuDifficulty::Easy),
+            1 => Ok(SudokuDifficulty::Medium),
+            2 => Ok(SudokuDifficulty::Hard),
+            3 => Ok(SudokuDifficulty::Expert),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Hand a grid over to the caller, writing its length to `len`
+fn give(cells: Vec<usize>, len: *mut usize) -> *mut u8 {
+    let cells: Box<[u8]> = cells.into_iter().map(|value| value as u8).collect();
+    if !len.is_null() {
+        // SAFETY: checked for null, and the caller passes a writable pointer
+        unsafe { *len = cells.len() };
+    }
+    Box::into_raw(cells) as *mut u8
+}
+
+/// Read a grid written in any of the usual shapes, or return null if it
+/// can't be read.
+///
+/// # Safety
+///
+/// `text` must be a null-terminated string, and `len` null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_parse(text: *const c_char, len: *mut usize) -> *mut u8 {
+    if text.is_null() {
+        return ptr::null_mut();
+    }
+    let Ok(text) = CStr::from_ptr(text).to_str() else {
+        return ptr::null_mut();
+    };
+    match lenient::parse(text) {
+        Ok(cells) => give(cells, len),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// The first solution of a grid of `len` cells, of the same length, or null
+/// if it has none.
+///
+/// # Safety
+///
+/// `cells` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_solve(cells: *const u8, len: usize) -> *mut u8 {
+    if cells.is_null() {
+        return ptr::null_mut();
+    }
+    let cells: Vec<usize> = slice::from_raw_parts(cells, len)
+        .iter()
+        .map(|&value| value as usize)
+        .collect();
+    match solver::solve_cells(&cells) {
+        Some(solution) => give(solution, ptr::null_mut()),
+        None => ptr::null_mut(),
+    }
+}
+
+/// A new puzzle with a unique solution, 4 or 9 cells a side, the same one
+/// for the same seed. `difficulty` is one of the `SudokuDifficulty` values.
+/// Null if there are no puzzles of that size or difficulty.
+///
+/// # Safety
+///
+/// `len` must be null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_generate(
+    size: usize,
+    difficulty: u32,
+    seed: u64,
+    len: *mut usize,
+) -> *mut u8 {
+    let Ok(difficulty) = SudokuDifficulty::try_from(difficulty) else {
+        return ptr::null_mut();
+    };
+    let Some(variant) = Variant::ALL
+        .into_iter()
+        .find(|variant| variant.size() == size)
+    else {
+        return ptr::null_mut();
+    };
+    let generated = generator::generate(variant, difficulty.into(), &mut Rng::new(seed));
+    give(generated.givens, len)
+}
+
+/// Give back a grid from `sudoku_parse`, `sudoku_solve` or
+/// `sudoku_generate`. Null is ignored.
+///
+/// # Safety
+///
+/// `cells` must be null, or a grid from this library of `len` cells that
+/// hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_free(cells: *mut u8, len: usize) {
+    if !cells.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(cells, len)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{format::text, library::PUZZLES};
+    use std::ffi::CString;
+
+    #[test]
+    fn round_trips_grids() {
+        let line = CString::new(text::write_line(&PUZZLES[1].cells())).unwrap();
+        let mut len = 0;
+        unsafe {
+            let cells = sudoku_parse(line.as_ptr(), &mut len);
+            assert_eq!(len, 81);
+            let solution = sudoku_solve(cells, len);
+            assert!(!solution.is_null());
+            assert!(!slice::from_raw_parts(solution, len).contains(&0));
+            sudoku_free(cells, len);
+            sudoku_free(solution, len);
+
+            let bad = CString::new("not a grid").unwrap();
+            assert!(sudoku_parse(bad.as_ptr(), &mut len).is_null());
+
+            let givens = sudoku_generate(4, SudokuDifficulty::Easy as u32, 7, &mut len);
+            assert_eq!(len, 16);
+            let solution = sudoku_solve(givens, len);
+            assert!(!solution.is_null());
+            sudoku_free(givens, len);
+            sudoku_free(solution, len);
+            assert!(sudoku_generate(5, SudokuDifficulty::Easy as u32, 7, &mut len).is_null());
+            assert!(sudoku_generate(4, 4, 7, &mut len).is_null());
+        }
+    }
+}
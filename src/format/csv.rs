@@ -0,0 +1,90 @@
+use super::FormatError;
+
+/// Parse a grid written as one row per line of comma-separated values, with
+/// empty fields or `0` for empty cells. Blank lines are ignored, though
+/// still counted in the rows errors report.
+pub fn parse(input: &str) -> Result<Vec<usize>, FormatError> {
+    let mut cells = vec![];
+    let mut width = None;
+    for (row, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if *width.get_or_insert(fields.len()) != fields.len() {
+            return Err(FormatError::WrongFieldCount {
+                row: row + 1,
+                count: fields.len(),
+            });
+        }
+        for (column, field) in fields.into_iter().enumerate() {
+            match field {
+                "" | "." => cells.push(0),
+                field => match field.parse() {
+                    Ok(value) if value <= 9 => cells.push(value),
+                    _ => {
+                        return Err(FormatError::InvalidField {
+                            row: row + 1,
+                            column: column + 1,
+                            field: field.to_string(),
+                        })
+                    }
+                },
+            }
+        }
+    }
+    if cells.len() != 81 {
+        return Err(FormatError::WrongCellCount(cells.len()));
+    }
+    Ok(cells)
+}
+
+/// Write a grid as one comma-separated row per line, with `0` for empty
+/// cells
+pub fn write(cells: &[usize]) -> String {
+    let size = cells.len().isqrt();
+    cells
+        .chunks(size)
+        .map(|row| {
+            let fields: Vec<String> = row.iter().map(usize::to_string).collect();
+            fields.join(",") + "\n"
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let cells = super::super::text::parse(
+            "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+        )
+        .unwrap();
+        let written = write(&cells);
+        assert_eq!(written.lines().next(), Some("5,3,0,0,7,0,0,0,0"));
+        assert_eq!(parse(&written), Ok(cells.clone()));
+        // spreadsheets leave empty cells blank
+        let blanks = written.replace(",0", ",").replace("0,", ",");
+        assert_eq!(parse(&blanks), Ok(cells));
+    }
+
+    #[test]
+    fn reports_positions() {
+        let mut input = "1,2,3,4,5,6,7,8,9\n".repeat(9);
+        input.replace_range(24..25, "x");
+        assert_eq!(
+            parse(&input),
+            Err(FormatError::InvalidField {
+                row: 2,
+                column: 4,
+                field: "x".to_string()
+            })
+        );
+        assert_eq!(
+            parse("1,2,3\n\n1,2"),
+            Err(FormatError::WrongFieldCount { row: 3, count: 2 })
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use super::FormatError;
+
+/// Characters that only decorate a grid: borders, and the joints and lines
+/// of box-drawing characters
+fn is_border(character: char) -> bool {
+    matches!(character, '|' | '-' | '+' | '=') || ('\u{2500}'..='\u{257f}').contains(&character)
+}
+
+/// A cell as read, with where it was found for error messages
+struct Read {
+    value: usize,
+    line: usize,
+    column: usize,
+    text: String,
+}
+
+/// Read a grid however it was written: on one line or one row per line,
+/// with values run together or separated by spaces, commas or semicolons,
+/// blanks as `.`, `0` or `_`, and borders of `|`, `-`, `+` or box-drawing
+/// characters around boxes. Blank lines and `#` comments are skipped. 4x4,
+/// 9x9 and 16x16 grids are recognized by their number of cells, and values
+/// above 9 must be separated by commas.
+pub fn parse(input: &str) -> Result<Vec<usize>, FormatError> {
+    let mut rows: Vec<(usize, Vec<Read>)> = vec![];
+    for (i, line) in input.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('#') || trimmed.starts_with('[') {
+            continue;
+        }
+        let row = match line.contains([',', ';']) {
+            true => fields(line, i + 1)?,
+            false => characters(line, i + 1)?,
+        };
+        if !row.is_empty() {
+            rows.push((i + 1, row));
+        }
+    }
+    let count: usize = rows.iter().map(|(_, row)| row.len()).sum();
+    let Some(size) = (2..=4)
+        .map(|k: usize| k * k)
+        .find(|size| size * size == count)
+    else {
+        // with a row per line, point at the first row that's too long or short
+        if let Some(&(line, ref row)) = rows
+            .iter()
+            .find(|(_, row)| rows.len() > 1 && row.len() != rows.len())
+        {
+            return Err(FormatError::WrongRowLength {
+                line,
+                count: row.len(),
+                expected: rows.len(),
+            });
+        }
+        return Err(FormatError::WrongCellCount(count));
+    };
+    let cells: Vec<Read> = rows.into_iter().flat_map(|(_, row)| row).collect();
+    if let Some(cell) = cells.iter().find(|cell| cell.value > size) {
+        return Err(FormatError::InvalidField {
+            row: cell.line,
+            column: cell.column,
+            field: cell.text.clone(),
+        });
+    }
+    Ok(cells.into_iter().map(|cell| cell.value).collect())
+}
+
+/// One value per character, ignoring whitespace and borders
+fn characters(line: &str, line_number: usize) -> Result<Vec<Read>, FormatError> {
+    let mut row = vec![];
+    for (column, character) in line.chars().enumerate() {
+        let value = match character {
+            '.' | '_' => 0,
+            '0'..='9' => character as usize - '0' as usize,
+            c if c.is_whitespace() || is_border(c) => continue,
+            character => {
+                return Err(FormatError::InvalidCharacter {
+                    line: line_number,
+                    column: column + 1,
+                    character,
+                })
+            }
+        };
+        row.push(Read {
+            value,
+            line: line_number,
+            column: column + 1,
+            text: character.to_string(),
+        });
+    }
+    Ok(row)
+}
+
+/// One value per comma or semicolon separated field, an empty field being
+/// a blank cell
+fn fields(line: &str, line_number: usize) -> Result<Vec<Read>, FormatError> {
+    let mut row = vec![];
+    let mut column = 1;
+    let fields: Vec<&str> = line.split([',', ';']).collect();
+    for (i, field) in fields.iter().enumerate() {
+        let text = field.trim_matches(|c: char| c.is_whitespace() || is_border(c));
+        // leading whitespace doesn't count towards the field's column
+        let start = column + field.chars().take_while(|c| c.is_whitespace()).count();
+        column += field.chars().count() + 1;
+        if text.is_empty() && i + 1 == fields.len() {
+            // a trailing separator
+            continue;
+        }
+        let value = match text {
+            "" | "." | "_" => 0,
+            text => text.parse().map_err(|_| FormatError::InvalidField {
+                row: line_number,
+                column: start,
+                field: text.to_string(),
+            })?,
+        };
+        row.push(Read {
+            value,
+            line: line_number,
+            column: start,
+            text: text.to_string(),
+        });
+    }
+    Ok(row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::text;
+
+    const LINE: &str =
+        "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+    #[test]
+    fn reads_many_shapes() {
+        let expected = text::parse(LINE).unwrap();
+        assert_eq!(parse(LINE).unwrap(), expected);
+        let bordered = "\
+            +-------+-------+-------+\n\
+            | 5 3 _ | _ 7 _ | _ _ _ |\n\
+            | 6 _ _ | 1 9 5 | _ _ _ |\n\
+            | _ 9 8 | _ _ _ | _ 6 _ |\n\
+            +-------+-------+-------+\n\
+            | 8 _ _ | _ 6 _ | _ _ 3 |\n\
+            | 4 _ _ | 8 _ 3 | _ _ 1 |\n\
+            | 7 _ _ | _ 2 _ | _ _ 6 |\n\
+            +-------+-------+-------+\n\
+            | _ 6 _ | _ _ _ | 2 8 _ |\n\
+            | _ _ _ | 4 1 9 | _ _ 5 |\n\
+            | _ _ _ | _ 8 _ | _ 7 9 |\n\
+            +-------+-------+-------+\n";
+        assert_eq!(parse(bordered).unwrap(), expected);
+        let commas: String = expected
+            .chunks(9)
+            .map(|row| {
+                row.iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+                    + "\n"
+            })
+            .collect();
+        assert_eq!(parse(&commas).unwrap(), expected);
+        assert_eq!(parse("1,,,;,,3,;,4,,;,,,2").unwrap().len(), 16);
+        let big: Vec<String> = (0..256).map(|i| (i % 17).to_string()).collect();
+        assert_eq!(parse(&big.join(",")).unwrap()[16], 16);
+    }
+
+    #[test]
+    fn reports_where_errors_are() {
+        assert_eq!(
+            parse("53..7....\n6..1x5..."),
+            Err(FormatError::InvalidCharacter {
+                line: 2,
+                column: 5,
+                character: 'x'
+            })
+        );
+        let short = LINE.as_bytes().chunks(9).enumerate().map(|(i, row)| {
+            let row = std::str::from_utf8(row).unwrap();
+            if i == 4 {
+                &row[1..]
+            } else {
+                row
+            }
+        });
+        assert_eq!(
+            parse(&short.collect::<Vec<_>>().join("\n")),
+            Err(FormatError::WrongRowLength {
+                line: 5,
+                count: 8,
+                expected: 9
+            })
+        );
+        assert_eq!(
+            parse("1, 2, x3"),
+            Err(FormatError::InvalidField {
+                row: 1,
+                column: 7,
+                field: "x3".to_string()
+            })
+        );
+        assert_eq!(
+            parse("1005 0000 0000 0000"),
+            Err(FormatError::InvalidField {
+                row: 1,
+                column: 4,
+                field: "5".to_string()
+            })
+        );
+        assert_eq!(parse("123"), Err(FormatError::WrongCellCount(3)));
+    }
+}
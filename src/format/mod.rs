@@ -0,0 +1,254 @@
+use crate::{game::Game, grid::GridError};
+use std::{fmt::Display, fs, io, path::Path, str::FromStr};
+
+pub mod csv;
+pub mod lenient;
+pub mod opensudoku;
+pub mod sdk;
+pub mod sdm;
+pub mod text;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FormatError {
+    Io(io::ErrorKind),
+    UnsupportedExtension,
+    InvalidCharacter {
+        line: usize,
+        column: usize,
+        character: char,
+    },
+    WrongCellCount(usize),
+    /// A CSV field, or a pasted value, that isn't a value of the grid,
+    /// counting rows and columns from 1
+    InvalidField {
+        row: usize,
+        column: usize,
+        field: String,
+    },
+    /// A row of a pasted grid with more or fewer cells than there are rows
+    WrongRowLength {
+        line: usize,
+        count: usize,
+        expected: usize,
+    },
+    /// A CSV row with a different number of fields than the first
+    WrongFieldCount {
+        row: usize,
+        count: usize,
+    },
+    InvalidGrid(GridError),
+    /// A saved state that changes the puzzle's givens
+    StateMismatch,
+    /// A line of a `[Cages]` section that isn't a sum and then cells
+    InvalidCage {
+        line: usize,
+    },
+    /// A puzzle in a collection that can't be read, counting from 1
+    InvalidEntry {
+        entry: usize,
+        error: Box<FormatError>,
+    },
+    EmptyCollection,
+}
+
+impl From<io::Error> for FormatError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error.kind())
+    }
+}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatError::Io(kind) => write!(f, "could not read file: {kind}"),
+            FormatError::UnsupportedExtension => write!(f, "unsupported file extension"),
+            FormatError::InvalidCharacter {
+                line,
+                column,
+                character,
+            } => write!(
+                f,
+                "invalid character {character:?} at line {line}, column {column}"
+            ),
+            FormatError::WrongCellCount(count) => {
+                write!(f, "expected 81 cells, found {count}")
+            }
+            FormatError::InvalidField { row, column, field } => {
+                write!(f, "invalid value {field:?} at row {row}, column {column}")
+            }
+            FormatError::WrongRowLength {
+                line,
+                count,
+                expected,
+            } => write!(f, "line {line} has {count} cells, expected {expected}"),
+            FormatError::WrongFieldCount { row, count } => {
+                write!(f, "row {row} has {count} fields, unlike the rows before it")
+            }
+            FormatError::InvalidGrid(error) => write!(f, "invalid grid: {error:?}"),
+            FormatError::StateMismatch => write!(f, "saved state doesn't match the puzzle"),
+            FormatError::InvalidCage { line } => write!(f, "invalid cage at line {line}"),
+            FormatError::InvalidEntry { entry, error } => write!(f, "puzzle {entry}: {error}"),
+            FormatError::EmptyCollection => write!(f, "no puzzles in the collection"),
+        }
+    }
+}
+
+/// File extensions that `load_file` knows how to read
+pub const EXTENSIONS: [&str; 3] = ["sdk", "txt", "csv"];
+/// File extensions of collections, read by `load_collection`
+pub const COLLECTION_EXTENSIONS: [&str; 2] = ["sdm", "opensudoku"];
+
+/// A set of puzzles from one file, played one at a time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collection {
+    pub name: Option<String>,
+    pub puzzles: Vec<Vec<usize>>,
+}
+
+pub fn is_collection(path: impl AsRef<Path>) -> bool {
+    path.as_ref()
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| COLLECTION_EXTENSIONS.contains(&extension))
+}
+
+pub fn load_collection(path: impl AsRef<Path>) -> Result<Collection, FormatError> {
+    let path = path.as_ref();
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    let mut collection = match extension {
+        Some("sdm") => Collection {
+            name: None,
+            puzzles: sdm::parse(&fs::read_to_string(path)?)?,
+        },
+        Some("opensudoku") => opensudoku::parse(&fs::read_to_string(path)?)?,
+        _ => return Err(FormatError::UnsupportedExtension),
+    };
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned());
+    collection.name = collection.name.or(stem);
+    Ok(collection)
+}
+
+/// The puzzle in a file, without any progress saved with it
+pub fn load_file(path: impl AsRef<Path>) -> Result<Vec<usize>, FormatError> {
+    let path = path.as_ref();
+    match extension(path)? {
+        "sdk" => Ok(sdk::parse(&fs::read_to_string(path)?)?.givens),
+        "csv" => csv::parse(&fs::read_to_string(path)?),
+        _ => text::parse(&fs::read_to_string(path)?),
+    }
+}
+
+/// The game in a file, picking up where it was saved if the format records
+/// progress
+pub fn load_game(path: impl AsRef<Path>) -> Result<Game, FormatError> {
+    let path = path.as_ref();
+    match extension(path)? {
+        "sdk" => sdk::parse(&fs::read_to_string(path)?)?.game(),
+        _ => Game::new(load_file(path)?).map_err(FormatError::InvalidGrid),
+    }
+}
+
+fn extension(path: &Path) -> Result<&str, FormatError> {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .filter(|extension| EXTENSIONS.contains(extension))
+        .ok_or(FormatError::UnsupportedExtension)
+}
+
+/// The ways a single grid can be written out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// One row of digits per line, as in `.sdk` files
+    Sdk,
+    /// All 81 cells on a single line
+    Line,
+    /// Rows with the boxes ruled off, for reading
+    Grid,
+    Csv,
+}
+
+impl Layout {
+    pub fn parse(self, input: &str) -> Result<Vec<usize>, FormatError> {
+        match self {
+            Layout::Sdk | Layout::Line => text::parse(input),
+            // rules become spaces so errors still point at the right column
+            Layout::Grid => text::parse(&input.replace(['|', '-', '+'], " ")),
+            Layout::Csv => csv::parse(input),
+        }
+    }
+
+    pub fn write(self, cells: &[usize]) -> String {
+        match self {
+            Layout::Sdk => text::write(cells),
+            Layout::Line => text::write_line(cells) + "\n",
+            Layout::Grid => write_ruled(cells),
+            Layout::Csv => csv::write(cells),
+        }
+    }
+}
+
+impl FromStr for Layout {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "sdk" => Ok(Layout::Sdk),
+            "line" => Ok(Layout::Line),
+            "grid" => Ok(Layout::Grid),
+            "csv" => Ok(Layout::Csv),
+            _ => Err(format!(
+                "expected one of sdk, line, grid or csv, found {name:?}"
+            )),
+        }
+    }
+}
+
+fn write_ruled(cells: &[usize]) -> String {
+    let size = cells.len().isqrt();
+    let box_size = size.isqrt();
+    let mut output = String::new();
+    for (y, row) in cells.chunks(size).enumerate() {
+        let boxes: Vec<String> = row
+            .chunks(box_size)
+            .map(|cells| {
+                let symbols: Vec<String> = cells
+                    .iter()
+                    .map(|&value| text::write_line(&[value]))
+                    .collect();
+                symbols.join(" ")
+            })
+            .collect();
+        let line = boxes.join(" | ");
+        if y > 0 && y % box_size == 0 {
+            let rule: String = line
+                .chars()
+                .map(|c| if c == '|' { '+' } else { '-' })
+                .collect();
+            output += &rule;
+            output.push('\n');
+        }
+        output += &line;
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layouts_round_trip() {
+        let line =
+            "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+        let cells = text::parse(line).unwrap();
+        let ruled = Layout::Grid.write(&cells);
+        assert_eq!(ruled.lines().next(), Some("5 3 . | . 7 . | . . ."));
+        assert_eq!(ruled.lines().nth(3), Some("------+-------+------"));
+        for layout in [Layout::Sdk, Layout::Line, Layout::Grid, Layout::Csv] {
+            assert_eq!(layout.parse(&layout.write(&cells)), Ok(cells.clone()));
+        }
+    }
+}
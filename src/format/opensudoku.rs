@@ -0,0 +1,102 @@
+use super::{text, Collection, FormatError};
+
+/// Parse an OpenSudoku puzzle collection, an XML file with the collection's
+/// `<name>` and a `<game data="...">` element per puzzle. Older files wrap
+/// the games in `<folder name="...">`, which is read the same way.
+pub fn parse(input: &str) -> Result<Collection, FormatError> {
+    let name = element_text(input, "name")
+        .or_else(|| {
+            tags(input, "folder")
+                .next()
+                .and_then(|tag| attribute(tag, "name"))
+        })
+        .map(|name| unescape(&name));
+    let puzzles = tags(input, "game")
+        .filter_map(|tag| attribute(tag, "data"))
+        .enumerate()
+        .map(|(i, data)| {
+            text::parse(&data).map_err(|error| FormatError::InvalidEntry {
+                entry: i + 1,
+                error: Box::new(error),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if puzzles.is_empty() {
+        return Err(FormatError::EmptyCollection);
+    }
+    Ok(Collection { name, puzzles })
+}
+
+/// The inside of every `<name ...>` start tag
+fn tags<'a>(input: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> {
+    input.split('<').skip(1).filter_map(move |rest| {
+        let tag = &rest[..rest.find('>')?];
+        let after_name = tag.strip_prefix(name)?;
+        after_name
+            .starts_with(|c: char| c.is_whitespace() || c == '/')
+            .then_some(after_name)
+    })
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let start = tag.find(&format!("{name}=\""))? + name.len() + 2;
+    let length = tag[start..].find('"')?;
+    Some(tag[start..start + length].to_string())
+}
+
+fn element_text(input: &str, name: &str) -> Option<String> {
+    let start = input.find(&format!("<{name}>"))? + name.len() + 2;
+    let length = input[start..].find(&format!("</{name}>"))?;
+    Some(input[start..start + length].trim().to_string())
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME: &str =
+        "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+    #[test]
+    fn parses_collections() {
+        let input = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <opensudoku>
+              <name>Easy &amp; fun</name>
+              <author>someone</author>
+              <game data="{GAME}" />
+              <game data="{GAME}"/>
+            </opensudoku>"#
+        );
+        let collection = parse(&input).unwrap();
+        assert_eq!(collection.name.as_deref(), Some("Easy & fun"));
+        assert_eq!(collection.puzzles.len(), 2);
+        assert_eq!(collection.puzzles[0], text::parse(GAME).unwrap());
+
+        let old = format!(
+            r#"<opensudoku><folder name="Old"><game data="{GAME}"/></folder></opensudoku>"#
+        );
+        assert_eq!(parse(&old).unwrap().name.as_deref(), Some("Old"));
+    }
+
+    #[test]
+    fn reports_bad_entries() {
+        let input = format!(r#"<opensudoku><game data="{GAME}"/><game data="123"/></opensudoku>"#);
+        assert_eq!(
+            parse(&input),
+            Err(FormatError::InvalidEntry {
+                entry: 2,
+                error: Box::new(FormatError::WrongCellCount(3))
+            })
+        );
+        assert_eq!(parse("<opensudoku/>"), Err(FormatError::EmptyCollection));
+    }
+}
@@ -0,0 +1,270 @@
+use super::{text, FormatError};
+use crate::{
+    cage::Cage,
+    game::Game,
+    grid::{parse_position, position_label, value_symbol, GridPosition},
+};
+
+/// A game as stored in a SadMan Sudoku `.sdk` file: the puzzle, and when
+/// saved mid-game, the values filled in and the pencil marks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SdkGame {
+    pub givens: Vec<usize>,
+    /// Every cell's current value, givens included
+    pub state: Option<Vec<usize>>,
+    pub notes: Vec<(GridPosition, Vec<usize>)>,
+    /// Killer cages, in a `[Cages]` section other programs skip over
+    pub cages: Vec<Cage>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Puzzle,
+    State,
+    PencilMarks,
+    Cages,
+    /// Sections this crate doesn't read, such as `[Metadata]`
+    Other,
+}
+
+/// Parse an `.sdk` file. A file with no section headers is all puzzle, and
+/// `#` lines (the author, source and other metadata) are skipped.
+pub fn parse(input: &str) -> Result<SdkGame, FormatError> {
+    // each section's text keeps the others' lines as blanks, so errors
+    // report lines of the whole file
+    let mut puzzle = String::new();
+    let mut state = String::new();
+    let mut marks = vec![];
+    let mut cages = vec![];
+    let mut has_state = false;
+    let mut section = Section::Puzzle;
+    for (i, line) in input.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            section = match trimmed.to_ascii_lowercase().as_str() {
+                "[puzzle]" => Section::Puzzle,
+                "[state]" => Section::State,
+                "[pencilmarks]" => Section::PencilMarks,
+                "[cages]" => Section::Cages,
+                _ => Section::Other,
+            };
+            has_state |= section == Section::State;
+        } else if !trimmed.starts_with('#') {
+            match section {
+                Section::Puzzle => puzzle += line,
+                Section::State => state += line,
+                Section::PencilMarks if !trimmed.is_empty() => marks.push((i + 1, line)),
+                Section::Cages if !trimmed.is_empty() => cages.push((i + 1, trimmed)),
+                Section::PencilMarks | Section::Cages | Section::Other => {}
+            }
+        }
+        puzzle.push('\n');
+        state.push('\n');
+    }
+    let givens = text::parse(&puzzle)?;
+    let state = match has_state {
+        true => Some(text::parse(&state)?),
+        false => None,
+    };
+    let size = givens.len().isqrt();
+    Ok(SdkGame {
+        givens,
+        state,
+        notes: parse_marks(&marks)?,
+        cages: parse_cages(&cages, size)?,
+    })
+}
+
+/// One cage per line, its sum and then its cells, like `15 r1c1 r1c2 r2c1`
+fn parse_cages(lines: &[(usize, &str)], size: usize) -> Result<Vec<Cage>, FormatError> {
+    lines
+        .iter()
+        .map(|&(line, text)| {
+            let mut words = text.split_whitespace();
+            let sum = words.next().and_then(|word| word.parse().ok());
+            let cells: Option<Vec<GridPosition>> =
+                words.map(|word| parse_position(word, size)).collect();
+            match (sum, cells) {
+                (Some(sum), Some(cells)) if !cells.is_empty() => Ok(Cage { sum, cells }),
+                _ => Err(FormatError::InvalidCage { line }),
+            }
+        })
+        .collect()
+}
+
+/// One line per row, with each cell's marks written as a run of digits and
+/// `.` for a cell without any
+fn parse_marks(lines: &[(usize, &str)]) -> Result<Vec<(GridPosition, Vec<usize>)>, FormatError> {
+    let mut notes = vec![];
+    for (y, &(line, text)) in lines.iter().enumerate() {
+        let mut x = 0;
+        let mut values = vec![];
+        let mut in_field = false;
+        for (column, character) in text.chars().chain([' ']).enumerate() {
+            if character.is_whitespace() {
+                if in_field && !values.is_empty() {
+                    notes.push(((x, y), std::mem::take(&mut values)));
+                }
+                x += in_field as usize;
+                in_field = false;
+                continue;
+            }
+            in_field = true;
+            match character {
+                '.' | '0' => {}
+                '1'..='9' => values.push(character as usize - '0' as usize),
+                character => {
+                    return Err(FormatError::InvalidCharacter {
+                        line,
+                        column: column + 1,
+                        character,
+                    })
+                }
+            }
+        }
+    }
+    Ok(notes)
+}
+
+impl SdkGame {
+    pub fn from_game(game: &Game) -> Self {
+        let mut notes: Vec<(GridPosition, Vec<usize>)> = game
+            .all_notes()
+            .map(|(position, notes)| (position, notes.iter().copied().collect()))
+            .collect();
+        notes.sort_by_key(|&((x, y), _)| (y, x));
+        Self {
+            givens: game.givens(),
+            state: Some(game.values()),
+            notes,
+            cages: game.cages().to_vec(),
+        }
+    }
+
+    /// The game with its state and marks filled in, the clock starting over
+    pub fn game(&self) -> Result<Game, FormatError> {
+        let mut game = Game::new(self.givens.clone())
+            .map_err(FormatError::InvalidGrid)?
+            .with_cages(self.cages.clone());
+        if let Some(state) = &self.state {
+            let givens_kept = state
+                .iter()
+                .zip(&self.givens)
+                .all(|(value, given)| *given == 0 || value == given);
+            if state.len() != self.givens.len() || !givens_kept {
+                return Err(FormatError::StateMismatch);
+            }
+            game.resume_from(state, Default::default())
+                .map_err(FormatError::InvalidGrid)?;
+        }
+        for (position, values) in &self.notes {
+            for &value in values {
+                // marks on givens mean nothing, so they're dropped
+                let _ = game.toggle_note(*position, value);
+            }
+        }
+        Ok(game)
+    }
+
+    pub fn write(&self) -> String {
+        let mut output = "[Puzzle]\n".to_string() + &text::write(&self.givens);
+        if let Some(state) = &self.state {
+            output += "[State]\n";
+            output += &text::write(state);
+        }
+        if !self.notes.is_empty() {
+            let size = self.givens.len().isqrt();
+            output += "[PencilMarks]\n";
+            for y in 0..size {
+                let fields: Vec<String> = (0..size)
+                    .map(|x| {
+                        let marks = self.notes.iter().find(|(position, _)| *position == (x, y));
+                        match marks {
+                            Some((_, values)) => values.iter().map(|&v| value_symbol(v)).collect(),
+                            None => ".".to_string(),
+                        }
+                    })
+                    .collect();
+                output += &fields.join(" ");
+                output.push('\n');
+            }
+        }
+        if !self.cages.is_empty() {
+            output += "[Cages]\n";
+            for cage in &self.cages {
+                let cells: Vec<String> = cage
+                    .cells
+                    .iter()
+                    .map(|&cell| position_label(cell))
+                    .collect();
+                output += &format!("{} {}\n", cage.sum, cells.join(" "));
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::PUZZLES;
+
+    #[test]
+    fn round_trips_a_game_in_progress() {
+        let mut game = PUZZLES[1].game().unwrap();
+        game.add_entry((2, 0), 4).unwrap();
+        game.toggle_note((3, 0), 2).unwrap();
+        game.toggle_note((3, 0), 6).unwrap();
+        let written = SdkGame::from_game(&game).write();
+        assert!(written.contains("[State]\n534.7....\n"));
+        assert!(written.contains("[PencilMarks]\n. . . 26 . . . . .\n"));
+
+        let parsed = parse(&format!("#A Someone\n{written}")).unwrap();
+        assert_eq!(parsed, SdkGame::from_game(&game));
+        let loaded = parsed.game().unwrap();
+        assert_eq!(loaded.get_cell((2, 0)), Ok(4));
+        assert_eq!(loaded.givens(), game.givens());
+        assert_eq!(loaded.notes((3, 0)), game.notes((3, 0)));
+    }
+
+    #[test]
+    fn reads_plain_puzzles_and_reports_lines() {
+        let line =
+            "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+        let parsed = parse(line).unwrap();
+        assert_eq!(parsed.givens, text::parse(line).unwrap());
+        assert_eq!(parsed.state, None);
+
+        let input = format!("[Puzzle]\n{line}\n[State]\n12x");
+        assert_eq!(
+            parse(&input),
+            Err(FormatError::InvalidCharacter {
+                line: 4,
+                column: 3,
+                character: 'x'
+            })
+        );
+        let clash = format!("[Puzzle]\n{line}\n[State]\n6{}", &line[1..]);
+        assert_eq!(
+            parse(&clash).unwrap().game().err(),
+            Some(FormatError::StateMismatch)
+        );
+    }
+
+    #[test]
+    fn round_trips_killer_cages() {
+        let killer = PUZZLES
+            .iter()
+            .find(|puzzle| puzzle.cages.is_some())
+            .unwrap();
+        let game = killer.game().unwrap();
+        let written = SdkGame::from_game(&game).write();
+        let first = &game.cages()[0];
+        assert!(written.contains(&format!("[Cages]\n{} r1c1 ", first.sum)));
+        let loaded = parse(&written).unwrap().game().unwrap();
+        assert_eq!(loaded.cages(), game.cages());
+
+        let input = "[Puzzle]\n".to_string() + &".".repeat(81) + "\n[Cages]\n\n12 r1c1 r0c2\n";
+        assert_eq!(parse(&input), Err(FormatError::InvalidCage { line: 5 }));
+    }
+}
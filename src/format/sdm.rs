@@ -0,0 +1,43 @@
+use super::{text, FormatError};
+
+/// Parse an `.sdm` collection, one 81-character puzzle per line. Blank lines
+/// and `#` comments are skipped.
+pub fn parse(input: &str) -> Result<Vec<Vec<usize>>, FormatError> {
+    let puzzles = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .enumerate()
+        .map(|(i, line)| {
+            text::parse(line).map_err(|error| FormatError::InvalidEntry {
+                entry: i + 1,
+                error: Box::new(error),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if puzzles.is_empty() {
+        return Err(FormatError::EmptyCollection);
+    }
+    Ok(puzzles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_lines() {
+        let line =
+            "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+        let puzzles = parse(&format!("# two\n{line}\n\n{line}\n")).unwrap();
+        assert_eq!(puzzles.len(), 2);
+        assert_eq!(
+            parse(&format!("{line}\n53..")),
+            Err(FormatError::InvalidEntry {
+                entry: 2,
+                error: Box::new(FormatError::WrongCellCount(4))
+            })
+        );
+        assert_eq!(parse("\n"), Err(FormatError::EmptyCollection));
+    }
+}
@@ -0,0 +1,112 @@
+use super::FormatError;
+use crate::grid::value_symbol;
+
+/// Parse a grid written as digits with `.`, `_` or `0` for empty cells.
+/// Whitespace is ignored, as are blank lines and lines starting with `#`
+/// or `[` (comments and section headers).
+pub fn parse(input: &str) -> Result<Vec<usize>, FormatError> {
+    let mut cells = vec![];
+    for (line_number, line) in input.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('#') || trimmed.starts_with('[') {
+            continue;
+        }
+        for (column, character) in line.chars().enumerate() {
+            match character {
+                '.' | '_' => cells.push(0),
+                c if c.is_ascii_digit() => cells.push(c as usize - '0' as usize),
+                c if c.is_whitespace() => {}
+                character => {
+                    return Err(FormatError::InvalidCharacter {
+                        line: line_number + 1,
+                        column: column + 1,
+                        character,
+                    })
+                }
+            }
+        }
+    }
+    if cells.len() != 81 {
+        return Err(FormatError::WrongCellCount(cells.len()));
+    }
+    Ok(cells)
+}
+
+/// Write a grid as one row of digits per line, with `.` for empty cells
+pub fn write(cells: &[usize]) -> String {
+    let size = cells.len().isqrt();
+    let mut output = String::with_capacity(cells.len() + size);
+    for row in cells.chunks(size) {
+        output.push_str(&write_line(row));
+        output.push('\n');
+    }
+    output
+}
+
+/// Write a grid on a single line, row after row, with `.` for empty cells
+pub fn write_line(cells: &[usize]) -> String {
+    cells
+        .iter()
+        .map(|&value| match value {
+            0 => '.',
+            value => value_symbol(value),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_single_line() {
+        let cells = parse(
+            "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+        )
+        .unwrap();
+        assert_eq!(cells.len(), 81);
+        assert_eq!(&cells[..9], &[5, 3, 0, 0, 7, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn parse_lines_with_comments() {
+        let cells = parse(
+            "# a comment\n\
+             [Puzzle]\n\
+             53..7....\n\
+             6..195...\n\
+             .98....6.\n\
+             8...6...3\n\
+             4..8.3..1\n\
+             7...2...6\n\
+             .6....28.\n\
+             ...419..5\n\
+             ....8..79\n",
+        )
+        .unwrap();
+        assert_eq!(&cells[72..], &[0, 0, 0, 0, 8, 0, 0, 7, 9]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            parse("53..7....\n6..1x5..."),
+            Err(FormatError::InvalidCharacter {
+                line: 2,
+                column: 5,
+                character: 'x'
+            })
+        );
+        assert_eq!(parse("123"), Err(FormatError::WrongCellCount(3)));
+    }
+
+    #[test]
+    fn write_round_trips() {
+        let line =
+            "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+        let written = write(&parse(line).unwrap());
+        assert_eq!(written.lines().next(), Some("53..7...."));
+        assert_eq!(parse(&write_line(&parse(line).unwrap())), parse(line));
+        assert_eq!(parse(&written).unwrap(), parse(line).unwrap());
+    }
+}
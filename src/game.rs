@@ -1,19 +1,91 @@
-use crate::checker::{Checker, CheckerResult};
+use crate::cage::Cage;
+use crate::checker::{Checker, ConflictTracker};
+use crate::constraint::Constraint;
+use crate::difficulty::Difficulty;
 use crate::grid::*;
+use crate::history::History;
+use crate::rng::Rng;
+use crate::timer::Timer;
+use crate::variant::Variant;
+#[cfg(feature = "tui")]
+use crate::{config::Assist, theme::Appearance, timer::format_duration};
+#[cfg(feature = "tui")]
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    widgets::{StatefulWidget, Widget},
+    style::Color,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, StatefulWidget, Widget},
 };
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+/// How long a cell stays highlighted after rejected input
+pub const FLASH_DURATION: Duration = Duration::from_millis(300);
+/// Number of recent events the game remembers for the history panel
+const HISTORY_LIMIT: usize = 100;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Entry {
     pub position: GridPosition,
     pub value: usize,
     pub previous_value: usize,
+    /// Time on the game clock when the entry was made
+    pub at: Duration,
+}
+
+/// Something the player did to the grid, as listed in the history panel
+#[derive(Debug, Clone, Copy)]
+pub enum GameEvent {
+    Entry(Entry),
+    Undo(Entry),
+    Redo(Entry),
+    /// An entry made by the other player on a shared board
+    Partner(Entry),
+}
+
+impl Display for GameEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameEvent::Entry(Entry {
+                position, value: 0, ..
+            })
+            | GameEvent::Partner(Entry {
+                position, value: 0, ..
+            }) => write!(f, "clear {}", position_label(*position)),
+            GameEvent::Entry(Entry {
+                position, value, ..
+            })
+            | GameEvent::Partner(Entry {
+                position, value, ..
+            }) => write!(
+                f,
+                "{} ← {}",
+                position_label(*position),
+                value_symbol(*value)
+            ),
+            GameEvent::Undo(Entry { position, .. }) => {
+                write!(f, "undo {}", position_label(*position))
+            }
+            GameEvent::Redo(Entry { position, .. }) => {
+                write!(f, "redo {}", position_label(*position))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+    InProgress,
+    Won,
+    /// The player gave up and the solution was filled in
+    Revealed,
+    /// The mistake limit was reached
+    Lost,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     Left,
     Right,
@@ -25,26 +97,202 @@ pub enum Direction {
 pub struct Game {
     pub selected: GridPosition,
     pub invalid_subsections: Vec<GridSubsectionType>,
-    is_complete: bool,
+    /// Indices into `cages` of the cages breaking their constraint
+    pub invalid_cages: Vec<usize>,
+    /// Indices into `constraints` of the constraints that are broken
+    pub invalid_constraints: Vec<usize>,
+    pub conflicts: HashSet<GridPosition>,
+    pub difficulty: Option<Difficulty>,
+    pub mistakes: usize,
+    pub hints_used: usize,
+    /// Mistakes that end the game, if there's a limit
+    mistake_limit: Option<usize>,
+    /// Carrying on in zen mode after reaching the mistake limit, so a win
+    /// doesn't count
+    zen: bool,
+    status: GameStatus,
+    paused: bool,
+    timer: Timer,
+    /// Time taken off the clock for going without input
+    idle: Duration,
+    rejected: Option<(GridPosition, Instant)>,
     grid: Grid,
-    entries: Vec<Entry>,
+    cages: Vec<Cage>,
+    regions: Regions,
+    constraints: Vec<Constraint>,
+    entries: History,
+    /// Undone entries, most recent last, until a new entry is made
+    undone: History,
+    /// Pencil marks, kept under a cell's value and shown again if it's cleared
+    notes: HashMap<GridPosition, BTreeSet<usize>>,
+    /// The most recent events, oldest first
+    events: VecDeque<GameEvent>,
+    /// Events recorded since the game began, counting those since dropped
+    /// from `events`
+    recorded: usize,
+    /// Cells holding a value the other player entered on a shared board
+    partner: HashSet<GridPosition>,
     checker: Checker,
+    tracker: ConflictTracker,
+    /// The grid's cells as last drawn
+    #[cfg(feature = "tui")]
+    render_cache: GridCache,
 }
 
 impl Game {
     pub fn new(cells: Vec<usize>) -> Result<Self, GridError> {
-        let grid = Grid::new(cells).unwrap();
+        let grid = Grid::new(cells)?;
         Ok(Self::from_grid(grid))
     }
 
     pub fn from_grid(grid: Grid) -> Self {
-        Self {
+        let size = grid.size();
+        let mut game = Self {
             grid,
             selected: (0, 0),
             checker: Checker::new(),
-            entries: vec![],
+            tracker: ConflictTracker::default(),
+            #[cfg(feature = "tui")]
+            render_cache: GridCache::default(),
+            entries: History::new(size),
+            undone: History::new(size),
+            notes: HashMap::new(),
+            events: VecDeque::new(),
+            recorded: 0,
+            partner: HashSet::new(),
             invalid_subsections: vec![],
-            is_complete: false,
+            invalid_cages: vec![],
+            invalid_constraints: vec![],
+            cages: vec![],
+            constraints: vec![],
+            regions: Regions::default(),
+            conflicts: HashSet::new(),
+            difficulty: None,
+            mistakes: 0,
+            hints_used: 0,
+            mistake_limit: None,
+            zen: false,
+            status: GameStatus::InProgress,
+            paused: false,
+            timer: Timer::start(),
+            idle: Duration::ZERO,
+            rejected: None,
+        };
+        game.apply_checker();
+        game
+    }
+
+    /// Turn the game into a killer sudoku with these cages
+    pub fn with_cages(mut self, cages: Vec<Cage>) -> Self {
+        self.set_cages(cages);
+        self
+    }
+
+    /// Swap the killer cages for others, as when they're drawn in the editor
+    pub fn set_cages(&mut self, cages: Vec<Cage>) {
+        self.cages = cages;
+        self.apply_checker();
+        // the cells were drawn with the old outlines
+        #[cfg(feature = "tui")]
+        {
+            self.render_cache = GridCache::default();
+        }
+    }
+
+    /// Turn the game into an X-Sudoku, where both main diagonals also hold
+    /// every value once
+    pub fn with_diagonals(mut self) -> Self {
+        self.regions.diagonals = true;
+        self.apply_checker();
+        self
+    }
+
+    pub fn has_diagonals(&self) -> bool {
+        self.regions.diagonals
+    }
+
+    /// Turn the game into a Windoku, where four windows also hold every
+    /// value once
+    pub fn with_windows(mut self) -> Self {
+        self.regions.windows = true;
+        self.apply_checker();
+        self
+    }
+
+    pub fn has_windows(&self) -> bool {
+        self.regions.windows
+    }
+
+    /// Add the extra regions of a variant, keeping any already set
+    pub fn with_regions(mut self, regions: Regions) -> Self {
+        self.regions.diagonals |= regions.diagonals;
+        self.regions.windows |= regions.windows;
+        self.apply_checker();
+        self
+    }
+
+    pub fn regions(&self) -> Regions {
+        self.regions
+    }
+
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+
+    /// Add rules between particular cells, like the arrows of arrow sudoku
+    pub fn with_constraints(mut self, constraints: Vec<Constraint>) -> Self {
+        self.constraints.extend(constraints);
+        self.apply_checker();
+        self
+    }
+
+    pub fn constraints(&self) -> &[Constraint] {
+        &self.constraints
+    }
+
+    /// A fresh game on the same puzzle in disguise: turned or flipped at
+    /// random, with its digits renamed at random too unless cages or
+    /// constraints depend on their values
+    pub fn shuffled(&self, rng: &mut Rng) -> Game {
+        let size = self.size();
+        let symmetry = Symmetry::ALL[rng.below(Symmetry::ALL.len())];
+        let mut digits: Vec<usize> = (1..=size).collect();
+        if self.cages.is_empty() && self.constraints.is_empty() {
+            rng.shuffle(&mut digits);
+        }
+        let moved = |cell| symmetry.apply(cell, size);
+        let cages = self
+            .cages
+            .iter()
+            .map(|cage| Cage {
+                sum: cage.sum,
+                cells: cage.cells.iter().map(|&cell| moved(cell)).collect(),
+            })
+            .collect();
+        let constraints = self
+            .constraints
+            .iter()
+            .map(|constraint| constraint.map_cells(moved))
+            .collect();
+        // the extra regions look the same however the grid is turned
+        let mut game = Game::new(transform(&self.givens(), symmetry, &digits))
+            .unwrap()
+            .with_regions(self.regions)
+            .with_cages(cages)
+            .with_constraints(constraints);
+        game.difficulty = self.difficulty;
+        game
+    }
+
+    /// The name of the rules the puzzle is played by, from its cages and
+    /// constraints or else its extra regions
+    pub fn variant_name(&self) -> &'static str {
+        match (self.cages.is_empty(), self.constraints.first()) {
+            (false, _) => "Killer",
+            (_, Some(Constraint::Arrow { .. })) => "Arrow",
+            (_, Some(Constraint::Kropki { .. })) => "Kropki",
+            (_, Some(Constraint::Inequality { .. })) => "Greater-than",
+            (_, None) => Variant::find(self.size(), self.regions).map_or("Custom", Variant::name),
         }
     }
 
@@ -55,63 +303,442 @@ impl Game {
             position,
             value,
             previous_value,
+            at: self.timer.elapsed(),
         };
         self.entries.push(entry);
-        self.apply_checker();
+        self.undone.clear();
+        self.partner.remove(&position);
+        self.record(GameEvent::Entry(entry));
+        self.apply_change(position, previous_value);
+        if value != 0 && self.conflicts.contains(&position) {
+            self.mistakes += 1;
+            self.check_mistakes();
+        }
         Ok(entry)
     }
 
-    pub fn move_selected(&mut self, direction: Direction) {
-        match direction {
-            Direction::Left => {
-                if self.selected.0 > 0 {
-                    self.selected.0 = (self.selected.0 as isize - 1) as usize;
-                }
+    /// End the game once this many mistakes are made, or never for `None`
+    pub fn set_mistake_limit(&mut self, limit: Option<usize>) {
+        self.mistake_limit = limit;
+        self.check_mistakes();
+    }
+
+    pub fn mistake_limit(&self) -> Option<usize> {
+        self.mistake_limit
+    }
+
+    /// Lift the mistake limit of a lost game and play on, without the win
+    /// counting
+    pub fn continue_in_zen(&mut self) {
+        if self.status == GameStatus::Lost {
+            self.status = GameStatus::InProgress;
+            self.mistake_limit = None;
+            self.zen = true;
+            self.timer.resume();
+        }
+    }
+
+    /// Carry on in zen mode a game saved after it went past the mistake
+    /// limit
+    pub fn resume_in_zen(&mut self) {
+        self.mistake_limit = None;
+        self.zen = true;
+    }
+
+    /// Whether the game went on past the mistake limit
+    pub fn is_zen(&self) -> bool {
+        self.zen
+    }
+
+    fn check_mistakes(&mut self) {
+        let reached = self
+            .mistake_limit
+            .is_some_and(|limit| self.mistakes >= limit);
+        if reached && self.status == GameStatus::InProgress {
+            self.status = GameStatus::Lost;
+            self.paused = false;
+            self.timer.pause();
+        }
+    }
+
+    /// Take an entry the other player made on a shared board. It shows in
+    /// the history in their color, but isn't the player's to undo. Like any
+    /// new entry, it leaves nothing to redo.
+    pub fn add_partner_entry(
+        &mut self,
+        position: GridPosition,
+        value: usize,
+    ) -> Result<Entry, GridError> {
+        let previous_value = self.grid.set_cell(position, value)?;
+        let entry = Entry {
+            position,
+            value,
+            previous_value,
+            at: self.timer.elapsed(),
+        };
+        self.undone.clear();
+        match value {
+            0 => self.partner.remove(&position),
+            _ => self.partner.insert(position),
+        };
+        self.record(GameEvent::Partner(entry));
+        self.apply_change(position, previous_value);
+        Ok(entry)
+    }
+
+    /// Whether the value in a cell was entered by the other player on a
+    /// shared board
+    pub fn is_partner_entry(&self, position: GridPosition) -> bool {
+        self.partner.contains(&position)
+    }
+
+    /// Move the selection one cell, wrapping to the opposite edge if `wrap`
+    /// is set and staying put otherwise
+    pub fn move_selected(&mut self, direction: Direction, wrap: bool) {
+        self.shift_selected(direction, 1, wrap);
+    }
+
+    /// Move the selection to the same cell in the neighboring box
+    pub fn jump_selected(&mut self, direction: Direction, wrap: bool) {
+        self.shift_selected(direction, self.grid.sub_square_size(), wrap);
+    }
+
+    fn shift_selected(&mut self, direction: Direction, distance: usize, wrap: bool) {
+        let size = self.size() as isize;
+        let distance = distance as isize;
+        let (coordinate, delta) = match direction {
+            Direction::Left => (&mut self.selected.0, -distance),
+            Direction::Right => (&mut self.selected.0, distance),
+            Direction::Up => (&mut self.selected.1, -distance),
+            Direction::Down => (&mut self.selected.1, distance),
+        };
+        let target = *coordinate as isize + delta;
+        if (0..size).contains(&target) {
+            *coordinate = target as usize;
+        } else if wrap {
+            *coordinate = target.rem_euclid(size) as usize;
+        }
+    }
+
+    /// Like `add_entry`, but flashes the selected cell when the entry is rejected
+    pub fn add_entry_at_selected(&mut self, value: usize) -> Result<Entry, GridError> {
+        let result = self.add_entry(self.selected, value);
+        if result.is_err() {
+            self.rejected = Some((self.selected, Instant::now()));
+        }
+        result
+    }
+
+    /// Like `add_entry_at_selected`, but also rejects a value the cell's row,
+    /// column, box or diagonal already holds
+    pub fn add_strict_entry_at_selected(&mut self, value: usize) -> Result<Entry, GridError> {
+        if value != 0 && !self.candidates_for_cell(self.selected)?.contains(&value) {
+            self.rejected = Some((self.selected, Instant::now()));
+            return Err(GridError::ConflictingValue(value));
+        }
+        self.add_entry_at_selected(value)
+    }
+
+    /// Add or remove a pencil mark in a cell that isn't a given
+    pub fn toggle_note(&mut self, position: GridPosition, value: usize) -> Result<(), GridError> {
+        if self.grid.is_readonly(position)? {
+            return Err(GridError::ReadonlyCellMutation);
+        }
+        if value == 0 || value > self.size() {
+            return Err(GridError::InvalidCellValue(value));
+        }
+        let notes = self.notes.entry(position).or_default();
+        if !notes.remove(&value) {
+            notes.insert(value);
+        }
+        Ok(())
+    }
+
+    /// Like `toggle_note`, but flashes the selected cell when it's rejected
+    pub fn toggle_note_at_selected(&mut self, value: usize) -> Result<(), GridError> {
+        let result = self.toggle_note(self.selected, value);
+        if result.is_err() {
+            self.rejected = Some((self.selected, Instant::now()));
+        }
+        result
+    }
+
+    /// Pencil in every candidate of every empty cell, replacing any marks
+    pub fn fill_notes(&mut self) {
+        self.notes.clear();
+        let size = self.size();
+        for position in (0..size).flat_map(|y| (0..size).map(move |x| (x, y))) {
+            if self.grid.get_cell(position) == Ok(0) {
+                let candidates = self.candidates_for_cell(position).unwrap();
+                self.notes
+                    .insert(position, candidates.into_iter().collect());
             }
-            Direction::Right => {
-                if self.selected.0 < 8 {
-                    self.selected.0 += 1;
-                }
+        }
+    }
+
+    /// The empty cells `value` can't go in with the values placed so far
+    pub fn ruled_out_cells(&self, value: usize) -> HashSet<GridPosition> {
+        let size = self.size();
+        (0..size)
+            .flat_map(|y| (0..size).map(move |x| (x, y)))
+            .filter(|&position| self.grid.get_cell(position) == Ok(0))
+            .filter(|&position| !self.candidates_for_cell(position).unwrap().contains(&value))
+            .collect()
+    }
+
+    /// Rub out `value` from the pencil marks of every cell sharing a row,
+    /// column, box or diagonal with `position`
+    pub fn remove_peer_notes(&mut self, position: GridPosition, value: usize) {
+        let peers: Vec<GridPosition> = self
+            .subsections()
+            .into_iter()
+            .map(|subsection| subsection.grid_subsection)
+            .filter(|cells| cells.clone().any(|cell| cell == position))
+            .flatten()
+            .collect();
+        for peer in peers {
+            if let Some(notes) = self.notes.get_mut(&peer) {
+                notes.remove(&value);
             }
-            Direction::Up => {
-                if self.selected.1 > 0 {
-                    self.selected.1 = (self.selected.1 as isize - 1) as usize;
-                }
+        }
+    }
+
+    pub fn clear_notes(&mut self, position: GridPosition) {
+        self.notes.remove(&position);
+    }
+
+    pub fn notes(&self, position: GridPosition) -> Option<&BTreeSet<usize>> {
+        self.notes.get(&position).filter(|notes| !notes.is_empty())
+    }
+
+    /// Every cell with pencil marks, in no particular order
+    pub fn all_notes(&self) -> impl Iterator<Item = (GridPosition, &BTreeSet<usize>)> {
+        self.notes
+            .iter()
+            .filter(|(_, notes)| !notes.is_empty())
+            .map(|(&position, notes)| (position, notes))
+    }
+
+    /// Pick up a game left unfinished: fill in its values without recording
+    /// them as entries, and carry on its clock from `elapsed`
+    pub fn resume_from(&mut self, values: &[usize], elapsed: Duration) -> Result<(), GridError> {
+        let size = self.size();
+        for (i, &value) in values.iter().enumerate() {
+            let position = (i % size, i / size);
+            if !self.grid.is_readonly(position)? {
+                self.grid.set_cell(position, value)?;
             }
-            Direction::Down => {
-                if self.selected.1 < 8 {
-                    self.selected.1 += 1;
-                }
+        }
+        self.timer = Timer::resumed_from(elapsed);
+        self.apply_checker();
+        Ok(())
+    }
+
+    /// Give up and fill in the `solution`, stopping the clock. The game is
+    /// over, but not won.
+    pub fn reveal(&mut self, solution: &[usize]) -> Result<(), GridError> {
+        if self.status != GameStatus::InProgress {
+            return Ok(());
+        }
+        self.status = GameStatus::Revealed;
+        self.paused = false;
+        self.timer.pause();
+        let size = self.size();
+        for (i, &value) in solution.iter().enumerate() {
+            let position = (i % size, i / size);
+            if !self.grid.is_readonly(position)? {
+                self.grid.set_cell(position, value)?;
             }
         }
+        self.apply_checker();
+        Ok(())
+    }
+
+    /// Entries that can be undone, oldest first
+    pub fn entries(&self) -> &History {
+        &self.entries
+    }
+
+    /// When each box was finished, in reading order: the clock at the entry
+    /// that first left it full of its values in the solution, or `None` for
+    /// a box that was given whole. Only a won game has a solution to go by,
+    /// so these are the splits of its solve, and every box is `None` before.
+    pub fn box_splits(&self) -> Vec<Option<Duration>> {
+        let (size, box_size) = (self.size(), self.box_size());
+        let mut splits = vec![None; size];
+        if self.status != GameStatus::Won {
+            return splits;
+        }
+        let box_of = |index: usize| index / size / box_size * box_size + index % size / box_size;
+        let solution = self.values();
+        let mut values = self.givens();
+        for entry in self.entries.iter() {
+            let (x, y) = entry.position;
+            values[y * size + x] = entry.value;
+            let square = box_of(y * size + x);
+            let finished = (0..size * size)
+                .filter(|&index| box_of(index) == square)
+                .all(|index| values[index] == solution[index]);
+            if finished && splits[square].is_none() {
+                splits[square] = Some(entry.at);
+            }
+        }
+        splits
+    }
+
+    /// Bring back the entries of a resumed game, so they can be undone and
+    /// show in the history panel. The grid is expected to hold their values
+    /// already.
+    pub fn restore_entries(&mut self, entries: History) -> Result<(), GridError> {
+        for entry in entries.iter() {
+            if self.grid.is_readonly(entry.position)? {
+                return Err(GridError::ReadonlyCellMutation);
+            }
+            if entry.value.max(entry.previous_value) > self.size() {
+                return Err(GridError::InvalidCellValue(
+                    entry.value.max(entry.previous_value),
+                ));
+            }
+        }
+        self.events.clear();
+        for entry in entries.iter() {
+            self.record(GameEvent::Entry(entry));
+        }
+        self.entries = entries;
+        self.undone.clear();
+        Ok(())
     }
 
-    pub fn add_entry_at_selected(&mut self, value: usize) {
-        self.add_entry(self.selected, value).unwrap();
+    /// The cell to flash, while the last rejected input is still recent
+    pub fn flashed(&self) -> Option<GridPosition> {
+        self.rejected
+            .filter(|(_, at)| at.elapsed() < FLASH_DURATION)
+            .map(|(position, _)| position)
     }
 
+    /// The cell of the last rejected input, however long ago it was
+    pub fn rejected(&self) -> Option<GridPosition> {
+        self.rejected.map(|(position, _)| position)
+    }
+
+    pub fn clear_rejected(&mut self) {
+        self.rejected = None;
+    }
+
+    /// Check the whole board, as when it's set up or its rules change
     fn apply_checker(&mut self) {
-        self.invalid_subsections = Vec::new();
-        self.is_complete = true;
-        for (subsection_type, CheckerResult { valid, complete }) in self
-            .checker
-            .check_subsections(&self.grid.get_all_subsection_values())
-        {
-            if !complete {
-                self.is_complete = false;
+        self.tracker = ConflictTracker::new(&self.grid, self.regions);
+        self.invalid_cages = (0..self.cages.len())
+            .filter(|&i| !self.is_cage_valid(i))
+            .collect();
+        self.invalid_constraints = (0..self.constraints.len())
+            .filter(|&i| !self.is_constraint_valid(i))
+            .collect();
+        self.apply_conflicts();
+    }
+
+    /// Check only what a change to the cell at `position` could affect: its
+    /// own subsections, cages and constraints
+    fn apply_change(&mut self, position: GridPosition, previous: usize) {
+        self.tracker.update(&self.grid, position, previous);
+        for i in 0..self.cages.len() {
+            if self.cages[i].cells.contains(&position) {
+                let valid = self.is_cage_valid(i);
+                mark_invalid(&mut self.invalid_cages, i, !valid);
             }
-            if !valid {
-                self.invalid_subsections.push(subsection_type);
+        }
+        for i in 0..self.constraints.len() {
+            if self.constraints[i].cells().contains(&position) {
+                let valid = self.is_constraint_valid(i);
+                mark_invalid(&mut self.invalid_constraints, i, !valid);
             }
         }
+        self.apply_conflicts();
+    }
+
+    fn is_cage_valid(&mut self, i: usize) -> bool {
+        let cage = &self.cages[i];
+        let values: Vec<usize> = cage
+            .cells
+            .iter()
+            .map(|&position| self.grid.get_cell(position).unwrap())
+            .collect();
+        self.checker.check_cage(cage, &values).valid
+    }
+
+    fn is_constraint_valid(&mut self, i: usize) -> bool {
+        let constraint = &self.constraints[i];
+        let values: Vec<usize> = constraint
+            .cells()
+            .into_iter()
+            .map(|position| self.grid.get_cell(position).unwrap())
+            .collect();
+        self.checker.check_constraint(constraint, &values).valid
+    }
+
+    /// Gather the conflicts from the tracker and the broken cages and
+    /// constraints, and end the game once it's solved
+    fn apply_conflicts(&mut self) {
+        self.invalid_subsections = self.tracker.invalid_subsections().collect();
+        self.conflicts = self.tracker.conflicts().collect();
+        for &i in &self.invalid_cages {
+            self.conflicts.extend(&self.cages[i].cells);
+        }
+        for &i in &self.invalid_constraints {
+            self.conflicts.extend(self.constraints[i].cells());
+        }
+        if self.is_correct() && self.status == GameStatus::InProgress {
+            self.status = GameStatus::Won;
+            self.timer.pause();
+        }
+    }
+
+    fn record(&mut self, event: GameEvent) {
+        if self.events.len() == HISTORY_LIMIT {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+        self.recorded += 1;
+    }
+
+    /// Recent entries and undos, oldest first
+    pub fn events(&self) -> &VecDeque<GameEvent> {
+        &self.events
+    }
+
+    /// The events recorded since `count` had been, oldest first, as far as
+    /// they're still remembered
+    pub fn events_since(&self, count: usize) -> impl Iterator<Item = &GameEvent> {
+        let new = self.recorded.saturating_sub(count).min(self.events.len());
+        self.events.range(self.events.len() - new..)
+    }
+
+    /// How many events have been recorded since the game began
+    pub fn events_recorded(&self) -> usize {
+        self.recorded
     }
 
     pub fn undo_entry(&mut self) -> Option<Entry> {
         let entry = self.entries.pop()?;
+        self.undone.push(entry);
+        self.partner.remove(&entry.position);
+        self.record(GameEvent::Undo(entry));
         self.grid
             .set_cell(entry.position, entry.previous_value)
             .unwrap();
-        self.apply_checker();
+        self.apply_change(entry.position, entry.value);
+        self.selected = entry.position;
+        Some(entry)
+    }
+
+    /// Make the most recently undone entry again
+    pub fn redo_entry(&mut self) -> Option<Entry> {
+        let entry = self.undone.pop()?;
+        self.entries.push(entry);
+        self.partner.remove(&entry.position);
+        self.record(GameEvent::Redo(entry));
+        self.grid.set_cell(entry.position, entry.value).unwrap();
+        self.apply_change(entry.position, entry.previous_value);
         self.selected = entry.position;
         Some(entry)
     }
@@ -121,23 +748,36 @@ impl Game {
         if previous_value == 0 {
             return Ok(());
         }
-        self.entries.push(Entry {
+        let entry = Entry {
             position,
             value: 0,
             previous_value,
-        });
+            at: self.timer.elapsed(),
+        };
+        self.entries.push(entry);
+        self.undone.clear();
+        self.record(GameEvent::Entry(entry));
+        self.apply_change(position, previous_value);
         Ok(())
     }
 
-    pub fn get_rows(&self) -> Vec<GridSubsectionValues> {
+    /// Every row, column and box, plus the diagonals in an X-Sudoku or the
+    /// windows in a Windoku
+    pub fn subsections(&self) -> Vec<GridSubsectionValues<'_>> {
+        let mut subsections = self.grid.get_all_subsection_values();
+        subsections.extend(self.regions.subsections(&self.grid));
+        subsections
+    }
+
+    pub fn get_rows(&self) -> Vec<GridSubsectionValues<'_>> {
         self.grid.get_row_values()
     }
 
-    pub fn get_columns(&self) -> Vec<GridSubsectionValues> {
+    pub fn get_columns(&self) -> Vec<GridSubsectionValues<'_>> {
         self.grid.get_column_values()
     }
 
-    pub fn get_square(&self) -> Vec<GridSubsectionValues> {
+    pub fn get_square(&self) -> Vec<GridSubsectionValues<'_>> {
         self.grid.get_square_values()
     }
 
@@ -145,14 +785,176 @@ impl Game {
         self.grid.size()
     }
 
+    /// Side length of the grid's boxes
+    pub fn box_size(&self) -> usize {
+        self.grid.sub_square_size()
+    }
+
+    pub fn candidates_for_cell(&self, position: GridPosition) -> Result<Vec<usize>, GridError> {
+        let mut candidates = self.grid.candidates_for_cell(position)?;
+        for region in self.regions.subsections_of(&self.grid, position) {
+            let cells: Vec<(GridPosition, usize)> = region.grid_subsection.zip(region).collect();
+            candidates.retain(|value| {
+                !cells
+                    .iter()
+                    .any(|(other, used)| *other != position && used == value)
+            });
+        }
+        // and whatever would break a constraint with the rest as they are
+        let mut checker = Checker::new();
+        for constraint in &self.constraints {
+            let cells = constraint.cells();
+            if !cells.contains(&position) {
+                continue;
+            }
+            candidates.retain(|&value| {
+                let values: Vec<usize> = cells
+                    .iter()
+                    .map(|&other| match other == position {
+                        true => value,
+                        false => self.grid.get_cell(other).unwrap(),
+                    })
+                    .collect();
+                checker.check_constraint(constraint, &values).valid
+            });
+        }
+        Ok(candidates)
+    }
+
+    pub fn givens(&self) -> Vec<usize> {
+        self.grid.givens()
+    }
+
+    /// Every cell's current value, givens and entries alike
+    pub fn values(&self) -> Vec<usize> {
+        self.grid.values()
+    }
+
+    /// A new game with this one's current values as its givens, keeping the
+    /// cages, extra regions and constraints
+    pub fn snapshot(&self) -> Game {
+        Game::new(self.grid.values())
+            .unwrap()
+            .with_cages(self.cages.clone())
+            .with_regions(self.regions)
+            .with_constraints(self.constraints.clone())
+    }
+
+    pub fn digit_counts(&self) -> Vec<usize> {
+        self.grid.digit_counts()
+    }
+
+    pub fn status(&self) -> GameStatus {
+        self.status
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        if self.status == GameStatus::InProgress {
+            self.paused = true;
+            self.timer.pause();
+        }
+    }
+
+    /// Pause a game left without input for `idle`, taking that time off the
+    /// clock
+    pub fn pause_idle(&mut self, idle: Duration) {
+        if self.status == GameStatus::InProgress && !self.paused {
+            self.pause();
+            self.timer.rewind(idle);
+            self.idle += idle;
+        }
+    }
+
+    /// Time left off the clock for going without input
+    pub fn idle_time(&self) -> Duration {
+        self.idle
+    }
+
+    pub fn resume(&mut self) {
+        if self.paused {
+            self.paused = false;
+            self.timer.resume();
+        }
+    }
+
+    /// Points for a won game: a base per difficulty, less penalties for
+    /// mistakes, hints and every ten seconds taken
+    pub fn score(&self) -> usize {
+        let base: usize = match self.difficulty {
+            Some(Difficulty::Easy) | None => 1000,
+            Some(Difficulty::Medium) => 2000,
+            Some(Difficulty::Hard) => 3000,
+            Some(Difficulty::Expert) => 4000,
+        };
+        let penalty =
+            self.mistakes * 50 + self.hints_used * 100 + self.elapsed().as_secs() as usize / 10;
+        base.saturating_sub(penalty)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.timer.elapsed()
+    }
+
+    pub fn filled_count(&self) -> usize {
+        self.get_rows()
+            .into_iter()
+            .flat_map(|row| row.filter(|value| *value != 0))
+            .count()
+    }
+
+    /// Whether the cell at `position` holds one of the puzzle's givens
+    pub fn is_given(&self, position: GridPosition) -> bool {
+        self.grid.is_readonly(position).unwrap_or(false)
+    }
+
+    pub fn get_cell(&self, position: GridPosition) -> Result<usize, GridError> {
+        self.grid.get_cell(position)
+    }
+
+    #[cfg(feature = "tui")]
+    pub fn position_at(&self, area: Rect, column: u16, row: u16) -> Option<GridPosition> {
+        self.grid.position_at(area, self.selected, column, row)
+    }
+
+    #[cfg(feature = "tui")]
+    pub fn render_size(&self, scale: CellScale) -> (u16, u16) {
+        self.grid.render_size(scale)
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.size() * self.size()
+    }
+
+    /// Whether no subsection, cage or constraint is broken
+    pub fn is_valid(&self) -> bool {
+        self.invalid_subsections.is_empty()
+            && self.invalid_cages.is_empty()
+            && self.invalid_constraints.is_empty()
+    }
+
     pub fn is_correct(&self) -> bool {
-        self.is_complete && self.invalid_subsections.is_empty()
+        self.tracker.is_complete() && self.is_valid()
     }
 
     pub fn reset(&mut self) {
         self.grid.reset();
-        self.is_complete = false;
         self.entries.clear();
+        self.undone.clear();
+        self.notes.clear();
+        self.events.clear();
+        self.recorded = 0;
+        self.partner.clear();
+        self.mistakes = 0;
+        self.zen = false;
+        self.hints_used = 0;
+        self.status = GameStatus::InProgress;
+        self.paused = false;
+        self.timer = Timer::start();
+        self.idle = Duration::ZERO;
         self.apply_checker();
     }
 }
@@ -163,19 +965,766 @@ impl Display for Game {
     }
 }
 
-impl Widget for &Game {
-    fn render(self, area: Rect, buf: &mut Buffer) {
+#[cfg(feature = "tui")]
+impl StatefulWidget for &Game {
+    type State = Appearance;
+
+    fn render(self, area: Rect, buf: &mut Buffer, appearance: &mut Self::State) {
+        self.render_with_highlights(area, buf, *appearance, HashMap::new());
+    }
+}
+
+#[cfg(feature = "tui")]
+impl Game {
+    pub fn render_with_highlights(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        appearance: Appearance,
+        highlights: HashMap<GridPosition, Color>,
+    ) {
+        self.render_with_assist(area, buf, appearance, highlights, Assist::Off, None);
+    }
+
+    /// Draw the board with candidate elimination laid over it as far as
+    /// `assist` goes, `armed` being the digit picked in number-first input
+    pub fn render_with_assist(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        appearance: Appearance,
+        highlights: HashMap<GridPosition, Color>,
+        assist: Assist,
+        armed: Option<usize>,
+    ) {
+        let ruled_out_notes = match (assist, self.notes(self.selected)) {
+            (Assist::Off, _) | (_, None) => vec![],
+            (_, Some(notes)) => {
+                let candidates = self.candidates_for_cell(self.selected).unwrap();
+                notes
+                    .iter()
+                    .copied()
+                    .filter(|note| !candidates.contains(note))
+                    .collect()
+            }
+        };
+        let greyed = match (assist, armed) {
+            (Assist::Cells, Some(digit)) => self.ruled_out_cells(digit),
+            _ => HashSet::new(),
+        };
         let mut state = GridState {
-            selected: self.selected.clone(),
-            subsections: self.invalid_subsections.clone(),
+            selected: self.selected,
+            conflicts: &self.conflicts,
+            highlights,
+            flashed: match appearance.reduced_motion {
+                true => self.rejected(),
+                false => self.flashed(),
+            },
+            cages: &self.cages,
+            constraints: &self.constraints,
+            regions: self.regions,
+            notes: &self.notes,
+            ruled_out_notes,
+            greyed,
+            partner: &self.partner,
+            appearance,
+            cache: &self.render_cache,
         };
         self.grid.render(area, buf, &mut state);
     }
 }
 
+#[cfg(feature = "tui")]
+pub struct StatusBar<'a> {
+    pub game: &'a Game,
+    /// Digit keys toggle pencil marks instead of placing values
+    pub notes_mode: bool,
+}
+
+#[cfg(feature = "tui")]
+impl Widget for StatusBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let game = self.game;
+        let difficulty = match game.difficulty {
+            Some(difficulty) => difficulty.to_string(),
+            None => "Custom".to_string(),
+        };
+        let state = match (game.status(), game.is_paused()) {
+            (GameStatus::Revealed, _) => " (revealed)",
+            (GameStatus::Lost, _) => " (game over)",
+            (_, true) => " (paused)",
+            _ => "",
+        };
+        let mode = if self.notes_mode { "Notes" } else { "Values" };
+        let mistakes = match game.mistake_limit {
+            Some(limit) => format!("{}/{limit}", game.mistakes),
+            None => game.mistakes.to_string(),
+        };
+        Line::from(format!(
+            "Time {}{state} | {mode} | {} {} | Mistakes {mistakes} | Hints {} | {}/{}",
+            format_duration(game.elapsed()),
+            game.variant_name(),
+            difficulty,
+            game.hints_used,
+            game.filled_count(),
+            game.cell_count(),
+        ))
+        .centered()
+        .render(area, buf);
+    }
+}
+
+#[cfg(feature = "tui")]
+pub struct DigitPanel<'a> {
+    pub game: &'a Game,
+    pub appearance: Appearance,
+    /// The digit picked in number-first input, highlighted in the list
+    pub armed: Option<usize>,
+    /// Dims the digits the selected cell can't take unless it's off
+    pub assist: Assist,
+}
+
+#[cfg(feature = "tui")]
+impl DigitPanel<'_> {
+    pub const WIDTH: u16 = 14;
+
+    /// The digit listed at a terminal row when the panel is rendered into `area`
+    pub fn digit_at(area: Rect, size: usize, column: u16, row: u16) -> Option<usize> {
+        let inside = column > area.x && column < area.right().saturating_sub(1);
+        let digit = (row as usize).checked_sub(area.y as usize)?;
+        (inside && (1..=size).contains(&digit)).then_some(digit)
+    }
+}
+
+#[cfg(feature = "tui")]
+impl Widget for DigitPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let size = self.game.size();
+        let position = self.game.selected;
+        let candidates = match self.game.get_cell(position) {
+            Ok(0) if self.assist != Assist::Off => self.game.candidates_for_cell(position).ok(),
+            _ => None,
+        };
+        let lines: Vec<Line> = self
+            .game
+            .digit_counts()
+            .into_iter()
+            .enumerate()
+            .skip(1)
+            .map(|(digit, count)| {
+                let remaining = size.saturating_sub(count);
+                let symbol = value_symbol(digit);
+                let marker = if self.armed == Some(digit) { '>' } else { ' ' };
+                let ruled_out = candidates
+                    .as_ref()
+                    .is_some_and(|candidates| !candidates.contains(&digit));
+                let line = if remaining == 0 {
+                    Line::styled(
+                        format!("{marker} {symbol}  done"),
+                        self.appearance.muted_style(),
+                    )
+                } else if ruled_out {
+                    Line::styled(
+                        format!("{marker} {symbol}  {remaining} left"),
+                        self.appearance.muted_style(),
+                    )
+                } else {
+                    Line::from(format!("{marker} {symbol}  {remaining} left"))
+                };
+                match self.armed == Some(digit) {
+                    true => line.patch_style(self.appearance.selected_style()),
+                    false => line,
+                }
+            })
+            .collect();
+        let title = match self.armed {
+            Some(digit) => format!(" Placing {} ", value_symbol(digit)),
+            None => " Digits ".to_string(),
+        };
+        Paragraph::new(lines)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .render(area, buf);
+    }
+}
+
+#[cfg(feature = "tui")]
+/// Legal values for the selected cell, laid out like the cells of a box
+pub struct CandidatePanel<'a> {
+    pub game: &'a Game,
+    pub appearance: Appearance,
+    /// Dims the pencil marks that are ruled out unless it's off
+    pub assist: Assist,
+}
+
+#[cfg(feature = "tui")]
+impl CandidatePanel<'_> {
+    pub fn height(game: &Game) -> u16 {
+        game.grid.sub_square_size() as u16 + 2
+    }
+}
+
+#[cfg(feature = "tui")]
+impl Widget for CandidatePanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let game = self.game;
+        let position = game.selected;
+        let lines: Vec<Line> = match game.get_cell(position) {
+            Ok(0) => {
+                let candidates = game.candidates_for_cell(position).unwrap_or_default();
+                let notes = game.notes(position);
+                let box_size = game.grid.sub_square_size();
+                (0..box_size)
+                    .map(|row| {
+                        let spans = (1..=box_size)
+                            .map(|column| row * box_size + column)
+                            .flat_map(|value| {
+                                // noted values stand out, even once they're ruled out,
+                                // unless the assist is on to point those out
+                                let noted = notes.is_some_and(|notes| notes.contains(&value));
+                                let symbol = match noted || candidates.contains(&value) {
+                                    true => value_symbol(value).to_string(),
+                                    false => "·".to_string(),
+                                };
+                                let ruled_out =
+                                    self.assist != Assist::Off && !candidates.contains(&value);
+                                let span = match noted {
+                                    true if ruled_out => {
+                                        Span::styled(symbol, self.appearance.muted_style())
+                                    }
+                                    true => Span::styled(symbol, self.appearance.selected_style()),
+                                    false => Span::raw(symbol),
+                                };
+                                [Span::raw(" "), span]
+                            })
+                            .skip(1);
+                        Line::from(spans.collect::<Vec<_>>())
+                    })
+                    .collect()
+            }
+            _ => vec![Line::styled("filled", self.appearance.muted_style())],
+        };
+        Paragraph::new(lines)
+            .centered()
+            .block(Block::default().title(" Candidates ").borders(Borders::ALL))
+            .render(area, buf);
+    }
+}
+
+#[cfg(feature = "tui")]
+/// Recent events, newest first, scrolled back `scroll` events
+pub struct HistoryPanel<'a> {
+    pub game: &'a Game,
+    pub appearance: Appearance,
+    pub scroll: usize,
+}
+
+#[cfg(feature = "tui")]
+impl Widget for HistoryPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let lines: Vec<Line> = self
+            .game
+            .events()
+            .iter()
+            .rev()
+            .skip(self.scroll)
+            .map(|event| match event {
+                GameEvent::Undo(_) => {
+                    Line::styled(event.to_string(), self.appearance.muted_style())
+                }
+                GameEvent::Partner(_) => {
+                    Line::styled(event.to_string(), self.appearance.partner_style())
+                }
+                GameEvent::Entry(_) | GameEvent::Redo(_) => Line::from(event.to_string()),
+            })
+            .collect();
+        Paragraph::new(lines)
+            .block(Block::default().title(" History ").borders(Borders::ALL))
+            .render(area, buf);
+    }
+}
+
+/// Keep `i` in the sorted list of broken indices exactly when `invalid`
+fn mark_invalid(broken: &mut Vec<usize>, i: usize, invalid: bool) {
+    match broken.binary_search(&i) {
+        Ok(index) if !invalid => {
+            broken.remove(index);
+        }
+        Err(index) if invalid => broken.insert(index, i),
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    fn game() -> Game {
+        Game::new(vec![
+            4, 6, 7, 1, 0, 0, 8, 0, 5, // row 0
+            9, 1, 2, 8, 3, 5, 6, 0, 7, // row 1
+            0, 8, 5, 6, 4, 7, 1, 9, 2, // row 2
+            2, 9, 6, 3, 5, 1, 4, 7, 0, // row 3
+            7, 0, 8, 9, 2, 0, 3, 5, 1, // row 4
+            5, 3, 1, 4, 0, 8, 9, 2, 6, // row 5
+            0, 7, 3, 0, 6, 4, 5, 1, 0, // row 6
+            6, 2, 4, 5, 1, 9, 7, 8, 3, // row 7
+            1, 5, 9, 7, 8, 3, 0, 6, 4, // row 8
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn counts_mistakes() {
+        let mut game = game();
+        game.add_entry((4, 0), 9).unwrap();
+        assert_eq!(game.mistakes, 0);
+        game.add_entry((5, 0), 4).unwrap();
+        assert_eq!(game.mistakes, 1);
+        assert!(game.conflicts.contains(&(5, 0)));
+        game.add_entry((5, 0), 0).unwrap();
+        assert_eq!(game.mistakes, 1);
+        assert!(game.conflicts.is_empty());
+    }
+
+    #[test]
+    fn progress() {
+        let mut game = game();
+        assert_eq!(game.cell_count(), 81);
+        assert_eq!(game.filled_count(), 68);
+        game.add_entry((4, 0), 9).unwrap();
+        assert_eq!(game.filled_count(), 69);
+        game.reset();
+        assert_eq!(game.filled_count(), 68);
+    }
+
+    #[test]
+    fn transitions_to_won() {
+        let mut game = game();
+        for (position, value) in [
+            ((4, 0), 9),
+            ((5, 0), 2),
+            ((7, 0), 3),
+            ((7, 1), 4),
+            ((0, 2), 3),
+            ((8, 3), 8),
+            ((1, 4), 4),
+            ((5, 4), 6),
+            ((4, 5), 7),
+            ((0, 6), 8),
+            ((3, 6), 2),
+            ((8, 6), 9),
+        ] {
+            game.add_entry(position, value).unwrap();
+            assert_eq!(game.status(), GameStatus::InProgress);
+        }
+        game.add_entry((6, 8), 2).unwrap();
+        assert_eq!(game.status(), GameStatus::Won);
+        let elapsed = game.elapsed();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(game.elapsed(), elapsed);
+        assert_eq!(game.score(), 1000);
+        game.reset();
+        assert_eq!(game.status(), GameStatus::InProgress);
+    }
+
+    #[test]
+    fn reveals_the_solution() {
+        let mut game = game();
+        let solution = crate::solver::solve_game(&game).unwrap();
+        game.add_entry((4, 0), 3).unwrap();
+        game.reveal(&solution).unwrap();
+        assert_eq!(game.status(), GameStatus::Revealed);
+        assert_eq!(game.values(), solution);
+        assert!(game.is_correct());
+        let elapsed = game.elapsed();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(game.elapsed(), elapsed);
+    }
+
+    #[test]
+    fn box_splits() {
+        let mut game = game();
+        game.add_entry((4, 0), 9).unwrap();
+        assert!(game.box_splits().iter().all(Option::is_none));
+        // the middle box is filled wrongly, then put right, then emptied and
+        // filled again
+        game.add_entry((5, 4), 6).unwrap();
+        game.add_entry((4, 5), 1).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        game.add_entry((4, 5), 7).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        game.add_entry((5, 4), 0).unwrap();
+        game.add_entry((5, 4), 6).unwrap();
+        for (position, value) in [
+            ((5, 0), 2),
+            ((7, 0), 3),
+            ((7, 1), 4),
+            ((0, 2), 3),
+            ((8, 3), 8),
+            ((1, 4), 4),
+            ((0, 6), 8),
+            ((3, 6), 2),
+            ((8, 6), 9),
+            ((6, 8), 2),
+        ] {
+            game.add_entry(position, value).unwrap();
+        }
+        assert_eq!(game.status(), GameStatus::Won);
+        let splits = game.box_splits();
+        assert_eq!(splits.len(), 9);
+        let refilled = game.entries().iter().map(|entry| entry.at).nth(5);
+        assert!(splits[4] >= Some(Duration::from_millis(5)));
+        assert!(splits[4] < refilled);
+        assert_eq!(splits.iter().flatten().count(), 9);
+    }
+
+    #[test]
+    fn shuffles_puzzles() {
+        let mut rng = Rng::new(3);
+        for puzzle in &crate::library::PUZZLES {
+            let game = puzzle.game().unwrap();
+            let shuffled = game.shuffled(&mut rng);
+            assert_eq!(
+                canonical_form(&shuffled.givens()),
+                canonical_form(&game.givens())
+            );
+            assert_eq!(shuffled.variant_name(), game.variant_name());
+            assert_eq!(shuffled.difficulty, game.difficulty);
+            assert!(crate::solver::solve_game(&shuffled).is_some());
+        }
+    }
+
+    #[test]
+    fn mistake_limit() {
+        let mut game = game();
+        game.set_mistake_limit(Some(2));
+        game.add_entry((4, 0), 4).unwrap();
+        assert_eq!(game.status(), GameStatus::InProgress);
+        game.add_entry((4, 0), 6).unwrap();
+        assert_eq!(game.status(), GameStatus::Lost);
+        let elapsed = game.elapsed();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(game.elapsed(), elapsed);
+
+        game.continue_in_zen();
+        assert_eq!(game.status(), GameStatus::InProgress);
+        assert!(game.is_zen() && game.mistake_limit().is_none());
+        game.add_entry((4, 0), 7).unwrap();
+        assert_eq!(game.status(), GameStatus::InProgress);
+        // starting over counts again
+        game.reset();
+        assert!(!game.is_zen());
+        assert_eq!(game.events_recorded(), 0);
+
+        // a limit already passed ends the game straight away
+        let mut game = Game::new(game.givens()).unwrap();
+        game.add_entry((4, 0), 4).unwrap();
+        game.set_mistake_limit(Some(1));
+        assert_eq!(game.status(), GameStatus::Lost);
+        game.reset();
+        assert_eq!(game.status(), GameStatus::InProgress);
+        assert_eq!(game.mistake_limit(), Some(1));
+    }
+
+    #[test]
+    fn checks_cages() {
+        let mut game = game().with_cages(vec![Cage {
+            sum: 10,
+            cells: vec![(4, 0), (5, 0)],
+        }]);
+        game.add_entry((4, 0), 9).unwrap();
+        assert!(game.is_valid());
+        game.add_entry((5, 0), 2).unwrap();
+        assert_eq!(game.invalid_cages, vec![0]);
+        assert!(game.conflicts.contains(&(4, 0)) && game.conflicts.contains(&(5, 0)));
+        game.undo_entry();
+        assert!(game.is_valid());
+    }
+
+    #[test]
+    fn checks_diagonals() {
+        let mut game = Game::new(vec![0; 16]).unwrap().with_diagonals();
+        game.add_entry((0, 0), 1).unwrap();
+        assert_eq!(game.candidates_for_cell((2, 2)), Ok(vec![2, 3, 4]));
+        assert_eq!(game.candidates_for_cell((3, 1)), Ok(vec![1, 2, 3, 4]));
+        game.add_entry((3, 3), 1).unwrap();
+        assert_eq!(game.invalid_subsections, vec![GridSubsectionType::Diagonal]);
+        assert!(game.conflicts.contains(&(0, 0)) && game.conflicts.contains(&(3, 3)));
+    }
+
+    #[test]
+    fn checks_arrows() {
+        let arrow = Constraint::parse("arrow r1c3 r2c2 r3c1", 9).unwrap();
+        let mut game = Game::new(vec![0; 81])
+            .unwrap()
+            .with_constraints(vec![arrow]);
+        game.add_entry((2, 0), 4).unwrap();
+        assert_eq!(game.candidates_for_cell((1, 1)), Ok(vec![1, 2, 3]));
+        game.add_entry((1, 1), 3).unwrap();
+        assert_eq!(game.candidates_for_cell((0, 2)), Ok(vec![1]));
+        game.add_entry((0, 2), 2).unwrap();
+        assert_eq!(game.invalid_constraints, vec![0]);
+        assert!(game.conflicts.contains(&(2, 0)) && !game.is_valid());
+        game.add_entry((0, 2), 1).unwrap();
+        assert!(game.is_valid());
+        assert_eq!(game.snapshot().constraints(), game.constraints());
+    }
+
+    #[test]
+    fn checks_windows() {
+        let mut game = Game::new(vec![0; 81]).unwrap().with_windows();
+        assert!(game.has_windows() && !game.has_diagonals());
+        game.add_entry((1, 1), 1).unwrap();
+        // (3, 3) shares the window, but not a row, column or box
+        assert_eq!(game.candidates_for_cell((3, 3)), Ok((2..=9).collect()));
+        assert_eq!(game.candidates_for_cell((6, 6)), Ok((1..=9).collect()));
+        game.add_entry((3, 3), 1).unwrap();
+        assert_eq!(
+            game.invalid_subsections,
+            vec![GridSubsectionType::Window(0, 0)]
+        );
+        assert!(game.snapshot().has_windows());
+    }
+
+    #[test]
+    fn variant_name() {
+        assert_eq!(game().variant_name(), "Classic");
+        assert_eq!(game().with_diagonals().variant_name(), "X-Sudoku");
+        let both = Regions {
+            diagonals: true,
+            windows: true,
+        };
+        assert_eq!(game().with_regions(both).variant_name(), "Custom");
+        let game = crate::library::PUZZLES[10].game().unwrap();
+        assert_eq!(game.variant_name(), "Arrow");
+    }
+
+    #[test]
+    fn records_events() {
+        let mut game = game();
+        game.add_entry((4, 0), 9).unwrap();
+        game.add_entry((4, 0), 0).unwrap();
+        game.undo_entry();
+        let events: Vec<String> = game
+            .events()
+            .iter()
+            .map(|event| event.to_string())
+            .collect();
+        assert_eq!(events, ["r1c5 ← 9", "clear r1c5", "undo r1c5"]);
+        for _ in 0..HISTORY_LIMIT {
+            game.add_entry((4, 0), 9).unwrap();
+        }
+        assert_eq!(game.events().len(), HISTORY_LIMIT);
+        game.reset();
+        assert!(game.events().is_empty());
+    }
+
+    #[test]
+    fn redoes_entries() {
+        let mut game = game();
+        game.add_entry((4, 0), 9).unwrap();
+        game.add_entry((5, 0), 3).unwrap();
+        game.undo_entry();
+        game.undo_entry();
+        assert_eq!(game.redo_entry().map(|entry| entry.position), Some((4, 0)));
+        assert_eq!(game.get_cell((4, 0)), Ok(9));
+        assert_eq!(game.events().back().unwrap().to_string(), "redo r1c5");
+        game.add_entry((7, 0), 3).unwrap();
+        assert!(game.redo_entry().is_none());
+        assert_eq!(game.get_cell((5, 0)), Ok(0));
+    }
+
+    #[test]
+    fn toggles_notes() {
+        let mut game = game();
+        game.toggle_note((4, 0), 9).unwrap();
+        game.toggle_note((4, 0), 3).unwrap();
+        game.toggle_note((4, 0), 9).unwrap();
+        assert_eq!(game.notes((4, 0)), Some(&BTreeSet::from([3])));
+        assert_eq!(
+            game.toggle_note((0, 0), 3),
+            Err(GridError::ReadonlyCellMutation)
+        );
+        assert_eq!(
+            game.toggle_note((4, 0), 10),
+            Err(GridError::InvalidCellValue(10))
+        );
+        game.toggle_note((4, 0), 3).unwrap();
+        assert_eq!(game.notes((4, 0)), None);
+        game.toggle_note((4, 0), 1).unwrap();
+        game.reset();
+        assert_eq!(game.notes((4, 0)), None);
+    }
+
+    #[test]
+    fn flashes_rejected_input() {
+        let mut game = game();
+        game.selected = (4, 0);
+        game.add_entry_at_selected(9).unwrap();
+        assert_eq!(game.flashed(), None);
+        game.selected = (0, 0);
+        assert!(game.add_entry_at_selected(2).is_err());
+        assert_eq!(game.flashed(), Some((0, 0)));
+        std::thread::sleep(FLASH_DURATION);
+        assert_eq!(game.flashed(), None);
+        // still marked for reduced motion, until cleared
+        assert_eq!(game.rejected(), Some((0, 0)));
+        game.clear_rejected();
+        assert_eq!(game.rejected(), None);
+    }
+
+    #[test]
+    fn strict_entry_and_auto_notes() {
+        let mut game = game();
+        game.selected = (5, 0);
+        assert_eq!(
+            game.add_strict_entry_at_selected(3).err(),
+            Some(GridError::ConflictingValue(3))
+        );
+        assert_eq!(game.flashed(), Some((5, 0)));
+
+        game.fill_notes();
+        assert_eq!(game.notes((4, 0)), Some(&BTreeSet::from([9])));
+        assert_eq!(game.notes((7, 0)), Some(&BTreeSet::from([3])));
+        game.add_strict_entry_at_selected(2).unwrap();
+        game.remove_peer_notes((5, 0), 2);
+        assert!(game.notes((5, 0)).is_none_or(|notes| notes.is_empty()));
+        assert_eq!(game.notes((4, 0)), Some(&BTreeSet::from([9])));
+    }
+
+    #[test]
+    fn ruled_out_cells() {
+        let mut game = game();
+        let ruled_out = game.ruled_out_cells(9);
+        assert!(ruled_out.contains(&(7, 0)));
+        assert!(!ruled_out.contains(&(4, 0)));
+        // filled cells are left alone
+        assert!(!ruled_out.contains(&(0, 0)));
+        game.add_entry((4, 0), 9).unwrap();
+        assert!(!game.ruled_out_cells(9).contains(&(4, 0)));
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn render_redraws_changed_cells() {
+        let render = |game: &Game| {
+            let mut buf = Buffer::empty(Rect::new(0, 0, 45, 27));
+            game.render(buf.area, &mut buf, &mut Appearance::default());
+            buf
+        };
+        let play = |game: &mut Game| {
+            game.selected = (4, 0);
+            game.add_entry_at_selected(9).unwrap();
+            game.toggle_note((5, 0), 2).unwrap();
+        };
+        let (mut game, mut fresh) = (game(), game());
+        render(&game);
+        play(&mut game);
+        play(&mut fresh);
+        // the first game redraws from its cache, the other from scratch
+        assert_eq!(render(&game), render(&fresh));
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn candidate_panel() {
+        let mut game = game();
+        game.selected = (4, 0);
+        let render = |game: &Game, assist| {
+            let mut buf = Buffer::empty(Rect::new(0, 0, 14, 5));
+            CandidatePanel {
+                game,
+                appearance: Appearance::default(),
+                assist,
+            }
+            .render(buf.area, &mut buf);
+            buf
+        };
+        let buf = render(&game, Assist::Off);
+        let rows: Vec<String> = (1..4)
+            .map(|y| (1..13).map(|x| buf.get(x, y).symbol()).collect())
+            .collect();
+        assert_eq!(rows, ["    · · ·   ", "    · · ·   ", "    · · 9   "]);
+
+        // a ruled-out pencil mark is dimmed only with the assist on
+        game.toggle_note((4, 0), 3).unwrap();
+        let muted = Appearance::default().muted_style().fg;
+        assert_eq!(render(&game, Assist::Off).get(9, 1).symbol(), "3");
+        assert_ne!(render(&game, Assist::Off).get(9, 1).style().fg, muted);
+        assert_eq!(render(&game, Assist::Digits).get(9, 1).style().fg, muted);
+    }
+
     #[test]
-    // TODO: unit tests for game
-    fn test() {}
+    fn move_selected() {
+        let mut game = game();
+        game.move_selected(Direction::Left, false);
+        assert_eq!(game.selected, (0, 0));
+        game.move_selected(Direction::Left, true);
+        assert_eq!(game.selected, (8, 0));
+        game.move_selected(Direction::Down, false);
+        assert_eq!(game.selected, (8, 1));
+        game.jump_selected(Direction::Down, false);
+        assert_eq!(game.selected, (8, 4));
+        game.jump_selected(Direction::Left, false);
+        assert_eq!(game.selected, (5, 4));
+        game.jump_selected(Direction::Down, false);
+        game.jump_selected(Direction::Down, false);
+        assert_eq!(game.selected, (5, 7));
+        game.jump_selected(Direction::Down, true);
+        assert_eq!(game.selected, (5, 1));
+    }
+
+    #[test]
+    fn pause() {
+        let mut game = game();
+        game.pause();
+        assert!(game.is_paused());
+        let elapsed = game.elapsed();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(game.elapsed(), elapsed);
+        game.resume();
+        assert!(!game.is_paused());
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(game.elapsed() > elapsed);
+    }
+
+    #[test]
+    fn pause_idle() {
+        let mut game = game();
+        std::thread::sleep(Duration::from_millis(20));
+        game.pause_idle(Duration::from_millis(15));
+        assert!(game.is_paused());
+        assert!(game.elapsed() < Duration::from_millis(15));
+        assert_eq!(game.idle_time(), Duration::from_millis(15));
+        game.pause_idle(Duration::from_millis(15));
+        assert_eq!(game.idle_time(), Duration::from_millis(15));
+    }
+
+    #[test]
+    fn digit_counts() {
+        let mut game = game();
+        assert_eq!(game.digit_counts(), vec![13, 9, 6, 7, 7, 9, 8, 8, 7, 7]);
+        game.add_entry((4, 0), 9).unwrap();
+        assert_eq!(game.digit_counts()[9], 8);
+    }
+
+    #[test]
+    fn partner_entries() {
+        let mut game = game();
+        game.add_entry((4, 0), 9).unwrap();
+        let seen = game.events_recorded();
+        game.add_partner_entry((5, 0), 2).unwrap();
+        assert!(game.is_partner_entry((5, 0)));
+        assert_eq!(game.events_since(seen).count(), 1);
+        // only the player's own entries can be undone
+        assert_eq!(game.undo_entry().map(|entry| entry.position), Some((4, 0)));
+        assert!(game.undo_entry().is_none());
+        game.add_entry((5, 0), 0).unwrap();
+        assert!(!game.is_partner_entry((5, 0)));
+        // an undone entry can't be redone over the other player's
+        game.undo_entry().unwrap();
+        game.add_partner_entry((5, 0), 2).unwrap();
+        assert!(game.redo_entry().is_none());
+        assert_eq!(game.get_cell((5, 0)).unwrap(), 2);
+    }
 }
@@ -0,0 +1,196 @@
+use crate::{
+    difficulty::Difficulty,
+    grid::Regions,
+    rng::Rng,
+    solver::{count_solutions_in, solve_cells_with_order},
+    variant::Variant,
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Puzzles `generate_best` tries before settling for the closest one
+const ATTEMPTS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generated {
+    pub givens: Vec<usize>,
+    pub solution: Vec<usize>,
+}
+
+/// Generate a puzzle with a unique solution, removing givens in symmetric
+/// pairs until the difficulty's target count is reached or no more can go
+pub fn generate(variant: Variant, difficulty: Difficulty, rng: &mut Rng) -> Generated {
+    let (size, regions) = (variant.size(), variant.regions());
+    let solution = random_solution(size, regions, rng);
+    let target = target_givens(size, difficulty);
+    let mut givens = solution.clone();
+    let mut given_count = givens.len();
+    let mut pairs: Vec<usize> = (0..givens.len().div_ceil(2)).collect();
+    rng.shuffle(&mut pairs);
+    for i in pairs {
+        if given_count <= target {
+            break;
+        }
+        let mirror = givens.len() - 1 - i;
+        let removed = (givens[i], givens[mirror]);
+        givens[i] = 0;
+        givens[mirror] = 0;
+        if count_solutions_in(&givens, regions, 2) == 1 {
+            given_count -= if i == mirror { 1 } else { 2 };
+        } else {
+            (givens[i], givens[mirror]) = removed;
+        }
+    }
+    Generated { givens, solution }
+}
+
+/// Generate puzzles until one gets down to the difficulty's target count of
+/// givens, or the one with fewest givens after `ATTEMPTS` tries. With the
+/// `parallel` feature the attempts run on every core at once, taking the
+/// earliest that meets the target, so the puzzle depends only on the seed.
+pub fn generate_best(variant: Variant, difficulty: Difficulty, rng: &mut Rng) -> Generated {
+    let target = target_givens(variant.size(), difficulty);
+    let seeds: Vec<u64> = (0..ATTEMPTS).map(|_| rng.next_u64()).collect();
+    let mut best: Option<(usize, Generated)> = None;
+    for round in seeds.chunks(round_size()) {
+        for generated in attempts(variant, difficulty, round) {
+            let count = generated.givens.iter().filter(|&&value| value != 0).count();
+            if count <= target {
+                return generated;
+            }
+            if best.as_ref().is_none_or(|(fewest, _)| count < *fewest) {
+                best = Some((count, generated));
+            }
+        }
+    }
+    best.map(|(_, generated)| generated).unwrap()
+}
+
+/// Attempts made side by side before checking whether any meets the target
+fn round_size() -> usize {
+    #[cfg(feature = "parallel")]
+    return rayon::current_num_threads();
+    #[cfg(not(feature = "parallel"))]
+    return 1;
+}
+
+#[cfg(feature = "parallel")]
+fn attempts(variant: Variant, difficulty: Difficulty, seeds: &[u64]) -> Vec<Generated> {
+    seeds
+        .par_iter()
+        .map(|&seed| generate(variant, difficulty, &mut Rng::new(seed)))
+        .collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn attempts(variant: Variant, difficulty: Difficulty, seeds: &[u64]) -> Vec<Generated> {
+    seeds
+        .iter()
+        .map(|&seed| generate(variant, difficulty, &mut Rng::new(seed)))
+        .collect()
+}
+
+fn target_givens(size: usize, difficulty: Difficulty) -> usize {
+    let percent = match difficulty {
+        Difficulty::Easy => 50,
+        Difficulty::Medium => 40,
+        Difficulty::Hard => 34,
+        Difficulty::Expert => 28,
+    };
+    size * size * percent / 100
+}
+
+fn random_solution(size: usize, regions: Regions, rng: &mut Rng) -> Vec<usize> {
+    let sub_square_size = size.isqrt();
+    loop {
+        // the squares on the diagonal don't share rows or columns, so each
+        // can be filled with an independent random permutation, though they
+        // can clash on a diagonal or in a window and need another try
+        let mut cells = vec![0; size * size];
+        for square in 0..sub_square_size {
+            let mut values: Vec<usize> = (1..=size).collect();
+            rng.shuffle(&mut values);
+            for (k, value) in values.into_iter().enumerate() {
+                let x = square * sub_square_size + k % sub_square_size;
+                let y = square * sub_square_size + k / sub_square_size;
+                cells[y * size + x] = value;
+            }
+        }
+        let mut value_order: Vec<usize> = (1..=size).collect();
+        rng.shuffle(&mut value_order);
+        if let Some(solution) = solve_cells_with_order(&cells, regions, &value_order) {
+            return solution;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{game::Game, solver::count_solutions};
+
+    #[test]
+    fn generates_unique_puzzles() {
+        let mut rng = Rng::new(42);
+        for variant in Variant::ALL {
+            for difficulty in [Difficulty::Easy, Difficulty::Hard] {
+                let Generated { givens, solution } = generate(variant, difficulty, &mut rng);
+                let regions = variant.regions();
+                assert_eq!(givens.len(), variant.size() * variant.size());
+                let solved = Game::new(solution.clone()).unwrap().with_regions(regions);
+                assert!(solved.is_correct());
+                assert_eq!(count_solutions_in(&givens, regions, 2), 1);
+                for (given, solved) in givens.iter().zip(&solution) {
+                    assert!(*given == 0 || given == solved);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn best_of_several_attempts() {
+        let count = |generated: &Generated| generated.givens.iter().filter(|&&v| v != 0).count();
+        let mut rng = Rng::new(3);
+        let best = generate_best(Variant::Classic, Difficulty::Expert, &mut rng);
+        assert_eq!(count_solutions(&best.givens, 2), 1);
+        let mut rng = Rng::new(3);
+        let seeds: Vec<u64> = (0..ATTEMPTS).map(|_| rng.next_u64()).collect();
+        let fewest = seeds
+            .iter()
+            .map(|&seed| {
+                count(&generate(
+                    Variant::Classic,
+                    Difficulty::Expert,
+                    &mut Rng::new(seed),
+                ))
+            })
+            .min()
+            .unwrap();
+        assert!(count(&best) <= fewest.max(target_givens(9, Difficulty::Expert)));
+        assert_eq!(
+            best,
+            generate_best(Variant::Classic, Difficulty::Expert, &mut Rng::new(3))
+        );
+    }
+
+    #[test]
+    fn windows_narrow_down_the_solution() {
+        let generated = generate(Variant::Windoku, Difficulty::Expert, &mut Rng::new(11));
+        let windows = Regions::WINDOKU;
+        // several solutions as a classic puzzle, only one with the windows
+        assert!(count_solutions(&generated.givens, 2) > 1);
+        assert_eq!(count_solutions_in(&generated.givens, windows, 2), 1);
+        assert_eq!(
+            solve_cells_with_order(&generated.givens, windows, &[1, 2, 3, 4, 5, 6, 7, 8, 9]),
+            Some(generated.solution)
+        );
+    }
+
+    #[test]
+    fn same_seed_same_puzzle() {
+        assert_eq!(
+            generate(Variant::Classic, Difficulty::Medium, &mut Rng::new(7)),
+            generate(Variant::Classic, Difficulty::Medium, &mut Rng::new(7))
+        );
+    }
+}
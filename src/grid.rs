@@ -1,12 +1,24 @@
+#[cfg(feature = "tui")]
+use crate::{
+    cage::Cage,
+    constraint::{Constraint, Dot},
+    theme::Appearance,
+    tui::centered_rect,
+};
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::Display;
+#[cfg(feature = "tui")]
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Color, Style},
-    text::{Line, Span, Text},
-    widgets::{StatefulWidget, Widget},
+    style::{Color, Style, Stylize},
+    widgets::StatefulWidget,
+};
+#[cfg(feature = "tui")]
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::Mutex,
 };
-use std::collections::HashSet;
-use std::fmt::Display;
 
 #[derive(Debug, PartialEq)]
 struct Cell {
@@ -14,9 +26,54 @@ struct Cell {
     readonly: bool,
 }
 
-pub struct GridState {
+#[cfg(feature = "tui")]
+pub struct GridState<'a> {
     pub selected: (usize, usize),
-    pub subsections: Vec<GridSubsectionType>,
+    pub conflicts: &'a HashSet<GridPosition>,
+    /// Background colors laid over individual cells, e.g. a solver heatmap
+    pub highlights: HashMap<GridPosition, Color>,
+    /// Cell briefly marked after rejected input
+    pub flashed: Option<GridPosition>,
+    /// Killer cages, outlined with their sums
+    pub cages: &'a [Cage],
+    /// Rules between particular cells, drawn over the cells they involve
+    pub constraints: &'a [Constraint],
+    /// Shade the diagonals of an X-Sudoku or the windows of a Windoku
+    pub regions: Regions,
+    /// Pencil marks, drawn in empty cells when they're large enough
+    pub notes: &'a HashMap<GridPosition, BTreeSet<usize>>,
+    /// Pencil marks in the selected cell that it can't take, drawn dimmed
+    pub ruled_out_notes: Vec<usize>,
+    /// Empty cells greyed out, e.g. those the armed digit can't go in
+    pub greyed: HashSet<GridPosition>,
+    /// Entries the other player made on a shared board
+    pub partner: &'a HashSet<GridPosition>,
+    pub appearance: Appearance,
+    /// The cells as last drawn, reused where nothing about them changed
+    pub cache: &'a GridCache,
+}
+
+/// The text of each cell as last drawn, with what it was drawn from, so a
+/// redraw only lays out again the cells whose contents changed. Styles are
+/// cheap and applied afresh each time.
+#[cfg(feature = "tui")]
+#[derive(Debug, Default)]
+pub struct GridCache {
+    cells: Mutex<HashMap<GridPosition, (CellKey, Vec<String>)>>,
+}
+
+/// Everything the text of a cell depends on, besides its cage and
+/// constraints, which stay the same through a game and clear the cache when
+/// they're changed in the editor
+#[cfg(feature = "tui")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CellKey {
+    value: usize,
+    markers: [char; 2],
+    /// Pencil marks shown, one bit per value
+    notes: u16,
+    scale: CellScale,
+    ascii: bool,
 }
 
 pub type GridPosition = (usize, usize);
@@ -27,6 +84,8 @@ pub enum GridError {
     CellOutOfBounds,
     InvalidCellValue(usize),
     ReadonlyCellMutation,
+    /// The value is already in the cell's row, column, box or extra region
+    ConflictingValue(usize),
     InvalidRowNumber,
     InvalidColumnNumber,
     InvalidSquareNumber,
@@ -40,13 +99,8 @@ pub struct Grid {
 }
 
 fn square_root(n: usize) -> Option<usize> {
-    let root = (n as f64).sqrt();
-    let i = root.floor();
-    if root > i {
-        None
-    } else {
-        Some(i as usize)
-    }
+    let root = n.isqrt();
+    (root * root == n).then_some(root)
 }
 
 impl Grid {
@@ -82,6 +136,10 @@ impl Grid {
         self.side_size
     }
 
+    pub fn sub_square_size(&self) -> usize {
+        self.sub_square_size
+    }
+
     fn get_cell_index(&self, position: GridPosition) -> Result<usize, GridError> {
         if position.0 >= self.side_size || position.1 >= self.side_size {
             Err(GridError::CellOutOfBounds)
@@ -97,6 +155,9 @@ impl Grid {
 
     pub fn set_cell(&mut self, position: GridPosition, value: usize) -> Result<usize, GridError> {
         let i = self.get_cell_index(position)?;
+        if value > self.side_size {
+            return Err(GridError::InvalidCellValue(i));
+        }
         let cell = &mut self.cells[i];
         if cell.readonly {
             return Err(GridError::ReadonlyCellMutation);
@@ -106,32 +167,58 @@ impl Grid {
         Ok(previous_value)
     }
 
+    pub fn is_readonly(&self, position: GridPosition) -> Result<bool, GridError> {
+        let i = self.get_cell_index(position)?;
+        Ok(self.cells[i].readonly)
+    }
+
     pub fn reset(&mut self) {
         for cell in self.cells.iter_mut().filter(|cell| !cell.readonly) {
             cell.value = 0;
         }
     }
 
+    /// Every cell's current value, givens and entries alike
+    pub fn values(&self) -> Vec<usize> {
+        self.cells.iter().map(|cell| cell.value).collect()
+    }
+
+    pub fn givens(&self) -> Vec<usize> {
+        self.cells
+            .iter()
+            .map(|cell| if cell.readonly { cell.value } else { 0 })
+            .collect()
+    }
+
+    /// How many times each value appears on the grid, indexed by value
+    pub fn digit_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.side_size + 1];
+        for cell in &self.cells {
+            counts[cell.value] += 1;
+        }
+        counts
+    }
+
     pub fn get_subsection_values(
         &self,
         subsection_type: GridSubsectionType,
-    ) -> GridSubsectionValues {
-        GridSubsectionValues::new(&self, subsection_type)
+    ) -> GridSubsectionValues<'_> {
+        GridSubsectionValues::new(self, subsection_type)
     }
 
-    pub fn get_row_values(&self) -> Vec<GridSubsectionValues> {
+    pub fn get_row_values(&self) -> Vec<GridSubsectionValues<'_>> {
         (0..self.side_size)
             .map(|i| self.get_subsection_values(GridSubsectionType::Row(i)))
             .collect()
     }
 
-    pub fn get_column_values(&self) -> Vec<GridSubsectionValues> {
+    pub fn get_column_values(&self) -> Vec<GridSubsectionValues<'_>> {
         (0..self.side_size)
             .map(|i| self.get_subsection_values(GridSubsectionType::Column(i)))
             .collect()
     }
 
-    pub fn get_square_values(&self) -> Vec<GridSubsectionValues> {
+    pub fn get_square_values(&self) -> Vec<GridSubsectionValues<'_>> {
         (0..self.side_size)
             .map(|i| {
                 self.get_subsection_values(GridSubsectionType::Square(
@@ -142,7 +229,7 @@ impl Grid {
             .collect()
     }
 
-    pub fn get_all_subsection_values(&self) -> Vec<GridSubsectionValues> {
+    pub fn get_all_subsection_values(&self) -> Vec<GridSubsectionValues<'_>> {
         (0..self.side_size)
             .flat_map(|i| {
                 [
@@ -157,20 +244,266 @@ impl Grid {
             .collect()
     }
 
+    /// The two main diagonals, which X-Sudoku treats as extra subsections
+    pub fn get_diagonal_values(&self) -> [GridSubsectionValues<'_>; 2] {
+        [
+            self.get_subsection_values(GridSubsectionType::Diagonal),
+            self.get_subsection_values(GridSubsectionType::AntiDiagonal),
+        ]
+    }
+
+    /// The windows of a Windoku, boxes set one cell in from the edges with
+    /// a line of cells between each
+    pub fn get_window_values(&self) -> Vec<GridSubsectionValues<'_>> {
+        window_types(self.side_size)
+            .map(|subsection_type| self.get_subsection_values(subsection_type))
+            .collect()
+    }
+
     pub fn get_subsections_vaules_for_cell(
         &self,
         position: GridPosition,
-    ) -> [GridSubsectionValues; 3] {
+    ) -> [GridSubsectionValues<'_>; 3] {
         [
             self.get_subsection_values(GridSubsectionType::Row(position.1)),
             self.get_subsection_values(GridSubsectionType::Column(position.0)),
-            self.get_subsection_values(GridSubsectionType::Square(position.0 / 3, position.1 / 3)),
+            self.get_subsection_values(GridSubsectionType::Square(
+                position.0 / self.sub_square_size,
+                position.1 / self.sub_square_size,
+            )),
         ]
     }
+
+    /// Values that could go in a cell without clashing with its row, column
+    /// or square, ignoring whatever the cell currently holds
+    pub fn candidates_for_cell(&self, position: GridPosition) -> Result<Vec<usize>, GridError> {
+        self.get_cell_index(position)?;
+        let mut used = vec![false; self.side_size + 1];
+        for subsection in self.get_subsections_vaules_for_cell(position) {
+            for (other, value) in subsection.grid_subsection.zip(subsection) {
+                if other != position {
+                    used[value] = true;
+                }
+            }
+        }
+        Ok((1..=self.side_size).filter(|value| !used[*value]).collect())
+    }
+}
+
+/// Subsections some variants add to the rows, columns and boxes, each also
+/// holding every value once
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Regions {
+    /// Both main diagonals, as in X-Sudoku
+    pub diagonals: bool,
+    /// The four windows of a Windoku
+    pub windows: bool,
+}
+
+impl Regions {
+    pub const DIAGONAL: Regions = Regions {
+        diagonals: true,
+        windows: false,
+    };
+
+    pub const WINDOKU: Regions = Regions {
+        diagonals: false,
+        windows: true,
+    };
+
+    pub fn is_empty(self) -> bool {
+        self == Regions::default()
+    }
+
+    fn types(self, size: usize) -> Vec<GridSubsectionType> {
+        let mut types = vec![];
+        if self.diagonals {
+            types.extend([
+                GridSubsectionType::Diagonal,
+                GridSubsectionType::AntiDiagonal,
+            ]);
+        }
+        if self.windows {
+            types.extend(window_types(size));
+        }
+        types
+    }
+
+    /// The extra subsections on `grid`
+    pub fn subsections(self, grid: &Grid) -> Vec<GridSubsectionValues<'_>> {
+        self.types(grid.size())
+            .into_iter()
+            .map(|subsection_type| grid.get_subsection_values(subsection_type))
+            .collect()
+    }
+
+    /// The extra subsections `position` is part of
+    pub fn subsections_of(
+        self,
+        grid: &Grid,
+        position: GridPosition,
+    ) -> Vec<GridSubsectionValues<'_>> {
+        self.subsections(grid)
+            .into_iter()
+            .filter(|subsection| {
+                let mut cells = subsection.grid_subsection;
+                cells.any(|other| other == position)
+            })
+            .collect()
+    }
+
+    /// Whether `position` is in any extra subsection of a grid `size` cells
+    /// a side, for shading them
+    pub fn contains(self, size: usize, position: GridPosition) -> bool {
+        self.types(size).into_iter().any(|subsection_type| {
+            GridSubsection::sized(size, subsection_type).any(|other| other == position)
+        })
+    }
+
+    /// The cell indices of each extra subsection of a grid `size` cells a
+    /// side, for solvers that work on flat lists of cells
+    pub fn units(self, size: usize) -> Vec<Vec<usize>> {
+        self.types(size)
+            .into_iter()
+            .map(|subsection_type| {
+                GridSubsection::sized(size, subsection_type)
+                    .map(|(x, y)| y * size + x)
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// The windows of a grid `size` cells a side, left to right then top to
+/// bottom
+fn window_types(size: usize) -> impl Iterator<Item = GridSubsectionType> {
+    let count = size.isqrt().saturating_sub(1);
+    (0..count * count).map(move |i| GridSubsectionType::Window(i % count, i / count))
+}
+
+/// Human readable cell coordinates, e.g. `r3c5` for row 3 column 5
+pub fn position_label(position: GridPosition) -> String {
+    format!("r{}c{}", position.1 + 1, position.0 + 1)
+}
+
+/// The cell named by coordinates like `r4c7`, or `47` as shorthand for row
+/// 4 column 7. Rows and columns can also be two numbers apart, like `12 3`,
+/// for grids too big for single digits.
+pub fn parse_position(text: &str, size: usize) -> Option<GridPosition> {
+    let text = text.trim().to_lowercase();
+    let numbers: Vec<usize> = match text.strip_prefix('r') {
+        Some(rest) => {
+            let (row, column) = rest.split_once('c')?;
+            vec![row.trim().parse().ok()?, column.trim().parse().ok()?]
+        }
+        None if text.len() == 2 && text.chars().all(|c| c.is_ascii_digit()) => text
+            .chars()
+            .map(|c| c.to_digit(10).map(|digit| digit as usize))
+            .collect::<Option<_>>()?,
+        None => text
+            .split([' ', ','])
+            .filter(|part| !part.is_empty())
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?,
+    };
+    match numbers[..] {
+        [row, column] if (1..=size).contains(&row) && (1..=size).contains(&column) => {
+            Some((column - 1, row - 1))
+        }
+        _ => None,
+    }
+}
+
+/// One of the eight ways to turn or flip a square grid, each of which
+/// leaves a sudoku a sudoku
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symmetry {
+    mirror_x: bool,
+    mirror_y: bool,
+    transpose: bool,
+}
+
+impl Symmetry {
+    pub const IDENTITY: Symmetry = Symmetry::from_index(0);
+
+    /// Every symmetry, the identity first
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::from_index(0),
+        Symmetry::from_index(1),
+        Symmetry::from_index(2),
+        Symmetry::from_index(3),
+        Symmetry::from_index(4),
+        Symmetry::from_index(5),
+        Symmetry::from_index(6),
+        Symmetry::from_index(7),
+    ];
+
+    const fn from_index(index: usize) -> Self {
+        Self {
+            mirror_x: index & 1 != 0,
+            mirror_y: index & 2 != 0,
+            transpose: index & 4 != 0,
+        }
+    }
+
+    /// Where the cell at `position` ends up on a grid `size` cells a side
+    pub fn apply(self, (mut x, mut y): GridPosition, size: usize) -> GridPosition {
+        if self.mirror_x {
+            x = size - 1 - x;
+        }
+        if self.mirror_y {
+            y = size - 1 - y;
+        }
+        if self.transpose {
+            (x, y) = (y, x);
+        }
+        (x, y)
+    }
+}
+
+/// `cells` turned or flipped by `symmetry`, with each digit `d` renamed
+/// `digits[d - 1]`. Empty cells stay empty.
+pub fn transform(cells: &[usize], symmetry: Symmetry, digits: &[usize]) -> Vec<usize> {
+    let size = cells.len().isqrt();
+    let mut transformed = vec![0; cells.len()];
+    for (i, &value) in cells.iter().enumerate() {
+        let (x, y) = symmetry.apply((i % size, i / size), size);
+        transformed[y * size + x] = match value {
+            0 => 0,
+            value => digits[value - 1],
+        };
+    }
+    transformed
+}
+
+/// The same for a puzzle however it's turned or flipped and whichever
+/// digits it uses: the smallest of its eight rotations and reflections, each
+/// with its digits renumbered in the order they're first read
+pub fn canonical_form(cells: &[usize]) -> Vec<usize> {
+    let size = cells.len().isqrt();
+    let digits: Vec<usize> = (1..=size).collect();
+    Symmetry::ALL
+        .into_iter()
+        .map(|symmetry| {
+            let mut labels = vec![0; size + 1];
+            let mut next = 0;
+            transform(cells, symmetry, &digits)
+                .into_iter()
+                .map(|value| {
+                    if value != 0 && labels[value] == 0 {
+                        next += 1;
+                        labels[value] = next;
+                    }
+                    labels[value]
+                })
+                .collect::<Vec<usize>>()
+        })
+        .min()
+        .unwrap_or_default()
 }
 
 impl Display for Grid {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         for i in 0..self.side_size {
             for j in 0..self.side_size {
                 match self.get_cell((i, j)).unwrap() {
@@ -180,77 +513,532 @@ impl Display for Grid {
                     n => write!(f, ",{}", n)?,
                 }
             }
-            write!(f, "\n")?
+            writeln!(f)?
         }
         Ok(())
     }
 }
 
-impl StatefulWidget for &Grid {
-    type State = GridState;
+#[cfg(feature = "tui")]
+struct Viewport {
+    first: GridPosition,
+    columns: usize,
+    rows: usize,
+    scale: CellScale,
+    area: Rect,
+}
+
+#[cfg(feature = "tui")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellScale {
+    Compact,
+    Large,
+}
+
+#[cfg(feature = "tui")]
+impl CellScale {
+    pub fn width(self) -> usize {
+        match self {
+            CellScale::Compact => 3,
+            CellScale::Large => 5,
+        }
+    }
+
+    pub fn height(self) -> usize {
+        match self {
+            CellScale::Compact => 1,
+            CellScale::Large => 3,
+        }
+    }
+}
+
+/// The character shown for a cell value: digits up to 9, then letters so
+/// 16x16 grids use `A`-`G` and 25x25 grids `A`-`P`
+pub fn value_symbol(value: usize) -> char {
+    match value {
+        0 => '_',
+        1..=9 => char::from(b'0' + value as u8),
+        _ => char::from(b'A' + (value - 10) as u8),
+    }
+}
+
+#[cfg(feature = "tui")]
+const LARGE_DIGITS: [[&str; 3]; 10] = [
+    ["   ", " · ", "   "],
+    [" ╷ ", " │ ", " ╵ "],
+    ["╶─┐", "┌─┘", "└─╴"],
+    ["╶─┐", " ─┤", "╶─┘"],
+    ["╷ ╷", "└─┤", "  ╵"],
+    ["┌─╴", "└─┐", "╶─┘"],
+    ["┌─╴", "├─┐", "└─┘"],
+    ["╶─┐", "  │", "  ╵"],
+    ["┌─┐", "├─┤", "└─┘"],
+    ["┌─┐", "└─┤", "╶─┘"],
+];
+
+#[cfg(feature = "tui")]
+/// Seven-segment style digits for terminals without box-drawing characters
+const ASCII_DIGITS: [[&str; 3]; 10] = [
+    ["   ", " . ", "   "],
+    ["   ", "  |", "  |"],
+    [" _ ", " _|", "|_ "],
+    [" _ ", " _|", " _|"],
+    ["   ", "|_|", "  |"],
+    [" _ ", "|_ ", " _|"],
+    [" _ ", "|_ ", "|_|"],
+    [" _ ", "  |", "  |"],
+    [" _ ", "|_|", "|_|"],
+    [" _ ", "|_|", " _|"],
+];
+
+#[cfg(feature = "tui")]
+impl Grid {
+    pub fn render_size(&self, scale: CellScale) -> (u16, u16) {
+        (
+            (self.side_size * scale.width()) as u16,
+            (self.side_size * scale.height()) as u16,
+        )
+    }
+
+    pub fn scale_for(&self, area: Rect) -> CellScale {
+        let (width, height) = self.render_size(CellScale::Large);
+        if area.width >= width && area.height >= height {
+            CellScale::Large
+        } else {
+            CellScale::Compact
+        }
+    }
+
+    /// The cells that fit in `area`, scrolled to keep `selected` in view when
+    /// the whole grid doesn't fit: the first visible cell, how many columns
+    /// and rows are visible, and where they're drawn
+    fn viewport(&self, area: Rect, selected: GridPosition) -> Viewport {
+        let scale = self.scale_for(area);
+        let columns = (area.width as usize / scale.width()).min(self.side_size);
+        let rows = (area.height as usize / scale.height()).min(self.side_size);
+        let first = |selected: usize, visible: usize| {
+            selected
+                .saturating_sub(visible / 2)
+                .min(self.side_size - visible)
+        };
+        Viewport {
+            first: (first(selected.0, columns), first(selected.1, rows)),
+            columns,
+            rows,
+            scale,
+            area: centered_rect(
+                area,
+                (columns * scale.width()) as u16,
+                (rows * scale.height()) as u16,
+            ),
+        }
+    }
+
+    /// The cell drawn at a terminal column and row when the grid is rendered
+    /// into `area` with `selected` selected
+    pub fn position_at(
+        &self,
+        area: Rect,
+        selected: GridPosition,
+        column: u16,
+        row: u16,
+    ) -> Option<GridPosition> {
+        let Viewport {
+            first, scale, area, ..
+        } = self.viewport(area, selected);
+        if column < area.x || row < area.y || column >= area.right() || row >= area.bottom() {
+            return None;
+        }
+        Some((
+            first.0 + (column - area.x) as usize / scale.width(),
+            first.1 + (row - area.y) as usize / scale.height(),
+        ))
+    }
+
+    fn cell_style(&self, position: GridPosition, state: &GridState) -> (Style, [char; 2]) {
+        let cell = &self.cells[self.get_cell_index(position).unwrap()];
+        let is_conflict = state.conflicts.contains(&position);
+        let is_selected = position == state.selected;
+        let size = self.side_size;
+        let shade = state
+            .regions
+            .contains(size, position)
+            .then_some(state.appearance.theme.diagonal);
+        let background = state.highlights.get(&position).copied().or(shade);
+        if state.flashed == Some(position) {
+            return if state.appearance.colorblind {
+                (Style::new().bold().reversed(), ['>', '<'])
+            } else {
+                let theme = &state.appearance.theme;
+                let style = Style::new().fg(theme.conflict_text).bg(theme.conflict);
+                (style.bold(), [' ', ' '])
+            };
+        }
+        if state.appearance.colorblind {
+            let style = if cell.readonly {
+                Style::new().bold()
+            } else {
+                Style::new()
+            };
+            let style = if is_conflict {
+                style.underlined()
+            } else {
+                style
+            };
+            let style = if state.partner.contains(&position) {
+                style.italic()
+            } else {
+                style
+            };
+            let style = if is_selected { style.reversed() } else { style };
+            let style = match background {
+                // the monochrome theme has no shades, so dim the cell instead
+                Some(Color::Reset) if !is_selected => style.dim(),
+                Some(color) if !is_selected => style.bg(color),
+                _ => style,
+            };
+            let markers = match (is_conflict, cell.readonly) {
+                (true, false) => ['!', '!'],
+                (true, true) => ['*', '*'],
+                _ if is_selected => ['[', ']'],
+                _ => [' ', ' '],
+            };
+            return (style, markers);
+        }
+        let theme = &state.appearance.theme;
+        let style = match (cell.readonly, is_conflict) {
+            (true, true) => Style::new().fg(theme.conflict),
+            (true, false) => Style::new().fg(theme.given),
+            (false, true) => Style::new()
+                .fg(theme.conflict_text)
+                .bg(theme.conflict)
+                .bold(),
+            (false, false) if state.partner.contains(&position) => Style::new().fg(theme.partner),
+            (false, false) => Style::new().fg(theme.entry),
+        };
+        let style = match background {
+            _ if is_selected => style.bg(theme.selected),
+            Some(color) => style.bg(color),
+            None => style,
+        };
+        (style, [' ', ' '])
+    }
+
+    /// The pencil marks drawn in a cell. They sit at their keypad position
+    /// in a 3x3 block, so only fit grids up to 9x9.
+    fn shown_notes<'a>(
+        &self,
+        position: GridPosition,
+        value: usize,
+        state: &GridState<'a>,
+    ) -> Option<&'a BTreeSet<usize>> {
+        state
+            .notes
+            .get(&position)
+            .filter(|notes| value == 0 && self.side_size <= 9 && !notes.is_empty())
+    }
+
+    fn cell_rows(
+        &self,
+        position: GridPosition,
+        state: &GridState,
+        scale: CellScale,
+        [left, right]: [char; 2],
+    ) -> Vec<String> {
+        let value = self.cells[self.get_cell_index(position).unwrap()].value;
+        let notes = self.shown_notes(position, value, state);
+        let rows: Vec<String> = match (scale, notes) {
+            (CellScale::Compact, _) => vec![value_symbol(value).to_string()],
+            (CellScale::Large, Some(notes)) => (0..3)
+                .map(|row| {
+                    (1..=3)
+                        .map(|column| row * 3 + column)
+                        .map(|note| match notes.contains(&note) {
+                            true => value_symbol(note),
+                            false => ' ',
+                        })
+                        .collect()
+                })
+                .collect(),
+            (CellScale::Large, None) if value > 9 => {
+                vec![
+                    "   ".into(),
+                    format!(" {} ", value_symbol(value)),
+                    "   ".into(),
+                ]
+            }
+            (CellScale::Large, None) if state.appearance.ascii => ASCII_DIGITS[value]
+                .iter()
+                .map(|row| row.to_string())
+                .collect(),
+            (CellScale::Large, None) => LARGE_DIGITS[value]
+                .iter()
+                .map(|row| row.to_string())
+                .collect(),
+        };
+        let middle = rows.len() / 2;
+        let mut rows: Vec<Vec<char>> = rows
+            .into_iter()
+            .enumerate()
+            .map(|(row, content)| {
+                let (left, right) = if row == middle {
+                    (left, right)
+                } else {
+                    (' ', ' ')
+                };
+                std::iter::once(left)
+                    .chain(content.chars())
+                    .chain(std::iter::once(right))
+                    .collect()
+            })
+            .collect();
+        outline_cage(&mut rows, position, state.cages);
+        for constraint in state.constraints {
+            mark_constraint(&mut rows, position, constraint, state.appearance.ascii);
+        }
+        rows.into_iter()
+            .map(|row| row.into_iter().collect())
+            .collect()
+    }
+}
+
+#[cfg(feature = "tui")]
+/// Overlay the dashed outline of the cage containing `position` on the free
+/// parts of its cell, along with the cage sum if it's the top-left cell
+fn outline_cage(rows: &mut [Vec<char>], position: GridPosition, cages: &[Cage]) {
+    let cage_index = |position| {
+        cages
+            .iter()
+            .position(|cage: &Cage| cage.cells.contains(&position))
+    };
+    let Some(index) = cage_index(position) else {
+        return;
+    };
+    let (x, y) = position;
+    if x == 0 || cage_index((x - 1, y)) != Some(index) {
+        for row in rows.iter_mut() {
+            if row[0] == ' ' {
+                row[0] = '┆';
+            }
+        }
+    }
+    // compact cells are a single row, leaving no room for a top edge
+    if rows.len() > 1 && (y == 0 || cage_index((x, y - 1)) != Some(index)) {
+        for c in rows[0].iter_mut().filter(|c| **c == ' ') {
+            *c = '┄';
+        }
+    }
+    let cage = &cages[index];
+    if cage.anchor() != position {
+        return;
+    }
+    // large cells put the sum in the top-left corner, while compact ones
+    // have to share their only row with the value, so they use superscript
+    // digits after it to tell the two apart
+    let (sum, target): (Vec<char>, &mut [char]) = if rows.len() > 1 {
+        (cage.sum.to_string().chars().collect(), &mut rows[0][..])
+    } else {
+        let sum: Vec<char> = cage.sum.to_string().chars().map(superscript).collect();
+        let start = rows[0].len() - sum.len();
+        (sum, &mut rows[0][start..])
+    };
+    if target.len() >= sum.len()
+        && target[..sum.len()]
+            .iter()
+            .all(|c| matches!(c, ' ' | '┄' | '┆' | '_'))
+    {
+        target[..sum.len()].copy_from_slice(&sum);
+    }
+}
+
+#[cfg(feature = "tui")]
+/// Mark the free parts of the cell at `position` with its part in
+/// `constraint`: an arrow's circle is drawn as brackets around the value,
+/// each cell along its path shows the way the arrow goes, and kropki dots
+/// and inequality signs sit on the border between their cells
+fn mark_constraint(
+    rows: &mut [Vec<char>],
+    position: GridPosition,
+    constraint: &Constraint,
+    ascii: bool,
+) {
+    let middle = rows.len() / 2;
+    let last = rows[0].len() - 1;
+    match constraint {
+        Constraint::Arrow { circle, .. } if *circle == position => {
+            let row = &mut rows[middle];
+            if row[0] == ' ' && row[last] == ' ' {
+                (row[0], row[last]) = ('(', ')');
+            }
+        }
+        Constraint::Arrow { circle, path } => {
+            let Some(i) = path.iter().position(|&cell| cell == position) else {
+                return;
+            };
+            let from = if i == 0 { *circle } else { path[i - 1] };
+            let dx = position.0 as isize - from.0 as isize;
+            let dy = position.1 as isize - from.1 as isize;
+            let glyphs = match ascii {
+                true => ['\\', '^', '/', '<', '>', '/', 'v', '\\'],
+                false => ['↖', '↑', '↗', '←', '→', '↙', '↓', '↘'],
+            };
+            // the eight neighbours, row by row, skipping the cell itself
+            let index = ((dy + 1) * 3 + dx + 1) as usize;
+            let glyph = glyphs[if index > 4 { index - 1 } else { index }];
+            // the bottom right corner, clear of a cage's outline and sum
+            let corner = &mut rows[rows.len() - 1][last];
+            if *corner == ' ' {
+                *corner = glyph;
+            }
+        }
+        Constraint::Kropki { dot, cells } => {
+            let glyph = match (dot, ascii) {
+                (Dot::White, false) => '○',
+                (Dot::Black, false) => '●',
+                (Dot::White, true) => 'o',
+                (Dot::Black, true) => '*',
+            };
+            mark_border(rows, position, *cells, glyph);
+        }
+        Constraint::Inequality { less, greater } => {
+            // the sign points at the smaller digit
+            let glyph = match (less.0 < greater.0, less.1 < greater.1) {
+                (true, _) => '<',
+                (_, true) => '^',
+                _ if less.1 == greater.1 => '>',
+                _ => 'v',
+            };
+            mark_border(rows, position, [*less, *greater], glyph);
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+/// Draw `glyph` on the border between two cells side by side: in the right
+/// column of the left one, level with its value, or in the bottom right
+/// corner of the upper one, the only part of its bottom row a digit leaves
+/// free. Compact cells are a single row, leaving no room for a border below.
+fn mark_border(
+    rows: &mut [Vec<char>],
+    position: GridPosition,
+    [a, b]: [GridPosition; 2],
+    glyph: char,
+) {
+    let first = a.min(b);
+    let (row, column) = match a.1 == b.1 {
+        true => (rows.len() / 2, rows[0].len() - 1),
+        false if rows.len() > 1 => (rows.len() - 1, rows[0].len() - 1),
+        false => return,
+    };
+    let cell = &mut rows[row][column];
+    if position == first && *cell == ' ' {
+        *cell = glyph;
+    }
+}
+
+#[cfg(feature = "tui")]
+fn superscript(digit: char) -> char {
+    match digit {
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        _ => char::from_u32('⁰' as u32 + digit.to_digit(10).unwrap()).unwrap(),
+    }
+}
+
+#[cfg(feature = "tui")]
+impl<'a> StatefulWidget for &'a Grid {
+    type State = GridState<'a>;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State)
     where
         Self: Sized,
     {
-        let red_cells: HashSet<(usize, usize)> = state
-            .subsections
-            .iter()
-            .flat_map(|t| GridSubsection::new(self, *t))
-            .collect();
-
-        let lines: Vec<Line> = (0..self.side_size)
-            .map(|j| {
-                let spans = (0..self.side_size)
-                    .map(|i| {
-                        let is_red = red_cells.contains(&(i, j));
-                        let cell = &self.cells[self.get_cell_index((i, j)).unwrap()];
-                        let style = if cell.readonly {
-                            Style::new().fg(Color::White)
-                        } else {
-                            Style::new().fg(Color::Blue)
-                        };
-                        let style = if (i, j) == state.selected {
-                            style.bg(Color::DarkGray)
-                        } else if is_red {
-                            style.bg(Color::Red)
-                        } else {
-                            style
-                        };
-                        let cell_string = match cell.value {
-                            0 => format!(" _ "),
-                            n => format!(" {n} "),
-                        };
-                        Span::styled(cell_string, style)
-                    })
-                    .collect::<Vec<Span>>();
-                Line::from(spans)
-            })
-            .collect();
-        let text = Text::from(lines);
-        text.render(area, buf);
+        let Viewport {
+            first: (first_column, first_row),
+            columns,
+            rows,
+            scale,
+            area,
+        } = self.viewport(area, state.selected);
+        let mut cache = state.cache.cells.lock().unwrap();
+        for j in first_row..first_row + rows {
+            for i in first_column..first_column + columns {
+                let position = (i, j);
+                let value = self.cells[self.get_cell_index(position).unwrap()].value;
+                let (mut style, markers) = self.cell_style(position, state);
+                if state.greyed.contains(&position) && position != state.selected {
+                    style = match state.appearance.colorblind {
+                        true => style.dim(),
+                        false => style.fg(state.appearance.theme.muted).dim(),
+                    };
+                }
+                let key = CellKey {
+                    value,
+                    markers,
+                    notes: self
+                        .shown_notes(position, value, state)
+                        .map_or(0, |notes| notes.iter().map(|note| 1 << note).sum()),
+                    scale,
+                    ascii: state.appearance.ascii,
+                };
+                let cached = cache.get(&position).filter(|(cached, _)| *cached == key);
+                if cached.is_none() {
+                    let text = self.cell_rows(position, state, scale, markers);
+                    cache.insert(position, (key, text));
+                }
+                let x = area.x + ((i - first_column) * scale.width()) as u16;
+                let y = area.y + ((j - first_row) * scale.height()) as u16;
+                for (row, text) in cache[&position].1.iter().enumerate() {
+                    buf.set_string(x, y + row as u16, text, style);
+                }
+                if position == state.selected && key.notes != 0 && scale == CellScale::Large {
+                    // each mark sits at its keypad position, past the marker column
+                    for note in &state.ruled_out_notes {
+                        let column = x + 1 + ((note - 1) % 3) as u16;
+                        let row = y + ((note - 1) / 3) as u16;
+                        if column < buf.area.right() && row < buf.area.bottom() {
+                            buf.get_mut(column, row).set_style(Style::new().dim());
+                        }
+                    }
+                }
+            }
+        }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum GridSubsectionType {
     Row(usize),
     Column(usize),
     Square(usize, usize),
+    /// From the top-left corner to the bottom-right
+    Diagonal,
+    /// From the top-right corner to the bottom-left
+    AntiDiagonal,
+    /// A Windoku window, numbered like the boxes
+    Window(usize, usize),
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct GridSubsection {
     pub subsection_type: GridSubsectionType,
     pub grid_size: usize,
+    sub_square_size: usize,
     current: usize,
 }
 
 impl GridSubsection {
     fn new(grid: &Grid, subsection_type: GridSubsectionType) -> Self {
         // validate grid
+        Self::sized(grid.side_size, subsection_type)
+    }
+
+    /// The subsection on any grid `size` cells a side
+    fn sized(size: usize, subsection_type: GridSubsectionType) -> Self {
         Self {
-            grid_size: grid.side_size,
+            grid_size: size,
+            sub_square_size: size.isqrt(),
             subsection_type,
             current: 0,
         }
@@ -261,15 +1049,24 @@ impl Iterator for GridSubsection {
     type Item = (usize, usize);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current > 8 {
+        if self.current >= self.grid_size {
             return None;
         }
         let (x, y) = match self.subsection_type {
             GridSubsectionType::Row(j) => (self.current, j),
             GridSubsectionType::Column(i) => (i, self.current),
             GridSubsectionType::Square(i, j) => {
-                let x = i * 3 + (self.current % 3);
-                let y = j * 3 + (self.current / 3);
+                let size = self.sub_square_size;
+                let x = i * size + (self.current % size);
+                let y = j * size + (self.current / size);
+                (x, y)
+            }
+            GridSubsectionType::Diagonal => (self.current, self.current),
+            GridSubsectionType::AntiDiagonal => (self.grid_size - 1 - self.current, self.current),
+            GridSubsectionType::Window(i, j) => {
+                let size = self.sub_square_size;
+                let x = 1 + i * (size + 1) + (self.current % size);
+                let y = 1 + j * (size + 1) + (self.current / size);
                 (x, y)
             }
         };
@@ -370,6 +1167,10 @@ mod tests {
             grid.set_cell((0, 0), 3),
             Err(GridError::ReadonlyCellMutation)
         );
+        assert_eq!(
+            grid.set_cell((1, 1), 10),
+            Err(GridError::InvalidCellValue(10))
+        );
         assert_eq!(grid.set_cell((1, 1), 6), Ok(0));
         assert_eq!(grid.get_cell((1, 1)), Ok(6));
     }
@@ -469,4 +1270,273 @@ mod tests {
             vec![1, 0, 0, 0, 0, 0, 0, 7, 9,]
         );
     }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn scale_for() {
+        let grid = Grid::new(vec![0; 81]).unwrap();
+        assert_eq!(grid.render_size(CellScale::Compact), (27, 9));
+        assert_eq!(grid.render_size(CellScale::Large), (45, 27));
+        assert_eq!(grid.scale_for(Rect::new(0, 0, 44, 40)), CellScale::Compact);
+        assert_eq!(grid.scale_for(Rect::new(0, 0, 45, 26)), CellScale::Compact);
+        assert_eq!(grid.scale_for(Rect::new(0, 0, 45, 27)), CellScale::Large);
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn position_at() {
+        let grid = Grid::new(vec![0; 81]).unwrap();
+        // a compact 27x9 grid centered in a 37x19 area starts at (5, 5)
+        let area = Rect::new(0, 0, 37, 19);
+        assert_eq!(grid.position_at(area, (0, 0), 5, 5), Some((0, 0)));
+        assert_eq!(grid.position_at(area, (0, 0), 7, 5), Some((0, 0)));
+        assert_eq!(grid.position_at(area, (0, 0), 8, 6), Some((1, 1)));
+        assert_eq!(grid.position_at(area, (0, 0), 31, 13), Some((8, 8)));
+        assert_eq!(grid.position_at(area, (0, 0), 4, 5), None);
+        assert_eq!(grid.position_at(area, (0, 0), 32, 5), None);
+        assert_eq!(grid.position_at(area, (0, 0), 5, 14), None);
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn scrolls_to_selection() {
+        let grid = Grid::new(vec![0; 625]).unwrap();
+        // 10 of the 25 columns and all rows fit, scrolled to center column 20
+        let area = Rect::new(0, 0, 30, 25);
+        assert_eq!(grid.position_at(area, (20, 0), 0, 0), Some((15, 0)));
+        assert_eq!(grid.position_at(area, (20, 0), 29, 24), Some((24, 24)));
+        assert_eq!(grid.position_at(area, (12, 3), 0, 0), Some((7, 0)));
+        assert_eq!(grid.position_at(area, (0, 0), 0, 0), Some((0, 0)));
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn outline_cage() {
+        let cages = [Cage {
+            sum: 12,
+            cells: vec![(1, 0), (2, 0), (1, 1)],
+        }];
+        let rows = |cell: &[&str]| -> Vec<Vec<char>> {
+            cell.iter().map(|row| row.chars().collect()).collect()
+        };
+        let mut compact = rows(&[" _ "]);
+        super::outline_cage(&mut compact, (1, 0), &cages);
+        assert_eq!(compact, rows(&["┆¹²"]));
+        let mut large = rows(&["     ", "  ·  ", "     "]);
+        super::outline_cage(&mut large, (1, 0), &cages);
+        assert_eq!(large, rows(&["12┄┄┄", "┆ ·  ", "┆    "]));
+        let mut large = rows(&["     ", "  ·  ", "     "]);
+        super::outline_cage(&mut large, (2, 0), &cages);
+        assert_eq!(large, rows(&["┄┄┄┄┄", "  ·  ", "     "]));
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn mark_arrow() {
+        let arrow = Constraint::Arrow {
+            circle: (1, 1),
+            path: vec![(2, 2), (2, 3)],
+        };
+        let rows = |cell: &[&str]| -> Vec<Vec<char>> {
+            cell.iter().map(|row| row.chars().collect()).collect()
+        };
+        let mut compact = rows(&[" 5 "]);
+        mark_constraint(&mut compact, (1, 1), &arrow, false);
+        assert_eq!(compact, rows(&["(5)"]));
+        let mut compact = rows(&[" _ "]);
+        mark_constraint(&mut compact, (2, 2), &arrow, false);
+        assert_eq!(compact, rows(&[" _↘"]));
+        let mut large = rows(&["     ", "  ·  ", "     "]);
+        mark_constraint(&mut large, (2, 3), &arrow, true);
+        assert_eq!(large, rows(&["     ", "  ·  ", "    v"]));
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn mark_kropki() {
+        let rows = |cell: &[&str]| -> Vec<Vec<char>> {
+            cell.iter().map(|row| row.chars().collect()).collect()
+        };
+        let across = Constraint::Kropki {
+            dot: Dot::White,
+            cells: [(3, 0), (2, 0)],
+        };
+        let mut compact = rows(&[" 4 "]);
+        mark_constraint(&mut compact, (2, 0), &across, false);
+        assert_eq!(compact, rows(&[" 4○"]));
+        let mut compact = rows(&[" 5 "]);
+        mark_constraint(&mut compact, (3, 0), &across, false);
+        assert_eq!(compact, rows(&[" 5 "]));
+        let down = Constraint::Kropki {
+            dot: Dot::Black,
+            cells: [(2, 0), (2, 1)],
+        };
+        let mut large = rows(&["     ", "  ·  ", "     "]);
+        mark_constraint(&mut large, (2, 0), &down, true);
+        assert_eq!(large, rows(&["     ", "  ·  ", "    *"]));
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn mark_inequality() {
+        let rows = |cell: &[&str]| -> Vec<Vec<char>> {
+            cell.iter().map(|row| row.chars().collect()).collect()
+        };
+        let signs = [((0, 0), (1, 0), " _<"), ((1, 0), (0, 0), " _>")];
+        for (less, greater, drawn) in signs {
+            let mut compact = rows(&[" _ "]);
+            let sign = Constraint::Inequality { less, greater };
+            mark_constraint(&mut compact, (0, 0), &sign, false);
+            assert_eq!(compact, rows(&[drawn]));
+        }
+        let signs = [((0, 0), (0, 1), '^'), ((0, 1), (0, 0), 'v')];
+        for (less, greater, drawn) in signs {
+            let mut large = rows(&["     ", "  ·  ", "     "]);
+            let sign = Constraint::Inequality { less, greater };
+            mark_constraint(&mut large, (0, 0), &sign, false);
+            assert_eq!(large[2][4], drawn);
+        }
+    }
+
+    #[test]
+    fn value_symbol() {
+        assert_eq!(super::value_symbol(0), '_');
+        assert_eq!(super::value_symbol(9), '9');
+        assert_eq!(super::value_symbol(10), 'A');
+        assert_eq!(super::value_symbol(16), 'G');
+        assert_eq!(super::value_symbol(25), 'P');
+    }
+
+    #[test]
+    fn digit_counts() {
+        let grid = Grid::new(vec![
+            2, 0, 0, 0, 0, 0, 0, 6, 1, // row 0
+            0, 0, 0, 0, 0, 0, 0, 0, 0, // row 1
+            0, 0, 3, 0, 1, 0, 0, 0, 0, // row 2
+            0, 0, 0, 0, 0, 0, 0, 0, 0, // row 3
+            0, 0, 0, 0, 0, 0, 0, 0, 0, // row 4
+            0, 0, 0, 0, 0, 0, 0, 0, 0, // row 5
+            0, 0, 0, 0, 0, 0, 0, 0, 0, // row 6
+            0, 0, 0, 0, 0, 0, 0, 0, 8, // row 7
+            1, 0, 0, 0, 0, 0, 0, 7, 9, // row 8
+        ])
+        .unwrap();
+        assert_eq!(grid.digit_counts(), vec![72, 3, 1, 1, 0, 0, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn small_grid_subsections() {
+        let grid = Grid::new(vec![
+            1, 2, 3, 4, // row 0
+            3, 4, 1, 2, // row 1
+            2, 1, 4, 3, // row 2
+            4, 3, 2, 1, // row 3
+        ])
+        .unwrap();
+        assert_eq!(
+            grid.get_subsection_values(GridSubsectionType::Row(1))
+                .collect::<Vec<_>>(),
+            vec![3, 4, 1, 2]
+        );
+        assert_eq!(
+            grid.get_subsection_values(GridSubsectionType::Square(1, 1))
+                .collect::<Vec<_>>(),
+            vec![4, 3, 2, 1]
+        );
+        let [diagonal, anti_diagonal] = grid.get_diagonal_values();
+        assert_eq!(diagonal.collect::<Vec<_>>(), vec![1, 4, 4, 1]);
+        assert_eq!(
+            anti_diagonal.grid_subsection.collect::<Vec<_>>(),
+            vec![(3, 0), (2, 1), (1, 2), (0, 3)]
+        );
+        let windows = grid.get_window_values();
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].collect::<Vec<_>>(), vec![4, 1, 1, 4]);
+        assert!(Regions::WINDOKU.contains(9, (7, 5)));
+        assert!(!Regions::WINDOKU.contains(9, (4, 4)));
+    }
+
+    #[test]
+    fn candidates_for_cell() {
+        let grid = Grid::new(vec![
+            5, 3, 0, 0, 7, 0, 0, 0, 0, // row 0
+            6, 0, 0, 1, 9, 5, 0, 0, 0, // row 1
+            0, 9, 8, 0, 0, 0, 0, 6, 0, // row 2
+            8, 0, 0, 0, 6, 0, 0, 0, 3, // row 3
+            4, 0, 0, 8, 0, 3, 0, 0, 1, // row 4
+            7, 0, 0, 0, 2, 0, 0, 0, 6, // row 5
+            0, 6, 0, 0, 0, 0, 2, 8, 0, // row 6
+            0, 0, 0, 4, 1, 9, 0, 0, 5, // row 7
+            0, 0, 0, 0, 8, 0, 0, 7, 9, // row 8
+        ])
+        .unwrap();
+        assert_eq!(grid.candidates_for_cell((2, 0)), Ok(vec![1, 2, 4]));
+        assert_eq!(grid.candidates_for_cell((0, 0)), Ok(vec![1, 2, 5]));
+        assert_eq!(
+            grid.candidates_for_cell((9, 0)),
+            Err(GridError::CellOutOfBounds)
+        );
+        assert_eq!(position_label((4, 2)), "r3c5");
+    }
+
+    #[test]
+    fn parse_position() {
+        assert_eq!(super::parse_position("r4c7", 9), Some((6, 3)));
+        assert_eq!(super::parse_position("R4C7", 9), Some((6, 3)));
+        assert_eq!(super::parse_position("47", 9), Some((6, 3)));
+        assert_eq!(super::parse_position("12, 3", 16), Some((2, 11)));
+        assert_eq!(super::parse_position("r10c1", 9), None);
+        assert_eq!(super::parse_position("40", 9), None);
+        assert_eq!(super::parse_position("4", 9), None);
+        assert_eq!(super::parse_position("r4", 9), None);
+    }
+
+    #[test]
+    fn canonical_form() {
+        let cells = vec![
+            1, 0, 0, 0, // row 0
+            0, 0, 3, 0, // row 1
+            0, 0, 0, 0, // row 2
+            0, 4, 0, 2, // row 3
+        ];
+        let turned = vec![
+            0, 0, 0, 1, // row 0
+            4, 0, 0, 0, // row 1
+            0, 0, 3, 0, // row 2
+            2, 0, 0, 0, // row 3
+        ];
+        let relabeled: Vec<usize> = cells.iter().map(|&value| [0, 4, 3, 2, 1][value]).collect();
+        let canonical = super::canonical_form(&cells);
+        assert_eq!(super::canonical_form(&turned), canonical);
+        assert_eq!(super::canonical_form(&relabeled), canonical);
+        assert_ne!(
+            super::canonical_form(&[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+            canonical
+        );
+    }
+
+    #[test]
+    fn transform() {
+        let cells = vec![
+            1, 2, 0, 0, // row 0
+            0, 0, 0, 0, // row 1
+            0, 0, 0, 0, // row 2
+            0, 0, 0, 3, // row 3
+        ];
+        assert_eq!(
+            super::transform(&cells, Symmetry::IDENTITY, &[1, 2, 3, 4]),
+            cells
+        );
+        let turned = super::transform(&cells, Symmetry::ALL[1], &[4, 3, 2, 1]);
+        assert_eq!(turned[..4], [0, 0, 3, 4]);
+        assert_eq!(turned[12], 2);
+        // every symmetry moves the cells somewhere different
+        let mut seen: Vec<GridPosition> = Symmetry::ALL
+            .iter()
+            .map(|symmetry| symmetry.apply((1, 0), 4))
+            .collect();
+        seen.sort();
+        seen.dedup();
+        assert_eq!(seen.len(), 8);
+    }
 }
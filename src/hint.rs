@@ -0,0 +1,252 @@
+#[cfg(feature = "tui")]
+use crate::theme::Theme;
+use crate::{
+    game::Game,
+    grid::{
+        position_label, value_symbol, Grid, GridPosition, GridSubsectionType, GridSubsectionValues,
+    },
+};
+#[cfg(feature = "tui")]
+use ratatui::style::Color;
+use std::collections::HashMap;
+
+/// A value the player can work out from the board as it stands
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hint {
+    pub position: GridPosition,
+    pub value: usize,
+    /// The cells the deduction is drawn from
+    pub region: Vec<GridPosition>,
+    /// The filled cells whose values the deduction rests on
+    pub reasons: Vec<GridPosition>,
+    /// The empty cells of the region the value is ruled out of
+    pub excluded: Vec<GridPosition>,
+    pub explanation: String,
+}
+
+#[cfg(feature = "tui")]
+impl Hint {
+    /// Background colors for the cells taking part in the deduction: the
+    /// region, then the cells ruled out and the values ruling them out over
+    /// it. The hint's own cell is left to the selection.
+    pub fn highlights(&self, theme: &Theme) -> HashMap<GridPosition, Color> {
+        let region = self.region.iter().map(|&cell| (cell, theme.hint));
+        let excluded = self
+            .excluded
+            .iter()
+            .map(|&cell| (cell, theme.hint_excluded));
+        let reasons = self.reasons.iter().map(|&cell| (cell, theme.hint_reason));
+        region.chain(excluded).chain(reasons).collect()
+    }
+}
+
+/// The first simple deduction on the board: a cell with only one value left,
+/// or a value with only one cell left in a row, column or box. Cages are
+/// ignored, since they only ever rule out more.
+pub fn find_hint(game: &Game) -> Option<Hint> {
+    let size = game.size();
+    let candidates: HashMap<GridPosition, Vec<usize>> = (0..size)
+        .flat_map(|y| (0..size).map(move |x| (x, y)))
+        .filter(|&position| game.get_cell(position) == Ok(0))
+        .map(|position| (position, game.candidates_for_cell(position).unwrap()))
+        .collect();
+    let subsections = game.subsections();
+    naked_single(&subsections, &candidates, size)
+        .or_else(|| hidden_single(&subsections, &candidates, game.box_size()))
+}
+
+/// The same deductions on a plain grid, for callers without a game, which
+/// keeps a clock
+pub fn find_grid_hint(grid: &Grid) -> Option<Hint> {
+    let size = grid.size();
+    let candidates: HashMap<GridPosition, Vec<usize>> = (0..size)
+        .flat_map(|y| (0..size).map(move |x| (x, y)))
+        .filter(|&position| grid.get_cell(position) == Ok(0))
+        .map(|position| (position, grid.candidates_for_cell(position).unwrap()))
+        .collect();
+    let subsections = grid.get_all_subsection_values();
+    naked_single(&subsections, &candidates, size)
+        .or_else(|| hidden_single(&subsections, &candidates, grid.sub_square_size()))
+}
+
+fn naked_single(
+    subsections: &[GridSubsectionValues],
+    candidates: &HashMap<GridPosition, Vec<usize>>,
+    size: usize,
+) -> Option<Hint> {
+    let (&position, values) = candidates
+        .iter()
+        .filter(|(_, values)| values.len() == 1)
+        .min_by_key(|((x, y), _)| (*y, *x))?;
+    let value = values[0];
+    let region = subsections
+        .iter()
+        .map(|subsection| subsection.grid_subsection)
+        .filter(|cells| cells.clone().any(|cell| cell == position))
+        .flatten()
+        .filter(|&cell| cell != position)
+        .collect();
+    // a cell holding each of the other values
+    let mut reasons = vec![];
+    for other in (1..=size).filter(|&other| other != value) {
+        if let Some(holder) = holder(subsections, position, other) {
+            if !reasons.contains(&holder) {
+                reasons.push(holder);
+            }
+        }
+    }
+    Some(Hint {
+        position,
+        value,
+        region,
+        reasons,
+        excluded: vec![],
+        explanation: format!(
+            "{} must be {}: every other value is already in its row, column or box",
+            position_label(position),
+            value_symbol(value)
+        ),
+    })
+}
+
+fn hidden_single(
+    subsections: &[GridSubsectionValues],
+    candidates: &HashMap<GridPosition, Vec<usize>>,
+    box_size: usize,
+) -> Option<Hint> {
+    for subsection in subsections {
+        let cells: Vec<GridPosition> = subsection.grid_subsection.collect();
+        for value in 1..=box_size * box_size {
+            let mut places = cells.iter().filter(|cell| {
+                candidates
+                    .get(cell)
+                    .is_some_and(|values| values.contains(&value))
+            });
+            if let (Some(&position), None) = (places.next(), places.next()) {
+                let excluded: Vec<GridPosition> = cells
+                    .iter()
+                    .copied()
+                    .filter(|&cell| cell != position && candidates.contains_key(&cell))
+                    .collect();
+                // a cell holding the value that each of the others can see
+                let mut reasons = vec![];
+                for &cell in &excluded {
+                    if let Some(holder) = holder(subsections, cell, value) {
+                        if !reasons.contains(&holder) {
+                            reasons.push(holder);
+                        }
+                    }
+                }
+                return Some(Hint {
+                    position,
+                    value,
+                    reasons,
+                    excluded,
+                    explanation: format!(
+                        "{} can only go in {} within {}",
+                        value_symbol(value),
+                        position_label(position),
+                        subsection_label(subsection.grid_subsection.subsection_type, box_size),
+                    ),
+                    region: cells,
+                });
+            }
+        }
+    }
+    None
+}
+
+/// A cell sharing a row, column, box or extra region with `position` that
+/// holds `value`
+fn holder(
+    subsections: &[GridSubsectionValues],
+    position: GridPosition,
+    value: usize,
+) -> Option<GridPosition> {
+    subsections
+        .iter()
+        .filter(|subsection| {
+            subsection
+                .grid_subsection
+                .clone()
+                .any(|cell| cell == position)
+        })
+        .find_map(|subsection| {
+            subsection
+                .grid_subsection
+                .zip(*subsection)
+                .find(|&(_, held)| held == value)
+                .map(|(cell, _)| cell)
+        })
+}
+
+fn subsection_label(subsection_type: GridSubsectionType, box_size: usize) -> String {
+    match subsection_type {
+        GridSubsectionType::Row(y) => format!("row {}", y + 1),
+        GridSubsectionType::Column(x) => format!("column {}", x + 1),
+        GridSubsectionType::Square(x, y) => {
+            format!("box {}", y * box_size + x + 1)
+        }
+        GridSubsectionType::Diagonal => "the diagonal".to_string(),
+        GridSubsectionType::AntiDiagonal => "the anti-diagonal".to_string(),
+        GridSubsectionType::Window(x, y) => {
+            format!("window {}", y * (box_size - 1) + x + 1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{library::PUZZLES, solver::solve_cells};
+
+    #[test]
+    fn finds_singles() {
+        let mut cells = vec![0; 16];
+        // row 1 is missing only a 4, so r1c4 is a naked single
+        cells[..3].copy_from_slice(&[1, 2, 3]);
+        let game = Game::new(cells).unwrap();
+        let hint = find_hint(&game).unwrap();
+        assert_eq!((hint.position, hint.value), ((3, 0), 4));
+        assert!(hint.region.contains(&(0, 0)));
+        assert!(!hint.region.contains(&(3, 0)));
+        assert_eq!(hint.reasons, [(0, 0), (1, 0), (2, 0)]);
+        assert!(hint.excluded.is_empty());
+
+        // 1 is ruled out of every cell of box 1 but r2c2
+        let game = Game::new(vec![
+            0, 0, 1, 0, //
+            0, 0, 0, 0, //
+            0, 0, 0, 0, //
+            1, 0, 0, 0, //
+        ])
+        .unwrap();
+        let hint = find_hint(&game).unwrap();
+        assert_eq!((hint.position, hint.value), ((1, 1), 1));
+        assert_eq!(hint.explanation, "1 can only go in r2c2 within box 1");
+        assert_eq!(hint.excluded, [(0, 0), (1, 0), (0, 1)]);
+        assert_eq!(hint.reasons, [(2, 0), (0, 3)]);
+
+        let solved = solve_cells(&PUZZLES[0].cells()).unwrap();
+        assert_eq!(find_hint(&Game::new(solved).unwrap()), None);
+
+        let cells = PUZZLES[1].cells();
+        assert_eq!(
+            find_grid_hint(&Grid::new(cells.clone()).unwrap()),
+            find_hint(&Game::new(cells).unwrap())
+        );
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn highlights_by_role() {
+        let mut cells = vec![0; 16];
+        cells[..3].copy_from_slice(&[1, 2, 3]);
+        let hint = find_hint(&Game::new(cells).unwrap()).unwrap();
+        let theme = Theme::DARK;
+        let highlights = hint.highlights(&theme);
+        assert_eq!(highlights[&(0, 0)], theme.hint_reason);
+        assert_eq!(highlights[&(3, 1)], theme.hint);
+        assert!(!highlights.contains_key(&hint.position));
+    }
+}
@@ -0,0 +1,164 @@
+//! The undo history, packed tightly: a long game or a solver run makes tens
+//! of thousands of entries, and saves carry them all
+
+use crate::game::Entry;
+use std::time::Duration;
+
+/// Bytes taken by each entry in `History::to_bytes`
+pub const RECORD_SIZE: usize = 8;
+
+/// An entry in 8 bytes: the cell as an index into the grid, the values it
+/// went between, and the clock in whole milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Record {
+    cell: u16,
+    value: u8,
+    previous_value: u8,
+    at_ms: u32,
+}
+
+/// Entries oldest first, stored as packed records and read back as `Entry`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct History {
+    /// Side of the grid, to turn cell indices back into positions
+    size: usize,
+    records: Vec<Record>,
+}
+
+impl History {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            records: Vec::new(),
+        }
+    }
+
+    fn pack(&self, entry: Entry) -> Record {
+        let (x, y) = entry.position;
+        Record {
+            cell: (y * self.size + x) as u16,
+            value: entry.value as u8,
+            previous_value: entry.previous_value as u8,
+            at_ms: entry.at.as_millis().min(u32::MAX as u128) as u32,
+        }
+    }
+
+    fn unpack(&self, record: Record) -> Entry {
+        let cell = record.cell as usize;
+        Entry {
+            position: (cell % self.size, cell / self.size),
+            value: record.value as usize,
+            previous_value: record.previous_value as usize,
+            at: Duration::from_millis(record.at_ms as u64),
+        }
+    }
+
+    pub fn push(&mut self, entry: Entry) {
+        let record = self.pack(entry);
+        self.records.push(record);
+    }
+
+    pub fn pop(&mut self) -> Option<Entry> {
+        let record = self.records.pop()?;
+        Some(self.unpack(record))
+    }
+
+    pub fn last(&self) -> Option<Entry> {
+        self.records.last().map(|&record| self.unpack(record))
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// Every entry, oldest first
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Entry> + ExactSizeIterator + '_ {
+        self.records.iter().map(|&record| self.unpack(record))
+    }
+
+    /// The records back to back, little endian, `RECORD_SIZE` bytes each
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.records.len() * RECORD_SIZE);
+        for record in &self.records {
+            bytes.extend_from_slice(&record.cell.to_le_bytes());
+            bytes.push(record.value);
+            bytes.push(record.previous_value);
+            bytes.extend_from_slice(&record.at_ms.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Read back the output of `to_bytes` for a grid of side `size`, or
+    /// `None` if it's cut short or names a cell outside the grid
+    pub fn from_bytes(size: usize, bytes: &[u8]) -> Option<Self> {
+        if !bytes.len().is_multiple_of(RECORD_SIZE) {
+            return None;
+        }
+        let records = bytes
+            .chunks_exact(RECORD_SIZE)
+            .map(|chunk| Record {
+                cell: u16::from_le_bytes([chunk[0], chunk[1]]),
+                value: chunk[2],
+                previous_value: chunk[3],
+                at_ms: u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]),
+            })
+            .collect::<Vec<_>>();
+        if records
+            .iter()
+            .any(|record| record.cell as usize >= size * size)
+        {
+            return None;
+        }
+        Some(Self { size, records })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(position: (usize, usize), value: usize, previous_value: usize, ms: u64) -> Entry {
+        Entry {
+            position,
+            value,
+            previous_value,
+            at: Duration::from_millis(ms),
+        }
+    }
+
+    #[test]
+    fn packs_and_unpacks() {
+        let mut history = History::new(9);
+        history.push(entry((4, 0), 9, 0, 1500));
+        history.push(entry((8, 8), 0, 3, 90_000));
+        assert_eq!(history.len(), 2);
+        let last = history.last().unwrap();
+        assert_eq!(
+            (last.position, last.value, last.previous_value),
+            ((8, 8), 0, 3)
+        );
+
+        let bytes = history.to_bytes();
+        assert_eq!(bytes.len(), 2 * RECORD_SIZE);
+        let read = History::from_bytes(9, &bytes).unwrap();
+        assert_eq!(read, history);
+        let first = read.iter().next().unwrap();
+        assert_eq!(first.position, (4, 0));
+        assert_eq!(first.at, Duration::from_millis(1500));
+
+        assert_eq!(History::from_bytes(9, &bytes[..5]), None);
+        assert_eq!(History::from_bytes(4, &bytes), None);
+
+        let popped = history.pop().unwrap();
+        assert_eq!(popped.position, (8, 8));
+        assert_eq!(history.len(), 1);
+    }
+}
@@ -0,0 +1,526 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    str::FromStr,
+};
+
+/// Something a key can be bound to. Screens only look up the actions they
+/// handle, so the same key can mean different things on different screens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    JumpUp,
+    JumpDown,
+    JumpLeft,
+    JumpRight,
+    RowStart,
+    RowEnd,
+    FirstRow,
+    LastRow,
+    GoTo,
+    Confirm,
+    Quit,
+    Help,
+    Clear,
+    Notes,
+    Undo,
+    Redo,
+    Pause,
+    Restart,
+    Hint,
+    Settings,
+    HistoryBack,
+    HistoryForward,
+    NewGame,
+    OpenFile,
+    Paste,
+    Export,
+    ExportCsv,
+    Solver,
+    Step,
+    FastForward,
+    Run,
+    Faster,
+    Slower,
+    Heatmap,
+    Yes,
+    No,
+    NextPuzzle,
+    Statistics,
+    Tutorial,
+    Editor,
+    CheckPuzzle,
+    Cages,
+    PickCell,
+    Reveal,
+    Zen,
+    Shuffle,
+    ExportRecord,
+}
+
+const DEFAULT_BINDINGS: &[(Action, &[&str])] = &[
+    (Action::Up, &["k", "Up"]),
+    (Action::Down, &["j", "Down"]),
+    (Action::Left, &["h", "Left"]),
+    (Action::Right, &["l", "Right"]),
+    (Action::JumpUp, &["K"]),
+    (Action::JumpDown, &["J"]),
+    (Action::JumpLeft, &["H"]),
+    (Action::JumpRight, &["L"]),
+    (Action::RowStart, &["^", "Home"]),
+    (Action::RowEnd, &["$", "End"]),
+    (Action::FirstRow, &["g"]),
+    (Action::LastRow, &["G"]),
+    (Action::GoTo, &[":"]),
+    (Action::Confirm, &["Enter"]),
+    (Action::Quit, &["q"]),
+    (Action::Help, &["?"]),
+    (Action::Clear, &["0", "Backspace"]),
+    (Action::Notes, &["m"]),
+    (Action::Undo, &["u", "Ctrl-z"]),
+    (Action::Redo, &["r", "Ctrl-r"]),
+    (Action::Pause, &["p"]),
+    (Action::Restart, &["R"]),
+    (Action::Hint, &["i"]),
+    (Action::Settings, &["c"]),
+    (Action::HistoryBack, &["["]),
+    (Action::HistoryForward, &["]"]),
+    (Action::NewGame, &["n"]),
+    (Action::OpenFile, &["o"]),
+    (Action::Paste, &["p"]),
+    (Action::Export, &["e"]),
+    (Action::ExportCsv, &["E"]),
+    (Action::Solver, &["s"]),
+    (Action::Step, &["n"]),
+    (Action::FastForward, &["N"]),
+    (Action::Run, &["Space"]),
+    (Action::Faster, &["+", "="]),
+    (Action::Slower, &["-"]),
+    (Action::Heatmap, &["m"]),
+    (Action::Yes, &["y", "Enter"]),
+    (Action::No, &["n", "Esc"]),
+    (Action::NextPuzzle, &["n"]),
+    (Action::Statistics, &["t"]),
+    (Action::Tutorial, &["T"]),
+    (Action::Editor, &["e"]),
+    (Action::CheckPuzzle, &["v"]),
+    (Action::Cages, &["c"]),
+    (Action::PickCell, &["Space"]),
+    (Action::Reveal, &["X"]),
+    (Action::Zen, &["z"]),
+    (Action::Shuffle, &["s"]),
+    (Action::ExportRecord, &["w"]),
+];
+
+/// Where the arrows preset differs from the vim-style defaults: WASD beside
+/// the arrows, and the page keys to jump between boxes
+const ARROW_BINDINGS: &[(Action, &[&str])] = &[
+    (Action::Up, &["Up", "w"]),
+    (Action::Down, &["Down", "s"]),
+    (Action::Left, &["Left", "a"]),
+    (Action::Right, &["Right", "d"]),
+    (Action::JumpUp, &["PageUp", "W"]),
+    (Action::JumpDown, &["PageDown", "S"]),
+    (Action::JumpLeft, &["Ctrl-Left", "A"]),
+    (Action::JumpRight, &["Ctrl-Right", "D"]),
+    (Action::RowStart, &["Home"]),
+    (Action::RowEnd, &["End"]),
+    (Action::Solver, &["v"]),
+];
+
+/// Key names other than single characters, as written in the config file
+const KEY_NAMES: &[(&str, KeyCode)] = &[
+    ("Space", KeyCode::Char(' ')),
+    ("Enter", KeyCode::Enter),
+    ("Esc", KeyCode::Esc),
+    ("Backspace", KeyCode::Backspace),
+    ("Tab", KeyCode::Tab),
+    ("Delete", KeyCode::Delete),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+];
+
+/// A key with the modifiers held, written like `u`, `Ctrl-z` or `PageUp`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidKey(pub String);
+
+impl Display for InvalidKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid key \"{}\"", self.0)
+    }
+}
+
+impl KeyBinding {
+    /// Shift is left out of the comparison since it's already part of the
+    /// character typed, and terminals disagree on whether to report it
+    pub fn matches(&self, key_event: KeyEvent) -> bool {
+        let modifiers = |modifiers: KeyModifiers| modifiers - KeyModifiers::SHIFT;
+        self.code == key_event.code && modifiers(self.modifiers) == modifiers(key_event.modifiers)
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = InvalidKey;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        // a lone `-` is the minus key rather than an empty modifier
+        while let Some((prefix, key)) = rest.split_once('-').filter(|(_, key)| !key.is_empty()) {
+            modifiers |= match prefix.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                _ => return Err(InvalidKey(s.to_string())),
+            };
+            rest = key;
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => KEY_NAMES
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(rest))
+                .map(|(_, code)| *code)
+                .or_else(|| {
+                    let number = rest.strip_prefix(['F', 'f'])?.parse().ok()?;
+                    (1..=12).contains(&number).then_some(KeyCode::F(number))
+                })
+                .ok_or_else(|| InvalidKey(s.to_string()))?,
+        };
+        Ok(Self { code, modifiers })
+    }
+}
+
+impl Display for KeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt-")?;
+        }
+        match KEY_NAMES.iter().find(|(_, code)| *code == self.code) {
+            Some((name, _)) => write!(f, "{name}"),
+            None => match self.code {
+                KeyCode::Char(c) => write!(f, "{c}"),
+                KeyCode::F(number) => write!(f, "F{number}"),
+                code => write!(f, "{code:?}"),
+            },
+        }
+    }
+}
+
+impl Serialize for KeyBinding {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyBinding {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Keys listed in a screen's instructions
+#[derive(Debug, Clone, Copy)]
+pub enum Hint {
+    /// The first key bound to each action
+    Actions(&'static [Action]),
+    /// Keys that can't be rebound, like the digits
+    Fixed(&'static str),
+}
+
+/// A built-in layout of keys, which the config file's own bindings are laid
+/// over
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    /// hjkl to move, as in vim
+    #[default]
+    Vim,
+    /// Arrows or WASD to move, and the page keys to jump between boxes
+    Arrows,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 2] = [Preset::Vim, Preset::Arrows];
+
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Display for Preset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Preset::Vim => "vim",
+            Preset::Arrows => "arrows",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The keys bound to each action. The config file only has to list the
+/// actions it rebinds; the rest keep the preset's keys.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    preset: Preset,
+    bindings: HashMap<Action, Vec<KeyBinding>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::preset(Preset::default())
+    }
+}
+
+impl Keymap {
+    /// The preset's keys, without any rebound
+    pub fn preset(preset: Preset) -> Self {
+        let changes = match preset {
+            Preset::Vim => &[][..],
+            Preset::Arrows => ARROW_BINDINGS,
+        };
+        let bindings = DEFAULT_BINDINGS
+            .iter()
+            .chain(changes)
+            .map(|(action, keys)| {
+                let keys = keys.iter().map(|key| key.parse().unwrap()).collect();
+                (*action, keys)
+            })
+            .collect();
+        Self { preset, bindings }
+    }
+
+    pub fn current_preset(&self) -> Preset {
+        self.preset
+    }
+
+    /// Switch to another preset, keeping the keys rebound on top of it
+    pub fn set_preset(&mut self, preset: Preset) {
+        let rebound = self.rebound();
+        *self = Self::preset(preset);
+        for (action, keys) in rebound {
+            self.bind(action, keys);
+        }
+    }
+
+    /// The actions bound differently from the preset, in the order they're
+    /// declared so the config file comes out the same every time
+    fn rebound(&self) -> BTreeMap<Action, Vec<KeyBinding>> {
+        let preset = Self::preset(self.preset);
+        self.bindings
+            .iter()
+            .filter(|(action, keys)| preset.bindings.get(action) != Some(keys))
+            .map(|(action, keys)| (*action, keys.clone()))
+            .collect()
+    }
+
+    /// The first of `actions` bound to the key pressed
+    pub fn action(&self, key_event: KeyEvent, actions: &[Action]) -> Option<Action> {
+        actions.iter().copied().find(|action| {
+            self.bindings
+                .get(action)
+                .is_some_and(|keys| keys.iter().any(|key| key.matches(key_event)))
+        })
+    }
+
+    pub fn bind(&mut self, action: Action, keys: Vec<KeyBinding>) {
+        self.bindings.insert(action, keys);
+    }
+
+    /// Instruction text for a hint, like `<h>/<j>/<k>/<l>`
+    pub fn describe(&self, hint: Hint) -> String {
+        match hint {
+            Hint::Actions(actions) => actions
+                .iter()
+                .filter_map(|action| self.bindings.get(action)?.first())
+                .map(|key| format!("<{key}>"))
+                .collect::<Vec<_>>()
+                .join("/"),
+            Hint::Fixed(keys) => keys.to_string(),
+        }
+    }
+
+    /// Labels paired with their instruction text, for a screen's footer
+    pub fn hints<'a>(&self, hints: &[(&'a str, Hint)]) -> Vec<(&'a str, String)> {
+        hints
+            .iter()
+            .map(|&(label, hint)| (label, self.describe(hint)))
+            .collect()
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The keys table of the config file: the preset, and the actions rebound
+/// on top of it
+#[derive(Serialize, Deserialize)]
+struct KeymapTable {
+    #[serde(default, skip_serializing_if = "Preset::is_default")]
+    preset: Preset,
+    #[serde(flatten)]
+    bindings: BTreeMap<Action, Vec<KeyBinding>>,
+}
+
+impl Serialize for Keymap {
+    /// Only the actions bound differently from the preset are written
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        KeymapTable {
+            preset: self.preset,
+            bindings: self.rebound(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Keymap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let table = KeymapTable::deserialize(deserializer)?;
+        let mut keymap = Self::preset(table.preset);
+        for (action, keys) in table.bindings {
+            keymap.bind(action, keys);
+        }
+        Ok(keymap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn parse_key_binding() {
+        let binding = |code, modifiers| KeyBinding { code, modifiers };
+        assert_eq!(
+            "u".parse(),
+            Ok(binding(KeyCode::Char('u'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            "-".parse(),
+            Ok(binding(KeyCode::Char('-'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            "Ctrl-z".parse(),
+            Ok(binding(KeyCode::Char('z'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            "pageup".parse(),
+            Ok(binding(KeyCode::PageUp, KeyModifiers::NONE))
+        );
+        assert_eq!("F5".parse(), Ok(binding(KeyCode::F(5), KeyModifiers::NONE)));
+        assert_eq!(
+            "Hyper-x".parse::<KeyBinding>(),
+            Err(InvalidKey("Hyper-x".to_string()))
+        );
+        for key in ["Ctrl-z", "Space", "-", "F5", "Alt-Left"] {
+            assert_eq!(key.parse::<KeyBinding>().unwrap().to_string(), key);
+        }
+    }
+
+    #[test]
+    fn action() {
+        let mut keymap = Keymap::default();
+        let h = key(KeyCode::Char('h'), KeyModifiers::NONE);
+        assert_eq!(
+            keymap.action(h, &[Action::Up, Action::Left]),
+            Some(Action::Left)
+        );
+        assert_eq!(keymap.action(h, &[Action::Up]), None);
+        let shifted = key(KeyCode::Char('H'), KeyModifiers::SHIFT);
+        assert_eq!(
+            keymap.action(shifted, &[Action::JumpLeft]),
+            Some(Action::JumpLeft)
+        );
+        let ctrl_r = key(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert_eq!(
+            keymap.action(ctrl_r, &[Action::Undo, Action::Redo]),
+            Some(Action::Redo)
+        );
+        keymap.bind(Action::Left, vec!["a".parse().unwrap()]);
+        assert_eq!(keymap.action(h, &[Action::Left]), None);
+        assert_eq!(
+            keymap.describe(Hint::Actions(&[Action::Left, Action::Right])),
+            "<a>/<l>"
+        );
+    }
+
+    #[test]
+    fn deserialize_overrides() {
+        let keymap: Keymap = toml::from_str(r#"undo = ["Ctrl-z", "u"]"#).unwrap();
+        assert_eq!(keymap.describe(Hint::Actions(&[Action::Undo])), "<Ctrl-z>");
+        assert_eq!(keymap.describe(Hint::Actions(&[Action::Up])), "<k>");
+        assert_eq!(
+            toml::to_string(&keymap).unwrap(),
+            "undo = [\"Ctrl-z\", \"u\"]\n"
+        );
+        assert!(toml::from_str::<Keymap>(r#"undo = ["Hyper-z"]"#).is_err());
+    }
+
+    #[test]
+    fn serialize_in_a_stable_order() {
+        let mut keymap = Keymap::default();
+        for action in [Action::Shuffle, Action::Undo, Action::Up, Action::Hint] {
+            keymap.bind(action, vec!["F1".parse().unwrap()]);
+        }
+        let actions: Vec<String> = toml::to_string(&keymap)
+            .unwrap()
+            .lines()
+            .map(|line| line.split(" = ").next().unwrap().to_string())
+            .collect();
+        assert_eq!(actions, ["up", "undo", "hint", "shuffle"]);
+    }
+
+    #[test]
+    fn arrows_preset() {
+        let mut keymap: Keymap = toml::from_str(
+            r#"
+            preset = "arrows"
+            undo = ["Ctrl-z"]
+            "#,
+        )
+        .unwrap();
+        let s = key(KeyCode::Char('s'), KeyModifiers::NONE);
+        assert_eq!(keymap.action(s, &[Action::Down]), Some(Action::Down));
+        assert_eq!(keymap.action(s, &[Action::Solver]), None);
+        let page_down = key(KeyCode::PageDown, KeyModifiers::NONE);
+        assert_eq!(
+            keymap.action(page_down, &[Action::JumpDown]),
+            Some(Action::JumpDown)
+        );
+        assert_eq!(
+            toml::to_string(&keymap).unwrap(),
+            "preset = \"arrows\"\nundo = [\"Ctrl-z\"]\n"
+        );
+        keymap.set_preset(Preset::Vim);
+        assert_eq!(keymap.describe(Hint::Actions(&[Action::Down])), "<j>");
+        assert_eq!(keymap.describe(Hint::Actions(&[Action::Undo])), "<Ctrl-z>");
+    }
+}
@@ -1,6 +1,93 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod announce;
+#[cfg(feature = "tui")]
 pub mod app;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "std")]
+pub mod broadcast;
+#[cfg(feature = "std")]
+pub mod browser;
+pub mod cage;
 pub mod checker;
+#[cfg(feature = "std")]
+pub mod clipboard;
+#[cfg(feature = "tui")]
+pub mod config;
+pub mod constraint;
+#[cfg(feature = "std")]
+pub mod coop;
+#[cfg(feature = "std")]
+pub mod difficulty;
+pub mod dlx;
+#[cfg(feature = "std")]
+pub mod editor;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod format;
+#[cfg(feature = "std")]
 pub mod game;
+#[cfg(feature = "std")]
+pub mod generator;
 pub mod grid;
+#[cfg(feature = "std")]
+pub mod hint;
+#[cfg(feature = "std")]
+pub mod history;
+#[cfg(feature = "tui")]
+pub mod keymap;
+#[cfg(feature = "std")]
+pub mod library;
+#[cfg(feature = "std")]
+pub mod logic;
+#[cfg(feature = "tui")]
+pub mod motion;
+#[cfg(feature = "std")]
+pub mod net;
+#[cfg(feature = "online")]
+pub mod online;
+#[cfg(feature = "std")]
+pub mod paths;
+#[cfg(feature = "tui")]
+pub mod popup;
+#[cfg(feature = "std")]
+pub mod progress;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "std")]
+pub mod race;
+#[cfg(feature = "std")]
+pub mod record;
+#[cfg(feature = "std")]
+pub mod rng;
+pub mod samurai;
+#[cfg(feature = "std")]
+pub mod save;
+#[cfg(feature = "tui")]
+pub mod screen;
+#[cfg(feature = "std")]
+pub mod serve;
 pub mod solver;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "tui")]
+pub mod theme;
+#[cfg(feature = "std")]
+pub mod timer;
+#[cfg(feature = "tui")]
+pub mod toast;
+#[cfg(feature = "tui")]
 pub mod tui;
+#[cfg(feature = "std")]
+pub mod tutorial;
+#[cfg(feature = "std")]
+pub mod variant;
+#[cfg(feature = "wasm")]
+pub mod wasm;
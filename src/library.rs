@@ -0,0 +1,282 @@
+use crate::{
+    cage::{parse_cages, Cage},
+    constraint::Constraint,
+    difficulty::Difficulty,
+    game::Game,
+    grid::GridError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Puzzle {
+    pub name: &'static str,
+    pub difficulty: Difficulty,
+    /// One character per cell, row by row, with `.` or `0` for empty cells
+    pub givens: &'static str,
+    /// Killer cage layout and sums, in the format read by `parse_cages`
+    pub cages: Option<(&'static str, &'static [usize])>,
+    /// Whether both main diagonals also hold every value once
+    pub diagonals: bool,
+    /// Rules between particular cells, in the format read by
+    /// `Constraint::parse`
+    pub constraints: &'static [&'static str],
+}
+
+pub const PUZZLES: [Puzzle; 13] = [
+    Puzzle {
+        name: "Warm-up",
+        difficulty: Difficulty::Easy,
+        givens: "467100805912835607085647192296351470708920351531408926073064510624519783159783064",
+        cages: None,
+        diagonals: false,
+        constraints: &[],
+    },
+    Puzzle {
+        name: "Classic",
+        difficulty: Difficulty::Easy,
+        givens: "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+        cages: None,
+        diagonals: false,
+        constraints: &[],
+    },
+    Puzzle {
+        name: "Euler I",
+        difficulty: Difficulty::Medium,
+        givens: "003020600900305001001806400008102900700000008006708200002609500800203009005010300",
+        cages: None,
+        diagonals: false,
+        constraints: &[],
+    },
+    Puzzle {
+        name: "Euler II",
+        difficulty: Difficulty::Medium,
+        givens: "200080300060070084030500209000105408000000000402706000301007040720040060004010003",
+        cages: None,
+        diagonals: false,
+        constraints: &[],
+    },
+    Puzzle {
+        name: "Seventeen",
+        difficulty: Difficulty::Hard,
+        givens: "52...6.........7.13...........4..8..6......5...........418.........3..2...87.....",
+        cages: None,
+        diagonals: false,
+        constraints: &[],
+    },
+    Puzzle {
+        name: "Sparse",
+        difficulty: Difficulty::Hard,
+        givens: "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......",
+        cages: None,
+        diagonals: false,
+        constraints: &[],
+    },
+    Puzzle {
+        name: "Everest",
+        difficulty: Difficulty::Expert,
+        givens: "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+        cages: None,
+        diagonals: false,
+        constraints: &[],
+    },
+    Puzzle {
+        name: "Easter Monster",
+        difficulty: Difficulty::Expert,
+        givens: "1.......2.9.4...5...6...7...5.9.3.......7.......85..4.7.....6...3...9.8...2.....1",
+        cages: None,
+        diagonals: false,
+        constraints: &[],
+    },
+    Puzzle {
+        name: "Killer I",
+        difficulty: Difficulty::Hard,
+        givens: "...............................................6....................3............",
+        cages: Some((
+            "abbbbcdddaefcccgdhieffjkgghlllmjknohppqmrnnsspqqrrnttuvwxyyzAAuvBxCCzDDEFBBCCzDGE",
+            &[
+                13, 22, 13, 20, 11, 16, 21, 10, 2, 10, 8, 17, 6, 22, 7, 10, 18, 22, 11, 6, 9, 11,
+                7, 6, 14, 19, 6, 15, 12, 16, 11, 6, 8,
+            ],
+        )),
+        diagonals: false,
+        constraints: &[],
+    },
+    Puzzle {
+        name: "Diagonal I",
+        difficulty: Difficulty::Medium,
+        givens: "2...79...6...2...........71...3..4.5...5...93...9...............5.....6....6.18.4",
+        cages: None,
+        diagonals: true,
+        constraints: &[],
+    },
+    Puzzle {
+        name: "Arrows I",
+        difficulty: Difficulty::Hard,
+        givens: "5...........195....98....6.8...6...34..8.3..17...2...6.6....2.....4.9..5....8...9",
+        cages: None,
+        diagonals: false,
+        constraints: &[
+            "arrow r3c9 r2c8 r2c7",
+            "arrow r7c1 r6c1 r5c2",
+            "arrow r7c6 r6c5 r5c5",
+            "arrow r8c2 r7c2 r8c1",
+            "arrow r1c6 r1c5 r2c4",
+            "arrow r5c3 r4c2 r3c1",
+            "arrow r9c3 r8c4 r7c3",
+            "arrow r7c9 r8c8 r9c7",
+        ],
+    },
+    Puzzle {
+        name: "Kropki I",
+        difficulty: Difficulty::Hard,
+        givens: "..3...6.......5..1......4.......2...7.......8......2.....6.95..8....3............",
+        cages: None,
+        diagonals: false,
+        constraints: &[
+            "black r1c1 r1c2",
+            "white r1c5 r1c6",
+            "black r1c5 r2c5",
+            "white r1c7 r1c8",
+            "white r2c2 r2c3",
+            "white r2c2 r3c2",
+            "white r2c4 r2c5",
+            "white r2c5 r2c6",
+            "white r2c6 r3c6",
+            "black r2c7 r3c7",
+            "white r2c8 r2c9",
+            "white r3c2 r4c2",
+            "white r3c4 r3c5",
+            "white r3c5 r3c6",
+            "black r3c9 r4c9",
+            "white r4c1 r4c2",
+            "black r4c2 r4c3",
+            "black r4c2 r5c2",
+            "white r4c3 r5c3",
+            "white r4c5 r4c6",
+            "black r4c5 r5c5",
+            "black r4c6 r5c6",
+            "white r4c8 r4c9",
+            "white r5c2 r6c2",
+            "white r5c4 r5c5",
+            "black r5c6 r6c6",
+            "white r5c7 r6c7",
+            "white r5c8 r6c8",
+            "black r6c2 r6c3",
+            "white r6c3 r6c4",
+            "white r6c4 r7c4",
+            "white r6c5 r6c6",
+            "white r6c5 r7c5",
+            "white r6c6 r7c6",
+            "black r6c7 r6c8",
+            "white r6c8 r6c9",
+            "white r6c9 r7c9",
+            "black r7c3 r8c3",
+            "white r7c5 r7c6",
+            "black r8c3 r8c4",
+            "white r8c3 r9c3",
+            "black r8c4 r9c4",
+            "white r8c7 r8c8",
+            "white r9c3 r9c4",
+        ],
+    },
+    Puzzle {
+        name: "Greater-than I",
+        difficulty: Difficulty::Hard,
+        givens: "...9...76......5..8...6...9.7.1.5..8...4...27....36......6...............5.....93",
+        cages: None,
+        diagonals: false,
+        constraints: &[
+            "less r1c6 r2c6",
+            "less r2c2 r2c3",
+            "greater r2c3 r2c4",
+            "less r2c6 r2c7",
+            "greater r2c8 r2c9",
+            "greater r4c1 r5c1",
+            "greater r4c6 r4c7",
+            "less r4c7 r5c7",
+            "greater r4c9 r5c9",
+            "greater r5c1 r5c2",
+            "greater r5c1 r6c1",
+            "less r6c1 r6c2",
+            "less r6c5 r7c5",
+            "less r6c6 r6c7",
+            "greater r6c7 r6c8",
+            "greater r6c7 r7c7",
+            "less r7c1 r8c1",
+            "less r7c3 r8c3",
+            "less r7c5 r7c6",
+            "greater r7c5 r8c5",
+            "greater r8c1 r9c1",
+            "less r8c2 r8c3",
+            "greater r8c3 r8c4",
+            "greater r8c5 r9c5",
+            "greater r8c6 r8c7",
+            "less r8c7 r8c8",
+            "less r8c7 r9c7",
+            "greater r8c8 r8c9",
+            "greater r9c1 r9c2",
+            "less r9c5 r9c6",
+            "less r9c7 r9c8",
+            "greater r9c8 r9c9",
+        ],
+    },
+];
+
+impl Puzzle {
+    pub fn cells(&self) -> Vec<usize> {
+        self.givens
+            .chars()
+            .map(|c| c.to_digit(10).unwrap_or(0) as usize)
+            .collect()
+    }
+
+    /// Whether only the rows, columns and boxes constrain the puzzle
+    pub fn is_classic(&self) -> bool {
+        self.cages.is_none() && !self.diagonals && self.constraints.is_empty()
+    }
+
+    pub fn cages(&self) -> Vec<Cage> {
+        self.cages
+            .map(|(layout, sums)| parse_cages(layout, sums, 9).expect("library cages are valid"))
+            .unwrap_or_default()
+    }
+
+    pub fn constraints(&self) -> Vec<Constraint> {
+        self.constraints
+            .iter()
+            .map(|text| Constraint::parse(text, 9).expect("library constraints are valid"))
+            .collect()
+    }
+
+    pub fn game(&self) -> Result<Game, GridError> {
+        let mut game = Game::new(self.cells())?
+            .with_cages(self.cages())
+            .with_constraints(self.constraints());
+        if self.diagonals {
+            game = game.with_diagonals();
+        }
+        game.difficulty = Some(self.difficulty);
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cage::check_tiling;
+
+    #[test]
+    fn puzzles_are_valid() {
+        for puzzle in PUZZLES {
+            let game = puzzle.game().unwrap();
+            assert_eq!(game.size(), 9, "{}", puzzle.name);
+            assert!(game.conflicts.is_empty(), "{}", puzzle.name);
+            assert_eq!(game.difficulty, Some(puzzle.difficulty));
+            let cages = game.cages();
+            assert!(
+                cages.is_empty() || check_tiling(cages, 9).is_ok(),
+                "{}",
+                puzzle.name
+            );
+        }
+    }
+}
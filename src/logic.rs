@@ -0,0 +1,382 @@
+use crate::{
+    difficulty::Difficulty,
+    grid::{Grid, Regions},
+};
+use std::fmt::Display;
+
+/// A deduction a person can make without guessing, from simplest to hardest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Technique {
+    NakedSingle,
+    HiddenSingle,
+    LockedCandidates,
+    NakedPair,
+    HiddenPair,
+    NakedTriple,
+    XWing,
+}
+
+impl Technique {
+    pub const ALL: [Technique; 7] = [
+        Technique::NakedSingle,
+        Technique::HiddenSingle,
+        Technique::LockedCandidates,
+        Technique::NakedPair,
+        Technique::HiddenPair,
+        Technique::NakedTriple,
+        Technique::XWing,
+    ];
+
+    /// The grade of a puzzle that needs this technique and nothing harder
+    pub fn difficulty(self) -> Difficulty {
+        match self {
+            Technique::NakedSingle | Technique::HiddenSingle => Difficulty::Easy,
+            Technique::LockedCandidates | Technique::NakedPair => Difficulty::Medium,
+            Technique::HiddenPair | Technique::NakedTriple | Technique::XWing => Difficulty::Hard,
+        }
+    }
+}
+
+impl Display for Technique {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Technique::NakedSingle => "Naked single",
+            Technique::HiddenSingle => "Hidden single",
+            Technique::LockedCandidates => "Locked candidates",
+            Technique::NakedPair => "Naked pair",
+            Technique::HiddenPair => "Hidden pair",
+            Technique::NakedTriple => "Naked triple",
+            Technique::XWing => "X-Wing",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// How far the techniques got with a puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rating {
+    pub difficulty: Difficulty,
+    /// Every technique used, simplest first
+    pub techniques: Vec<Technique>,
+    /// False if the techniques ran out before the grid was full, which
+    /// leaves guessing, and an expert grade
+    pub solved: bool,
+}
+
+/// Grade a puzzle by solving it the way a person would, always reaching for
+/// the simplest technique that makes progress. Only rows, columns and boxes
+/// are considered.
+pub fn rate(cells: &[usize]) -> Option<Rating> {
+    rate_in(cells, Regions::default())
+}
+
+/// Grade a puzzle whose extra `regions` also hold every value once, using
+/// them like any row, column or box
+pub fn rate_in(cells: &[usize], regions: Regions) -> Option<Rating> {
+    let mut board = Board::new(cells, regions)?;
+    let mut techniques = vec![];
+    while !board.is_full() {
+        let Some(technique) = Technique::ALL
+            .into_iter()
+            .find(|&technique| board.apply(technique))
+        else {
+            break;
+        };
+        if !techniques.contains(&technique) {
+            techniques.push(technique);
+        }
+    }
+    techniques.sort();
+    let solved = board.is_full();
+    let difficulty = match solved {
+        true => techniques
+            .iter()
+            .map(|technique| technique.difficulty())
+            .max()
+            .unwrap_or(Difficulty::Easy),
+        false => Difficulty::Expert,
+    };
+    Some(Rating {
+        difficulty,
+        techniques,
+        solved,
+    })
+}
+
+/// Values and pencil marks, as bitmasks with bit `v` set for value `v`
+struct Board {
+    size: usize,
+    values: Vec<usize>,
+    candidates: Vec<u32>,
+    /// Cell indices of every row, then every column, then every box, then
+    /// every extra region
+    units: Vec<Vec<usize>>,
+}
+
+impl Board {
+    fn new(cells: &[usize], regions: Regions) -> Option<Self> {
+        let grid = Grid::new(cells.to_vec()).ok()?;
+        let (size, box_size) = (grid.size(), grid.sub_square_size());
+        let rows = (0..size).map(|y| (0..size).map(|x| y * size + x).collect());
+        let columns = (0..size).map(|x| (0..size).map(|y| y * size + x).collect());
+        let boxes = (0..size).map(|b| {
+            let (bx, by) = (b % box_size * box_size, b / box_size * box_size);
+            (0..size)
+                .map(|k| (by + k / box_size) * size + bx + k % box_size)
+                .collect()
+        });
+        let all = (1..=size).fold(0, |mask, value| mask | 1 << value);
+        let mut board = Self {
+            size,
+            values: vec![0; cells.len()],
+            candidates: vec![all; cells.len()],
+            units: rows
+                .chain(columns)
+                .chain(boxes)
+                .chain(regions.units(size))
+                .collect(),
+        };
+        for (i, &value) in cells.iter().enumerate() {
+            if value != 0 {
+                if board.candidates[i] & 1 << value == 0 {
+                    return None;
+                }
+                board.place(i, value);
+            }
+        }
+        Some(board)
+    }
+
+    fn is_full(&self) -> bool {
+        self.values.iter().all(|&value| value != 0)
+    }
+
+    fn place(&mut self, i: usize, value: usize) {
+        self.values[i] = value;
+        self.candidates[i] = 0;
+        for unit in self.units.iter().filter(|unit| unit.contains(&i)) {
+            for &peer in unit {
+                self.candidates[peer] &= !(1 << value);
+            }
+        }
+    }
+
+    /// Remove `mask` from the candidates of `cells`, returning whether any
+    /// were there to remove
+    fn eliminate(&mut self, cells: impl IntoIterator<Item = usize>, mask: u32) -> bool {
+        let mut changed = false;
+        for i in cells {
+            changed |= self.candidates[i] & mask != 0;
+            self.candidates[i] &= !mask;
+        }
+        changed
+    }
+
+    /// The cells of `unit` that could hold `value`
+    fn places(&self, unit: &[usize], value: usize) -> Vec<usize> {
+        unit.iter()
+            .copied()
+            .filter(|&i| self.candidates[i] & 1 << value != 0)
+            .collect()
+    }
+
+    fn apply(&mut self, technique: Technique) -> bool {
+        match technique {
+            Technique::NakedSingle => self.naked_single(),
+            Technique::HiddenSingle => self.hidden_single(),
+            Technique::LockedCandidates => self.locked_candidates(),
+            Technique::NakedPair => self.naked_subset(2),
+            Technique::HiddenPair => self.hidden_pair(),
+            Technique::NakedTriple => self.naked_subset(3),
+            Technique::XWing => self.x_wing(),
+        }
+    }
+
+    fn naked_single(&mut self) -> bool {
+        let single = (0..self.values.len()).find(|&i| self.candidates[i].count_ones() == 1);
+        match single {
+            Some(i) => {
+                self.place(i, self.candidates[i].trailing_zeros() as usize);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn hidden_single(&mut self) -> bool {
+        for u in 0..self.units.len() {
+            for value in 1..=self.size {
+                if let [i] = self.places(&self.units[u], value)[..] {
+                    self.place(i, value);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// A value confined to one line within a box can't be elsewhere on that
+    /// line, and one confined to one box within a line can't be elsewhere in
+    /// that box. The same goes for any two units that overlap, like a box
+    /// and a window.
+    fn locked_candidates(&mut self) -> bool {
+        for u in 0..self.units.len() {
+            for value in 1..=self.size {
+                let places = self.places(&self.units[u], value);
+                if places.len() < 2 {
+                    continue;
+                }
+                let others: Vec<usize> = self
+                    .units
+                    .iter()
+                    .enumerate()
+                    .filter(|&(o, unit)| o != u && places.iter().all(|i| unit.contains(i)))
+                    .flat_map(|(_, unit)| unit.iter().copied())
+                    .filter(|i| !places.contains(i))
+                    .collect();
+                if self.eliminate(others, 1 << value) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// `n` cells of a unit that share `n` candidates between them take those
+    /// values, so no other cell in the unit can
+    fn naked_subset(&mut self, n: usize) -> bool {
+        for u in 0..self.units.len() {
+            let unit = self.units[u].clone();
+            let open: Vec<usize> = unit
+                .iter()
+                .copied()
+                .filter(|&i| (2..=n as u32).contains(&self.candidates[i].count_ones()))
+                .collect();
+            for subset in subsets(&open, n) {
+                let mask = subset.iter().fold(0, |mask, &i| mask | self.candidates[i]);
+                if mask.count_ones() as usize != n {
+                    continue;
+                }
+                let rest = unit.iter().copied().filter(|i| !subset.contains(i));
+                if self.eliminate(rest, mask) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Two values that can only go in the same two cells of a unit rule out
+    /// every other candidate of those cells
+    fn hidden_pair(&mut self) -> bool {
+        for u in 0..self.units.len() {
+            let unit = self.units[u].clone();
+            let pairs: Vec<(usize, Vec<usize>)> = (1..=self.size)
+                .map(|value| (value, self.places(&unit, value)))
+                .filter(|(_, places)| places.len() == 2)
+                .collect();
+            for (a, (first, places)) in pairs.iter().enumerate() {
+                for (second, _) in pairs[a + 1..].iter().filter(|(_, other)| other == places) {
+                    let keep = 1 << first | 1 << second;
+                    if self.eliminate(places.iter().copied(), !keep) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// A value that can only go in the same two columns of two rows must be
+    /// in those columns on those rows, so it's ruled out of the rest of both
+    /// columns, and the same with rows and columns swapped
+    fn x_wing(&mut self) -> bool {
+        for (lines, crossing) in [(0, self.size), (self.size, 0)] {
+            for value in 1..=self.size {
+                let positions: Vec<(usize, Vec<usize>)> = (0..self.size)
+                    .map(|line| {
+                        let places = self.places(&self.units[lines + line], value);
+                        let offsets = places
+                            .into_iter()
+                            .map(|i| self.units[lines + line].iter().position(|&c| c == i))
+                            .map(Option::unwrap)
+                            .collect();
+                        (line, offsets)
+                    })
+                    .filter(|(_, offsets): &(usize, Vec<usize>)| offsets.len() == 2)
+                    .collect();
+                for (a, (first, offsets)) in positions.iter().enumerate() {
+                    for (second, _) in positions[a + 1..].iter().filter(|(_, o)| o == offsets) {
+                        let rest: Vec<usize> = offsets
+                            .iter()
+                            .flat_map(|&offset| self.units[crossing + offset].iter().copied())
+                            .filter(|i| {
+                                ![first, second]
+                                    .iter()
+                                    .any(|&&line| self.units[lines + line].contains(i))
+                            })
+                            .collect();
+                        if self.eliminate(rest, 1 << value) {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Every way of picking `n` items from `items`, in order
+fn subsets(items: &[usize], n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    (0..items.len())
+        .flat_map(|i| {
+            subsets(&items[i + 1..], n - 1)
+                .into_iter()
+                .map(move |mut rest| {
+                    rest.insert(0, items[i]);
+                    rest
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{format::text, library::PUZZLES};
+
+    #[test]
+    fn rates_puzzles() {
+        let rating = rate(&PUZZLES[1].cells()).unwrap();
+        assert_eq!(rating.difficulty, Difficulty::Easy);
+        assert!(rating.solved);
+        assert!(rating
+            .techniques
+            .iter()
+            .all(|technique| technique.difficulty() == Difficulty::Easy));
+
+        let rating = rate(&PUZZLES[7].cells()).unwrap();
+        assert_eq!(rating.difficulty, Difficulty::Expert);
+        assert!(!rating.solved);
+
+        assert_eq!(rate(&[1, 1, 0, 0]), None);
+    }
+
+    #[test]
+    fn finds_x_wing() {
+        // a textbook X-Wing on 7 in rows 2 and 8
+        let cells = text::parse(
+            "1.....569492.561.8.561.924...964.8.1.64.1....218.356.4.4.5...169.5.614.2621.....5",
+        )
+        .unwrap();
+        let mut board = Board::new(&cells, Regions::default()).unwrap();
+        while board.apply(Technique::NakedSingle) || board.apply(Technique::HiddenSingle) {}
+        let before = board.candidates.clone();
+        assert!(board.apply(Technique::XWing));
+        assert_ne!(board.candidates, before);
+    }
+}
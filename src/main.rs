@@ -1,8 +1,634 @@
-use sudoku::{app::App, tui};
+use clap::{Parser, Subcommand};
+use serde_json::{json, Value};
+use std::{
+    fs, io,
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+use sudoku::{
+    app::App,
+    batch::{self, Outcome, Solved, Summary},
+    broadcast::Broadcast,
+    coop::Coop,
+    difficulty::Difficulty,
+    export::{self, SvgOptions},
+    format::{self, lenient, text, FormatError, Layout},
+    game::Game,
+    generator,
+    grid::Grid,
+    logic,
+    net::{Message, Peer},
+    paths::Paths,
+    race::Race,
+    rng::Rng,
+    serve,
+    solver::{self, Validity},
+    stats::{self, Stats},
+    timer::format_duration,
+    tui,
+    variant::Variant,
+};
 
-fn main() -> std::io::Result<()> {
+#[derive(Parser)]
+#[command(version, about = "Sudoku in the terminal")]
+struct Cli {
+    /// Keep settings, saves and statistics in DIR, the current directory if
+    /// not given, instead of the usual places for the platform
+    #[arg(long, global = true, value_name = "DIR", num_args = 0..=1, default_missing_value = ".")]
+    portable: Option<PathBuf>,
+    /// Let others watch the games played, by connecting to ADDRESS with
+    /// `sudoku watch`. Each event is sent as a line of JSON.
+    #[arg(long, value_name = "ADDRESS")]
+    broadcast: Option<String>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Play the puzzle in a file
+    Play {
+        /// A .sdk or .txt puzzle
+        path: PathBuf,
+    },
+    /// Solve the puzzle in a file and print the solution
+    Solve {
+        path: PathBuf,
+        /// Read one puzzle per line and report timings for each
+        #[arg(long)]
+        batch: bool,
+        /// Print the results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print newly generated puzzles, one per line
+    Generate {
+        #[arg(long, default_value = "medium")]
+        difficulty: Difficulty,
+        /// How many puzzles to generate
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+        /// Seed for reproducible puzzles, otherwise taken from the clock
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Print 4x4 puzzles instead of 9x9
+        #[arg(long)]
+        mini: bool,
+        /// Follow each puzzle with its solution
+        #[arg(long)]
+        solutions: bool,
+        /// Print the puzzles as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check that a puzzle's givens are consistent and it has exactly one
+    /// solution. Exits with 0 if so, 1 if the puzzle can't be read, 3 if
+    /// givens clash, 4 if it has no solution and 5 if it has several.
+    Check {
+        /// A puzzle file, or a puzzle written out on one line
+        puzzle: String,
+        /// Print the findings as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Rewrite a puzzle from one layout to another
+    Convert {
+        /// Layout of the input: sdk, line, grid or csv
+        #[arg(long)]
+        from: Layout,
+        /// Layout to write: sdk, line, grid or csv
+        #[arg(long)]
+        to: Layout,
+        /// File to read, otherwise standard input
+        input: Option<PathBuf>,
+        /// File to write, otherwise standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Show the statistics of games won so far
+    Stats {
+        /// Print the statistics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Lay a puzzle out for printing, as a ruled grid of text, a web page or
+    /// an image
+    Print {
+        /// A puzzle file, or a puzzle written out on one line
+        puzzle: String,
+        /// Write a standalone HTML page instead of text
+        #[arg(long)]
+        html: bool,
+        /// Draw an SVG image instead of text
+        #[arg(long, conflicts_with = "html")]
+        svg: bool,
+        /// File to write, otherwise standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Grade a puzzle by the techniques needed to solve it
+    Rate {
+        /// A puzzle file, or a puzzle written out on one line
+        puzzle: String,
+        /// Print the grade as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Race another player over the network on the same new puzzle. One
+    /// player hosts and the other joins the host's address.
+    Race {
+        /// Address of the host to join, or to listen on with --host
+        address: String,
+        /// Wait for the other player instead of joining one
+        #[arg(long)]
+        host: bool,
+        /// Difficulty of the puzzle, when hosting
+        #[arg(long, default_value = "medium")]
+        difficulty: Difficulty,
+    },
+    /// Fill in the same new puzzle together with another player over the
+    /// network. One player hosts and the other joins the host's address.
+    Coop {
+        /// Address of the host to join, or to listen on with --host
+        address: String,
+        /// Wait for the other player instead of joining one
+        #[arg(long)]
+        host: bool,
+        /// Difficulty of the puzzle, when hosting
+        #[arg(long, default_value = "medium")]
+        difficulty: Difficulty,
+    },
+    /// Follow a game broadcast with --broadcast, without playing
+    Watch {
+        /// Address the game is broadcast on
+        address: String,
+    },
+    /// Answer solve, generate, rate and check requests over HTTP, as JSON
+    /// POSTed to /solve, /generate, /rate and /check
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        address: String,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let paths = cli.portable.map_or_else(Paths::platform, Paths::portable);
+    let app = match cli.command {
+        None => App::new(paths),
+        Some(Command::Play { path }) => match format::load_game(&path) {
+            Ok(game) => App::with_game(paths, game),
+            Err(error) => {
+                eprintln!("{}: {error}", path.display());
+                return ExitCode::FAILURE;
+            }
+        },
+        Some(Command::Solve { path, batch, json }) => {
+            return match batch {
+                true => solve_batch(&path, json),
+                false => solve(&path, json),
+            }
+        }
+        Some(Command::Generate {
+            difficulty,
+            count,
+            seed,
+            mini,
+            solutions,
+            json,
+        }) => {
+            let variant = match mini {
+                true => Variant::Mini,
+                false => Variant::Classic,
+            };
+            let mut rng = seed.map_or_else(Rng::from_time, Rng::new);
+            let puzzles = (0..count).map(|_| generator::generate(variant, difficulty, &mut rng));
+            return generate(puzzles, solutions, json);
+        }
+        Some(Command::Check { puzzle, json }) => return check(&puzzle, json),
+        Some(Command::Rate { puzzle, json }) => return rate(&puzzle, json),
+        Some(Command::Print {
+            puzzle,
+            html,
+            svg,
+            output,
+        }) => return print(&puzzle, html, svg, output.as_deref()),
+        Some(Command::Stats { json }) => return stats(&paths, json),
+        Some(Command::Serve { address }) => return serve(&address),
+        Some(Command::Watch { address }) => match Peer::connect(&address) {
+            Ok(peer) => App::watching(paths, peer),
+            Err(error) => {
+                eprintln!("{address}: {error}");
+                return ExitCode::FAILURE;
+            }
+        },
+        Some(Command::Race {
+            address,
+            host,
+            difficulty,
+        }) => match meet(&address, host, difficulty) {
+            Ok((game, peer)) => App::with_race(paths, game, Race::new(peer)),
+            Err(error) => {
+                eprintln!("{address}: {error}");
+                return ExitCode::FAILURE;
+            }
+        },
+        Some(Command::Coop {
+            address,
+            host,
+            difficulty,
+        }) => match meet(&address, host, difficulty) {
+            Ok((game, peer)) => App::with_coop(paths, game, Coop::new(peer, host)),
+            Err(error) => {
+                eprintln!("{address}: {error}");
+                return ExitCode::FAILURE;
+            }
+        },
+        Some(Command::Convert {
+            from,
+            to,
+            input,
+            output,
+        }) => return convert(from, to, input.as_deref(), output.as_deref()),
+    };
+    let app = match cli.broadcast {
+        Some(address) => match TcpListener::bind(&address) {
+            Ok(listener) => app.broadcasting(Broadcast::listen(listener)),
+            Err(error) => {
+                eprintln!("{address}: {error}");
+                return ExitCode::FAILURE;
+            }
+        },
+        None => app,
+    };
+    match run(app) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// A grid as JSON, an array of rows
+fn grid_json(cells: &[usize]) -> Value {
+    json!(cells.chunks(cells.len().isqrt()).collect::<Vec<_>>())
+}
+
+fn solve(path: &Path, json: bool) -> ExitCode {
+    let cells = match format::load_file(path) {
+        Ok(cells) => cells,
+        Err(error) => {
+            eprintln!("{}: {error}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let start = Instant::now();
+    let (solution, steps) = solver::solve_cells_with_steps(&cells);
+    let elapsed = start.elapsed();
+    if json {
+        // null for no solution, failing the same as the plain output
+        let code = match solution {
+            Some(_) => ExitCode::SUCCESS,
+            None => ExitCode::FAILURE,
+        };
+        let solution = solution.as_deref().map(grid_json);
+        let result =
+            json!({ "solution": solution, "steps": steps, "seconds": elapsed.as_secs_f64() });
+        println!("{result}");
+        return code;
+    }
+    match solution {
+        Some(solution) => {
+            print!("{}", text::write(&solution));
+            ExitCode::SUCCESS
+        }
+        None => {
+            eprintln!("{}: no solution", path.display());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn solve_batch(path: &Path, json: bool) -> ExitCode {
+    let input = match fs::read_to_string(path) {
+        Ok(input) => input,
+        Err(error) => {
+            eprintln!("{}: {error}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let results = batch::solve_lines(&input);
+    let summary = Summary::new(&results);
+    if json {
+        let puzzles: Vec<Value> = results.iter().map(solved_json).collect();
+        let summary = json!({
+            "count": summary.count,
+            "unsolvable": summary.unsolvable,
+            "invalid": summary.invalid,
+            "mean_seconds": summary.mean.as_secs_f64(),
+            "max_seconds": summary.max.as_secs_f64(),
+        });
+        println!("{}", json!({ "puzzles": puzzles, "summary": summary }));
+        return ExitCode::SUCCESS;
+    }
+    for solved in &results {
+        println!("{solved}");
+    }
+    println!("{summary}");
+    ExitCode::SUCCESS
+}
+
+fn solved_json(solved: &Solved) -> Value {
+    let (solution, error) = match &solved.outcome {
+        Outcome::Solution(cells) => (grid_json(cells), None),
+        Outcome::Unsolvable => (Value::Null, None),
+        Outcome::Invalid(reason) => (Value::Null, Some(reason)),
+    };
+    json!({
+        "line": solved.line,
+        "solution": solution,
+        "error": error,
+        "steps": solved.steps,
+        "seconds": solved.elapsed.as_secs_f64(),
+    })
+}
+
+fn generate(
+    puzzles: impl Iterator<Item = generator::Generated>,
+    solutions: bool,
+    json: bool,
+) -> ExitCode {
+    let mut generated = vec![];
+    for puzzle in puzzles {
+        match (json, solutions) {
+            (true, true) => generated.push(json!({
+                "givens": grid_json(&puzzle.givens),
+                "solution": grid_json(&puzzle.solution),
+            })),
+            (true, false) => generated.push(json!({ "givens": grid_json(&puzzle.givens) })),
+            (false, true) => println!(
+                "{} {}",
+                text::write_line(&puzzle.givens),
+                text::write_line(&puzzle.solution)
+            ),
+            (false, false) => println!("{}", text::write_line(&puzzle.givens)),
+        }
+    }
+    if json {
+        println!("{}", Value::Array(generated));
+    }
+    ExitCode::SUCCESS
+}
+
+/// Read a puzzle from a file if `puzzle` names one, or parse it as a grid
+/// in any of the shapes people paste
+fn read_puzzle(puzzle: &str) -> Result<Vec<usize>, FormatError> {
+    match Path::new(puzzle).is_file() {
+        true => format::load_file(puzzle),
+        false => lenient::parse(puzzle),
+    }
+}
+
+fn check(puzzle: &str, json: bool) -> ExitCode {
+    let cells = match read_puzzle(puzzle) {
+        Ok(cells) => cells,
+        Err(error) => {
+            eprintln!("{puzzle}: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let validity = solver::validate(&cells);
+    let consistent = validity != Validity::Inconsistent;
+    let solvable = consistent && validity != Validity::Unsolvable;
+    let unique = validity == Validity::Unique;
+    if json {
+        let result = json!({ "consistent": consistent, "solvable": solvable, "unique": unique });
+        println!("{result}");
+    } else {
+        let yes_no = |answer: bool| if answer { "yes" } else { "no" };
+        println!("Givens consistent: {}", yes_no(consistent));
+        println!("Solvable: {}", yes_no(solvable));
+        println!("Unique solution: {}", yes_no(unique));
+    }
+    ExitCode::from(match validity {
+        Validity::Unique => 0,
+        Validity::Inconsistent => 3,
+        Validity::Unsolvable => 4,
+        Validity::MultipleSolutions => 5,
+    })
+}
+
+fn rate(puzzle: &str, json: bool) -> ExitCode {
+    let cells = match read_puzzle(puzzle) {
+        Ok(cells) => cells,
+        Err(error) => {
+            eprintln!("{puzzle}: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let rating = match solver::count_solutions(&cells, 2) {
+        1 => logic::rate(&cells),
+        0 => None,
+        _ => {
+            eprintln!("{puzzle}: more than one solution");
+            return ExitCode::FAILURE;
+        }
+    };
+    let Some(rating) = rating else {
+        eprintln!("{puzzle}: no solution");
+        return ExitCode::FAILURE;
+    };
+    let mut techniques: Vec<String> = rating.techniques.iter().map(|t| t.to_string()).collect();
+    if json {
+        let result = json!({
+            "difficulty": rating.difficulty,
+            "techniques": techniques,
+            "solved_by_logic": rating.solved,
+        });
+        println!("{result}");
+        return ExitCode::SUCCESS;
+    }
+    if !rating.solved {
+        techniques.push("guessing".to_string());
+    }
+    println!("{}", rating.difficulty);
+    println!("Techniques: {}", techniques.join(", "));
+    ExitCode::SUCCESS
+}
+
+/// Meet the other player and agree on the puzzle: the host generates it and
+/// sends it over as the other player joins
+fn meet(address: &str, host: bool, difficulty: Difficulty) -> io::Result<(Game, Peer)> {
+    let (givens, difficulty, peer) = if host {
+        let listener = TcpListener::bind(address)?;
+        let generated = generator::generate(Variant::Classic, difficulty, &mut Rng::from_time());
+        eprintln!("Waiting for a player on {address}");
+        let mut peer = Peer::accept(&listener)?;
+        peer.send(&Message::Puzzle {
+            givens: generated.givens.clone(),
+            difficulty: Some(difficulty),
+        });
+        (generated.givens, Some(difficulty), peer)
+    } else {
+        let mut peer = Peer::connect(address)?;
+        let (givens, difficulty) = peer.recv_puzzle()?;
+        (givens, difficulty, peer)
+    };
+    let mut game = Game::new(givens)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{e:?}")))?;
+    game.difficulty = difficulty;
+    Ok((game, peer))
+}
+
+fn serve(address: &str) -> ExitCode {
+    let listener = match TcpListener::bind(address) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("{address}: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!("Listening on http://{address}");
+    match serve::serve(listener) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{address}: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn print(puzzle: &str, html: bool, svg: bool, output: Option<&Path>) -> ExitCode {
+    let cells = match read_puzzle(puzzle) {
+        Ok(cells) => cells,
+        Err(error) => {
+            eprintln!("{puzzle}: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let printable = match (html, svg) {
+        (_, true) => match Grid::new(cells) {
+            Ok(grid) => export::to_svg(&grid, &SvgOptions::default()),
+            Err(error) => {
+                eprintln!("{puzzle}: invalid grid: {error:?}");
+                return ExitCode::FAILURE;
+            }
+        },
+        (true, false) => {
+            let title = Path::new(puzzle)
+                .file_stem()
+                .filter(|_| Path::new(puzzle).is_file())
+                .map_or("Sudoku".into(), |stem| stem.to_string_lossy());
+            export::to_html(&cells, &title)
+        }
+        (false, false) => export::to_text(&cells),
+    };
+    match output {
+        Some(path) => {
+            if let Err(error) = fs::write(path, printable) {
+                eprintln!("{}: {error}", path.display());
+                return ExitCode::FAILURE;
+            }
+        }
+        None => print!("{printable}"),
+    }
+    ExitCode::SUCCESS
+}
+
+fn stats(paths: &Paths, json: bool) -> ExitCode {
+    let stats = match Stats::load(paths.stats()) {
+        Ok(stats) => stats,
+        Err(_) => {
+            eprintln!("Could not read {}", paths.stats().display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let rows = Difficulty::ALL
+        .into_iter()
+        .map(|difficulty| {
+            (
+                difficulty.to_string(),
+                stats.for_difficulty(Some(difficulty)),
+            )
+        })
+        .chain([("Unrated".to_string(), stats.for_difficulty(None))])
+        .chain([("Total".to_string(), stats.total())]);
+    let streak = stats.current_streak(stats::today());
+    if json {
+        let difficulties: serde_json::Map<String, Value> = rows
+            .map(|(name, solves)| {
+                let solves = json!({
+                    "solved": solves.solved,
+                    "best_seconds": solves.best().map(|best| best.as_secs_f64()),
+                    "average_seconds": solves.average().map(|average| average.as_secs_f64()),
+                    "hints_used": solves.hints_used,
+                    "failed": solves.failed,
+                });
+                (name.to_lowercase(), solves)
+            })
+            .collect();
+        let streak = json!({ "current": streak, "best": stats.streak.best });
+        println!(
+            "{}",
+            json!({ "difficulties": difficulties, "daily_streak": streak })
+        );
+        return ExitCode::SUCCESS;
+    }
+    let time = |duration: Option<Duration>| duration.map_or("-".to_string(), format_duration);
+    println!(
+        "{:<10} {:>6} {:>8} {:>8} {:>6} {:>6}",
+        "", "Solved", "Best", "Average", "Hints", "Failed"
+    );
+    for (name, solves) in rows {
+        println!(
+            "{name:<10} {:>6} {:>8} {:>8} {:>6} {:>6}",
+            solves.solved,
+            time(solves.best()),
+            time(solves.average()),
+            solves.hints_used,
+            solves.failed
+        );
+    }
+    println!("Daily streak: {streak} (best {})", stats.streak.best);
+    ExitCode::SUCCESS
+}
+
+fn convert(from: Layout, to: Layout, input: Option<&Path>, output: Option<&Path>) -> ExitCode {
+    let name = input.map_or("standard input".into(), |path| path.display().to_string());
+    let text = match input {
+        Some(path) => fs::read_to_string(path),
+        None => io::read_to_string(io::stdin()),
+    };
+    let cells = match text
+        .map_err(FormatError::from)
+        .and_then(|text| from.parse(&text))
+    {
+        Ok(cells) => cells,
+        Err(error) => {
+            eprintln!("{name}: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let converted = to.write(&cells);
+    match output {
+        Some(path) => {
+            if let Err(error) = fs::write(path, converted) {
+                eprintln!("{}: {error}", path.display());
+                return ExitCode::FAILURE;
+            }
+        }
+        None => print!("{converted}"),
+    }
+    ExitCode::SUCCESS
+}
+
+fn run(mut app: App) -> std::io::Result<()> {
     let mut terminal = tui::init()?;
-    let app_result = App::new().run(&mut terminal);
+    let app_result = app.run(&mut terminal);
     tui::restore()?;
     app_result
 }
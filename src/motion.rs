@@ -0,0 +1,181 @@
+use crate::{
+    game::{Direction, Game},
+    keymap::Action,
+};
+use std::time::{Duration, Instant};
+
+/// How long a typed number waits for a motion, or another digit, before it's
+/// entered as a value
+pub const COUNT_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Actions that move the selection, and so can be given a count
+pub const MOTIONS: &[Action] = &[
+    Action::Left,
+    Action::Right,
+    Action::Up,
+    Action::Down,
+    Action::JumpLeft,
+    Action::JumpRight,
+    Action::JumpUp,
+    Action::JumpDown,
+    Action::RowStart,
+    Action::RowEnd,
+    Action::FirstRow,
+    Action::LastRow,
+];
+
+/// Builds vim-style motions out of several key presses: a count before a
+/// move (`3j`), and `gg` for the first row. Digits are also how values are
+/// entered, so a number only becomes a count if a motion follows it, and
+/// only where digits don't place values straight away.
+#[derive(Debug, Default)]
+pub struct MotionParser {
+    count: Option<usize>,
+    typed_at: Option<Instant>,
+    /// The first `g` of `gg` has been pressed
+    prefix: bool,
+}
+
+impl MotionParser {
+    /// Add a digit to the number being typed. Returns false for a leading
+    /// zero, which isn't part of a count and is left to the caller.
+    pub fn push_digit(&mut self, digit: usize) -> bool {
+        if digit == 0 && self.count.is_none() {
+            return false;
+        }
+        self.prefix = false;
+        self.count = Some(self.count.unwrap_or(0).saturating_mul(10) + digit);
+        self.typed_at = Some(Instant::now());
+        true
+    }
+
+    /// Combine a motion with the keys typed before it, giving the motion and
+    /// its count once it's complete
+    pub fn motion(&mut self, action: Action) -> Option<(Action, Option<usize>)> {
+        if action == Action::FirstRow && !self.prefix {
+            self.prefix = true;
+            return None;
+        }
+        self.prefix = false;
+        self.typed_at = None;
+        Some((action, self.count.take()))
+    }
+
+    /// Take the number typed so far, to be entered as a value
+    pub fn flush(&mut self) -> Option<usize> {
+        self.prefix = false;
+        self.typed_at = None;
+        self.count.take()
+    }
+
+    /// The number typed, once no further digit could keep it a value up to
+    /// `size`
+    pub fn complete(&mut self, size: usize) -> Option<usize> {
+        match self.count {
+            Some(count) if count.saturating_mul(10) > size => self.flush(),
+            _ => None,
+        }
+    }
+
+    /// The number typed, once it's waited `COUNT_TIMEOUT` without a motion
+    pub fn expired(&mut self) -> Option<usize> {
+        match self.typed_at {
+            Some(typed_at) if typed_at.elapsed() >= COUNT_TIMEOUT => self.flush(),
+            _ => None,
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.count.is_some() || self.prefix
+    }
+}
+
+/// Move the selection for a motion. Moves repeat `count` times, while the
+/// row jumps `gg` and `G` go to row `count` when one is given, like lines in
+/// vim.
+pub fn apply_motion(game: &mut Game, action: Action, count: Option<usize>, wrap: bool) {
+    let last = game.size() - 1;
+    let row = count.map(|count| count.clamp(1, last + 1) - 1);
+    let repeat = count.unwrap_or(1);
+    match action {
+        Action::RowStart => game.selected.0 = 0,
+        Action::RowEnd => game.selected.0 = last,
+        Action::FirstRow => game.selected.1 = row.unwrap_or(0),
+        Action::LastRow => game.selected.1 = row.unwrap_or(last),
+        _ => {
+            let (direction, jump) = match action {
+                Action::Left => (Direction::Left, false),
+                Action::Right => (Direction::Right, false),
+                Action::Up => (Direction::Up, false),
+                Action::Down => (Direction::Down, false),
+                Action::JumpLeft => (Direction::Left, true),
+                Action::JumpRight => (Direction::Right, true),
+                Action::JumpUp => (Direction::Up, true),
+                Action::JumpDown => (Direction::Down, true),
+                _ => return,
+            };
+            for _ in 0..repeat {
+                match jump {
+                    true => game.jump_selected(direction, wrap),
+                    false => game.move_selected(direction, wrap),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::PUZZLES;
+
+    #[test]
+    fn parses_counts() {
+        let mut parser = MotionParser::default();
+        assert!(!parser.push_digit(0));
+        assert!(parser.push_digit(1));
+        assert!(parser.push_digit(2));
+        assert!(parser.is_pending());
+        assert_eq!(parser.motion(Action::Down), Some((Action::Down, Some(12))));
+        assert!(!parser.is_pending());
+        assert_eq!(parser.motion(Action::FirstRow), None);
+        assert_eq!(
+            parser.motion(Action::FirstRow),
+            Some((Action::FirstRow, None))
+        );
+        parser.push_digit(4);
+        assert_eq!(parser.expired(), None);
+        std::thread::sleep(COUNT_TIMEOUT);
+        assert_eq!(parser.expired(), Some(4));
+        assert_eq!(parser.flush(), None);
+    }
+
+    #[test]
+    fn completes_numbers() {
+        let mut parser = MotionParser::default();
+        parser.push_digit(4);
+        assert_eq!(parser.complete(9), Some(4));
+        parser.push_digit(1);
+        assert_eq!(parser.complete(16), None);
+        parser.push_digit(2);
+        assert_eq!(parser.complete(16), Some(12));
+        assert!(!parser.is_pending());
+    }
+
+    #[test]
+    fn applies_motions() {
+        let mut game = PUZZLES[0].game().unwrap();
+        apply_motion(&mut game, Action::Down, Some(3), false);
+        assert_eq!(game.selected, (0, 3));
+        apply_motion(&mut game, Action::Right, Some(20), false);
+        assert_eq!(game.selected, (8, 3));
+        apply_motion(&mut game, Action::RowStart, None, false);
+        assert_eq!(game.selected, (0, 3));
+        apply_motion(&mut game, Action::LastRow, None, false);
+        assert_eq!(game.selected, (0, 8));
+        apply_motion(&mut game, Action::FirstRow, Some(5), false);
+        assert_eq!(game.selected, (0, 4));
+        apply_motion(&mut game, Action::JumpRight, Some(2), false);
+        assert_eq!(game.selected, (6, 4));
+    }
+}
@@ -0,0 +1,178 @@
+use crate::difficulty::Difficulty;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::Duration,
+};
+
+/// How long joining waits for the host to send the puzzle
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What two games say to each other, sent as one line of JSON each
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    /// The puzzle to play, sent by the host once the other player joins
+    Puzzle {
+        givens: Vec<usize>,
+        #[serde(default)]
+        difficulty: Option<Difficulty>,
+    },
+    /// How much of the grid the sender has filled, without its values
+    Progress { percent: u8 },
+    /// The sender solved the puzzle, taking this long
+    Solved { millis: u64 },
+    /// The whole board, for a spectator joining or a game starting over
+    Board {
+        givens: Vec<usize>,
+        values: Vec<usize>,
+        millis: u64,
+    },
+    /// A value entered on a shared or watched board, 0 clearing the cell
+    Entry {
+        position: (usize, usize),
+        value: usize,
+        by_host: bool,
+    },
+}
+
+/// The other end of a game played over the network. Messages are read on a
+/// thread of their own, so checking for them never blocks.
+pub struct Peer {
+    stream: TcpStream,
+    incoming: Receiver<Message>,
+    closed: bool,
+}
+
+impl Peer {
+    fn new(stream: TcpStream) -> io::Result<Self> {
+        let reader = BufReader::new(stream.try_clone()?);
+        let (sender, incoming) = mpsc::channel();
+        thread::spawn(move || {
+            for line in reader.lines() {
+                let Ok(line) = line else { break };
+                // a line that isn't a message is from a newer or broken peer
+                let Ok(message) = serde_json::from_str(&line) else {
+                    continue;
+                };
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            stream,
+            incoming,
+            closed: false,
+        })
+    }
+
+    /// Wait on `listener` for the other player to connect
+    pub fn accept(listener: &TcpListener) -> io::Result<Self> {
+        let (stream, _) = listener.accept()?;
+        Self::new(stream)
+    }
+
+    pub fn connect(address: impl ToSocketAddrs) -> io::Result<Self> {
+        Self::new(TcpStream::connect(address)?)
+    }
+
+    /// Send a message, marking the peer closed if it's gone
+    pub fn send(&mut self, message: &Message) {
+        let mut line = serde_json::to_string(message).expect("messages serialize");
+        line.push('\n');
+        if self.stream.write_all(line.as_bytes()).is_err() {
+            self.closed = true;
+        }
+    }
+
+    /// The next message, if one has arrived
+    pub fn try_recv(&mut self) -> Option<Message> {
+        match self.incoming.try_recv() {
+            Ok(message) => Some(message),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.closed = true;
+                None
+            }
+        }
+    }
+
+    /// Wait for the puzzle the host sends on joining
+    pub fn recv_puzzle(&mut self) -> io::Result<(Vec<usize>, Option<Difficulty>)> {
+        match self.incoming.recv_timeout(HANDSHAKE_TIMEOUT) {
+            Ok(Message::Puzzle { givens, difficulty }) => Ok((givens, difficulty)),
+            Ok(_) => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "expected a puzzle from the host",
+            )),
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the host sent no puzzle",
+            )),
+        }
+    }
+
+    /// Whether the other player has left, as far as is known yet
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
+
+impl Drop for Peer {
+    fn drop(&mut self) {
+        // closes the reading thread's half too, so the other player sees
+        // the game end
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_are_json_lines() {
+        let message = Message::Progress { percent: 40 };
+        let json = serde_json::to_string(&message).unwrap();
+        assert_eq!(json, r#"{"type":"progress","percent":40}"#);
+        assert_eq!(serde_json::from_str::<Message>(&json).unwrap(), message);
+    }
+
+    #[test]
+    fn exchanges_messages() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let host = thread::spawn(move || {
+            let mut peer = Peer::accept(&listener).unwrap();
+            peer.send(&Message::Puzzle {
+                givens: vec![1, 0, 0, 0],
+                difficulty: Some(Difficulty::Easy),
+            });
+            peer
+        });
+        let mut guest = Peer::connect(address).unwrap();
+        assert_eq!(
+            guest.recv_puzzle().unwrap(),
+            (vec![1, 0, 0, 0], Some(Difficulty::Easy))
+        );
+        let mut host = host.join().unwrap();
+        guest.send(&Message::Solved { millis: 1500 });
+        let received = (0..100).find_map(|_| {
+            thread::sleep(Duration::from_millis(10));
+            host.try_recv()
+        });
+        assert_eq!(received, Some(Message::Solved { millis: 1500 }));
+
+        drop(guest);
+        let closed = (0..100).any(|_| {
+            thread::sleep(Duration::from_millis(10));
+            host.try_recv();
+            host.is_closed()
+        });
+        assert!(closed);
+    }
+}
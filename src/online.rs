@@ -0,0 +1,131 @@
+use crate::format::{lenient, sdm, text, Collection, FormatError};
+use serde_json::Value;
+use std::{fmt::Display, fs, io, path::Path};
+
+/// Where fetched puzzles are kept, among the puzzle packs so they can be
+/// played again offline
+pub const CACHE_FILE: &str = "online.sdm";
+
+#[derive(Debug)]
+pub enum OnlineError {
+    Request(ureq::Error),
+    Io(io::Error),
+    /// The source answered with nothing that reads as a puzzle
+    NoPuzzles,
+}
+
+impl From<ureq::Error> for OnlineError {
+    fn from(error: ureq::Error) -> Self {
+        Self::Request(error)
+    }
+}
+
+impl From<io::Error> for OnlineError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl Display for OnlineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OnlineError::Request(error) => write!(f, "could not fetch puzzles: {error}"),
+            OnlineError::Io(error) => write!(f, "could not cache puzzles: {error}"),
+            OnlineError::NoPuzzles => write!(f, "no puzzles found at the source"),
+        }
+    }
+}
+
+/// The puzzles in a response, whatever its shape: a JSON document with
+/// puzzles somewhere in it, either written out as strings or as arrays of
+/// rows, or text with one puzzle per line
+pub fn puzzles_in(body: &str) -> Vec<Vec<usize>> {
+    let mut puzzles = vec![];
+    match serde_json::from_str::<Value>(body) {
+        Ok(value) => collect(&value, &mut puzzles),
+        Err(_) => puzzles.extend(body.lines().filter_map(|line| lenient::parse(line).ok())),
+    }
+    // APIs often send the solution along, which is no puzzle at all
+    puzzles.retain(|cells| cells.contains(&0));
+    puzzles
+}
+
+fn collect(value: &Value, puzzles: &mut Vec<Vec<usize>>) {
+    match value {
+        Value::String(text) => puzzles.extend(lenient::parse(text).ok()),
+        Value::Array(items) => match rows(items) {
+            Some(cells) => puzzles.push(cells),
+            None => items.iter().for_each(|item| collect(item, puzzles)),
+        },
+        Value::Object(fields) => fields.values().for_each(|field| collect(field, puzzles)),
+        _ => {}
+    }
+}
+
+/// A grid written as an array of rows of numbers
+fn rows(items: &[Value]) -> Option<Vec<usize>> {
+    let mut cells = vec![];
+    for row in items {
+        for value in row.as_array()? {
+            cells.push(value.as_u64()?.to_string());
+        }
+    }
+    lenient::parse(&cells.join(",")).ok()
+}
+
+/// Fetch puzzles from `url` and add the new ones to the cache at `cache`,
+/// returning everything cached
+pub fn refresh(url: &str, cache: &Path) -> Result<Collection, OnlineError> {
+    let body = ureq::get(url).call()?.body_mut().read_to_string()?;
+    let fetched = puzzles_in(&body);
+    if fetched.is_empty() {
+        return Err(OnlineError::NoPuzzles);
+    }
+    let mut puzzles = cached(cache).map_or(vec![], |collection| collection.puzzles);
+    for puzzle in fetched {
+        if !puzzles.contains(&puzzle) {
+            puzzles.push(puzzle);
+        }
+    }
+    let lines: Vec<String> = puzzles
+        .iter()
+        .map(|puzzle| text::write_line(puzzle))
+        .collect();
+    fs::write(cache, lines.join("\n") + "\n")?;
+    Ok(Collection {
+        name: Some("Online pack".to_string()),
+        puzzles,
+    })
+}
+
+/// The puzzles fetched before, to play without a connection
+pub fn cached(cache: &Path) -> Result<Collection, FormatError> {
+    Ok(Collection {
+        name: Some("Online pack".to_string()),
+        puzzles: sdm::parse(&fs::read_to_string(cache)?)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::PUZZLES;
+    use serde_json::json;
+
+    #[test]
+    fn finds_puzzles_in_responses() {
+        let cells = PUZZLES[1].cells();
+        let line = text::write_line(&cells);
+        assert_eq!(puzzles_in(&format!("{line}\n\n{line}\n")).len(), 2);
+
+        let rows: Vec<&[usize]> = cells.chunks(9).collect();
+        let solution: Vec<&[usize]> = rows.iter().map(|_| &[1; 9][..]).collect();
+        let api =
+            json!({ "grids": [{ "value": rows, "solution": solution, "difficulty": "Easy" }] });
+        assert_eq!(puzzles_in(&api.to_string()), vec![cells.clone()]);
+
+        let strings = json!([{ "puzzle": line, "id": 7 }, { "puzzle": "not one" }]);
+        assert_eq!(puzzles_in(&strings.to_string()), vec![cells]);
+        assert!(puzzles_in("<html></html>").is_empty());
+    }
+}
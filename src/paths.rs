@@ -0,0 +1,130 @@
+#[cfg(feature = "tui")]
+use crate::config::CONFIG_FILE;
+use crate::{progress::PROGRESS_FILE, save::SAVE_FILE, stats::STATS_FILE};
+use directories::ProjectDirs;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Where the files kept between sessions live: settings in the config
+/// directory, and saves, statistics, solve records and puzzle packs in the
+/// data directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paths {
+    config_dir: PathBuf,
+    data_dir: PathBuf,
+}
+
+impl Paths {
+    /// The platform's usual directories, such as `~/.config/sudoku` and
+    /// `~/.local/share/sudoku` on Linux, or the current directory if there's
+    /// no home directory to put them in
+    pub fn platform() -> Self {
+        match ProjectDirs::from("", "", "sudoku") {
+            Some(dirs) => Self {
+                config_dir: dirs.config_dir().to_path_buf(),
+                data_dir: dirs.data_dir().to_path_buf(),
+            },
+            None => Self::portable("."),
+        }
+    }
+
+    /// Everything in the one directory, to carry the game around with its
+    /// settings and saves
+    pub fn portable(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        Self {
+            config_dir: dir.clone(),
+            data_dir: dir,
+        }
+    }
+
+    #[cfg(feature = "tui")]
+    pub fn config(&self) -> PathBuf {
+        self.config_dir.join(CONFIG_FILE)
+    }
+
+    pub fn save(&self) -> PathBuf {
+        self.data_dir.join(SAVE_FILE)
+    }
+
+    pub fn stats(&self) -> PathBuf {
+        self.data_dir.join(STATS_FILE)
+    }
+
+    pub fn progress(&self) -> PathBuf {
+        self.data_dir.join(PROGRESS_FILE)
+    }
+
+    /// Where to keep puzzle files, offered first by the file browser
+    pub fn packs(&self) -> PathBuf {
+        self.data_dir.join("packs")
+    }
+
+    /// Where exported solve records go
+    pub fn records(&self) -> PathBuf {
+        self.data_dir.join("records")
+    }
+
+    /// Where grids exported from a game go
+    pub fn exports(&self) -> PathBuf {
+        self.data_dir.join("exports")
+    }
+
+    /// Make the directories, so files can be written into them
+    pub fn create_dirs(&self) -> io::Result<()> {
+        fs::create_dir_all(&self.config_dir)?;
+        fs::create_dir_all(self.records())?;
+        fs::create_dir_all(self.exports())?;
+        fs::create_dir_all(self.packs())
+    }
+}
+
+/// A file name in `dir` starting with `stem` that none of the files ending
+/// in `suffixes` have yet, counting up from `stem-2` past earlier ones, so
+/// exporting again never overwrites
+pub fn fresh_name(dir: &Path, stem: &str, suffixes: &[&str]) -> String {
+    let taken = |name: &str| {
+        suffixes
+            .iter()
+            .any(|suffix| dir.join(format!("{name}{suffix}")).exists())
+    };
+    (1..)
+        .map(|count| match count {
+            1 => stem.to_string(),
+            _ => format!("{stem}-{count}"),
+        })
+        .find(|name| !taken(name))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn portable_keeps_everything_together() {
+        let paths = Paths::portable("/media/stick");
+        #[cfg(feature = "tui")]
+        assert_eq!(paths.config(), Path::new("/media/stick/config.toml"));
+        assert_eq!(paths.save(), Path::new("/media/stick/save.json"));
+        assert_eq!(paths.packs(), Path::new("/media/stick/packs"));
+        assert_eq!(paths.records(), Path::new("/media/stick/records"));
+        assert_eq!(paths.exports(), Path::new("/media/stick/exports"));
+    }
+
+    #[test]
+    fn fresh_names_skip_existing_files() {
+        let dir = std::env::temp_dir().join(format!("sudoku-paths-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(fresh_name(&dir, "easy", &[".sdk"]), "easy");
+        fs::write(dir.join("easy.sdk"), "").unwrap();
+        assert_eq!(fresh_name(&dir, "easy", &[".sdk"]), "easy-2");
+        assert_eq!(fresh_name(&dir, "easy", &[".csv"]), "easy");
+        fs::write(dir.join("easy-2.json"), "").unwrap();
+        assert_eq!(fresh_name(&dir, "easy", &[".sdk", ".json"]), "easy-3");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
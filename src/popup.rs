@@ -0,0 +1,34 @@
+use crate::tui::centered_rect;
+use ratatui::{
+    prelude::*,
+    widgets::{block::Title, Block, Borders, Clear, Paragraph},
+};
+
+/// A bordered box drawn over the centre of whatever is already rendered
+pub struct Popup<'a> {
+    pub title: &'a str,
+    pub lines: Vec<Line<'a>>,
+}
+
+impl Widget for Popup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = self
+            .lines
+            .iter()
+            .map(|line| line.width())
+            .chain([self.title.len()])
+            .max()
+            .unwrap_or(0) as u16
+            + 4;
+        let height = self.lines.len() as u16 + 2;
+        let area = centered_rect(area, width, height);
+        let block = Block::default()
+            .title(Title::from(self.title.bold()).alignment(Alignment::Center))
+            .borders(Borders::ALL);
+        Clear.render(area, buf);
+        Paragraph::new(self.lines)
+            .centered()
+            .block(block)
+            .render(area, buf);
+    }
+}
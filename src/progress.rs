@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+    path::Path,
+};
+
+pub const PROGRESS_FILE: &str = "progress.toml";
+
+/// Which puzzles of each collection file have been solved, by the puzzle's
+/// position in the file
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackProgress {
+    #[serde(default)]
+    solved: BTreeMap<String, BTreeSet<usize>>,
+}
+
+#[derive(Debug)]
+pub enum ProgressError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+}
+
+impl From<io::Error> for ProgressError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Collections are told apart by their full path, so the same file opened
+/// from another directory keeps its progress
+fn key(file: &Path) -> String {
+    let file = fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf());
+    file.to_string_lossy().into_owned()
+}
+
+impl PackProgress {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ProgressError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents).map_err(ProgressError::Parse),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ProgressError> {
+        let contents = toml::to_string_pretty(self).map_err(ProgressError::Serialize)?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    pub fn is_solved(&self, file: &Path, index: usize) -> bool {
+        self.solved
+            .get(&key(file))
+            .is_some_and(|solved| solved.contains(&index))
+    }
+
+    pub fn mark_solved(&mut self, file: &Path, index: usize) {
+        self.solved.entry(key(file)).or_default().insert(index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut progress = PackProgress::default();
+        progress.mark_solved(Path::new("/puzzles/hard.sdm"), 3);
+        assert!(progress.is_solved(Path::new("/puzzles/hard.sdm"), 3));
+        assert!(!progress.is_solved(Path::new("/puzzles/hard.sdm"), 2));
+        assert!(!progress.is_solved(Path::new("/puzzles/easy.sdm"), 3));
+        let contents = toml::to_string_pretty(&progress).unwrap();
+        assert_eq!(toml::from_str::<PackProgress>(&contents).unwrap(), progress);
+    }
+}
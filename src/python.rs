@@ -0,0 +1,218 @@
+use crate::{
+    difficulty::Difficulty,
+    format::lenient,
+    generator,
+    grid::{Grid, GridError},
+    logic,
+    rng::Rng,
+    solver::{self, Validity},
+    variant::Variant,
+};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+// Grids cross over to Python as lists of every cell, row by row, with 0 for
+// an empty cell. Positions are `(x, y)` from the top left, as in the engine.
+
+fn grid_error(error: GridError) -> PyErr {
+    PyValueError::new_err(format!("invalid grid: {error:?}"))
+}
+
+/// A grid of values, the givens among them read-only
+#[pyclass(name = "Grid")]
+pub struct PyGrid {
+    grid: Grid,
+}
+
+#[pymethods]
+impl PyGrid {
+    /// The puzzle's cells, every non-zero one a given
+    #[new]
+    fn new(cells: Vec<usize>) -> PyResult<Self> {
+        let grid = Grid::new(cells).map_err(grid_error)?;
+        Ok(Self { grid })
+    }
+
+    /// Read a grid written in any of the usual shapes
+    #[staticmethod]
+    fn parse(text: &str) -> PyResult<Self> {
+        let cells =
+            lenient::parse(text).map_err(|error| PyValueError::new_err(error.to_string()))?;
+        Self::new(cells)
+    }
+
+    #[getter]
+    fn size(&self) -> usize {
+        self.grid.size()
+    }
+
+    #[getter]
+    fn box_size(&self) -> usize {
+        self.grid.sub_square_size()
+    }
+
+    #[getter]
+    fn cells(&self) -> Vec<usize> {
+        self.grid.values()
+    }
+
+    #[getter]
+    fn givens(&self) -> Vec<usize> {
+        self.grid.givens()
+    }
+
+    fn get(&self, x: usize, y: usize) -> PyResult<usize> {
+        self.grid.get_cell((x, y)).map_err(grid_error)
+    }
+
+    /// Set a cell that isn't a given, 0 clearing it
+    fn set(&mut self, x: usize, y: usize, value: usize) -> PyResult<()> {
+        self.grid.set_cell((x, y), value).map_err(grid_error)?;
+        Ok(())
+    }
+
+    /// The values not yet in the cell's row, column or box
+    fn candidates(&self, x: usize, y: usize) -> PyResult<Vec<usize>> {
+        self.grid.candidates_for_cell((x, y)).map_err(grid_error)
+    }
+
+    fn __str__(&self) -> String {
+        self.grid.to_string()
+    }
+}
+
+/// Backtracking over plain lists of cells
+#[pyclass(name = "Solver")]
+pub struct PySolver;
+
+#[pymethods]
+impl PySolver {
+    /// The first solution, or `None` if there is none
+    #[staticmethod]
+    fn solve(cells: Vec<usize>) -> Option<Vec<usize>> {
+        solver::solve_cells(&cells)
+    }
+
+    /// The number of solutions, counting no further than `limit`
+    #[staticmethod]
+    #[pyo3(signature = (cells, limit = 2))]
+    fn count_solutions(cells: Vec<usize>, limit: usize) -> usize {
+        solver::count_solutions(&cells, limit)
+    }
+
+    /// `"unique"`, `"multiple"`, `"unsolvable"`, or `"inconsistent"` when
+    /// givens clash
+    #[staticmethod]
+    fn validate(cells: Vec<usize>) -> &'static str {
+        match solver::validate(&cells) {
+            Validity::Unique => "unique",
+            Validity::MultipleSolutions => "multiple",
+            Validity::Unsolvable => "unsolvable",
+            Validity::Inconsistent => "inconsistent",
+        }
+    }
+}
+
+/// Puzzles with a unique solution, reproducible from the seed
+#[pyclass(name = "Generator")]
+pub struct PyGenerator {
+    rng: Rng,
+}
+
+#[pymethods]
+impl PyGenerator {
+    /// Seeded from the clock if no seed is given
+    #[new]
+    #[pyo3(signature = (seed = None))]
+    fn new(seed: Option<u64>) -> Self {
+        Self {
+            rng: seed.map_or_else(Rng::from_time, Rng::new),
+        }
+    }
+
+    /// A puzzle 4 or 9 cells a side, as its givens and its solution
+    #[pyo3(signature = (size = 9, difficulty = "medium"))]
+    fn generate(&mut self, size: usize, difficulty: &str) -> PyResult<(Vec<usize>, Vec<usize>)> {
+        let variant = Variant::ALL
+            .into_iter()
+            .find(|variant| variant.size() == size)
+            .ok_or_else(|| PyValueError::new_err(format!("no {size}x{size} puzzles")))?;
+        let difficulty: Difficulty = difficulty.parse().map_err(PyValueError::new_err)?;
+        let generated = generator::generate(variant, difficulty, &mut self.rng);
+        Ok((generated.givens, generated.solution))
+    }
+}
+
+/// How far human techniques get with a puzzle
+#[pyclass(name = "Rating", get_all, frozen)]
+pub struct PyRating {
+    /// `"easy"`, `"medium"`, `"hard"` or `"expert"`
+    difficulty: String,
+    /// Names of the techniques used, simplest first
+    techniques: Vec<String>,
+    /// False if solving it takes guessing
+    solved: bool,
+}
+
+#[pymethods]
+impl PyRating {
+    fn __repr__(&self) -> String {
+        format!(
+            "Rating(difficulty={:?}, techniques={:?}, solved={})",
+            self.difficulty,
+            self.techniques,
+            if self.solved { "True" } else { "False" }
+        )
+    }
+}
+
+/// Grade a puzzle by the techniques it takes, or `None` if its givens clash
+#[pyfunction]
+fn rate(cells: Vec<usize>) -> Option<PyRating> {
+    let rating = logic::rate(&cells)?;
+    Some(PyRating {
+        difficulty: rating.difficulty.to_string().to_lowercase(),
+        techniques: rating
+            .techniques
+            .iter()
+            .map(|technique| technique.to_string())
+            .collect(),
+        solved: rating.solved,
+    })
+}
+
+#[pymodule(name = "sudoku")]
+fn sudoku_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyGrid>()?;
+    m.add_class::<PySolver>()?;
+    m.add_class::<PyGenerator>()?;
+    m.add_class::<PyRating>()?;
+    m.add_function(wrap_pyfunction!(rate, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::PUZZLES;
+
+    #[test]
+    fn wraps_the_engine() {
+        let cells = PUZZLES[1].cells();
+        let mut grid = PyGrid::new(cells.clone()).unwrap();
+        assert_eq!(grid.size(), 9);
+        let solution = PySolver::solve(cells.clone()).unwrap();
+        let empty = cells.iter().position(|&value| value == 0).unwrap();
+        let (x, y) = (empty % 9, empty / 9);
+        assert!(grid.candidates(x, y).unwrap().contains(&solution[empty]));
+        grid.set(x, y, solution[empty]).unwrap();
+        assert_eq!(grid.get(x, y).unwrap(), solution[empty]);
+        assert_eq!(PySolver::validate(cells.clone()), "unique");
+
+        let rating = rate(cells).unwrap();
+        assert_eq!(rating.difficulty, "easy");
+        assert!(rating.solved);
+
+        let (givens, solution) = PyGenerator::new(Some(7)).generate(4, "easy").unwrap();
+        assert_eq!(PySolver::solve(givens), Some(solution));
+    }
+}
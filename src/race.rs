@@ -0,0 +1,160 @@
+use crate::{
+    game::{Game, GameStatus},
+    net::{Message, Peer},
+};
+#[cfg(feature = "tui")]
+use crate::{theme::Appearance, timer::format_duration};
+#[cfg(feature = "tui")]
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph},
+};
+use std::time::Duration;
+
+/// Two players on the same puzzle, each seeing how far the other has got
+pub struct Race {
+    peer: Peer,
+    /// The opponent's progress as last reported, in percent
+    pub opponent: u8,
+    /// How long the opponent took, once they've solved it
+    pub opponent_time: Option<Duration>,
+    /// How long the player took, once they've solved it
+    time: Option<Duration>,
+    /// Whether the player solved it before the opponent, once either has
+    pub won: Option<bool>,
+    /// The progress last sent, to send again only when it changes
+    sent: Option<u8>,
+}
+
+impl Race {
+    pub fn new(peer: Peer) -> Self {
+        Self {
+            peer,
+            opponent: 0,
+            opponent_time: None,
+            time: None,
+            won: None,
+            sent: None,
+        }
+    }
+
+    /// Read what the opponent sent and tell them how the player is doing
+    pub fn update(&mut self, game: &Game) {
+        while let Some(message) = self.peer.try_recv() {
+            match message {
+                // as sent by the other side, so it may be anything
+                Message::Progress { percent } => self.opponent = percent.min(100),
+                Message::Solved { millis } => {
+                    self.opponent = 100;
+                    self.opponent_time = Some(Duration::from_millis(millis));
+                }
+                _ => {}
+            }
+        }
+        let percent = progress(game);
+        if self.sent != Some(percent) {
+            self.peer.send(&Message::Progress { percent });
+            self.sent = Some(percent);
+        }
+        if game.status() == GameStatus::Won && self.time.is_none() {
+            self.time = Some(game.elapsed());
+            let millis = game.elapsed().as_millis() as u64;
+            self.peer.send(&Message::Solved { millis });
+        }
+        self.won = winner(self.time, self.opponent_time, game.elapsed());
+    }
+
+    pub fn opponent_left(&self) -> bool {
+        self.peer.is_closed()
+    }
+}
+
+/// Whether the player won, going by the times both sides report rather than
+/// which arrived first. A time not yet reported loses once the clock has run
+/// past the other one, and a tie goes to both.
+fn winner(time: Option<Duration>, opponent: Option<Duration>, elapsed: Duration) -> Option<bool> {
+    match (time, opponent) {
+        (Some(time), Some(opponent)) => Some(time <= opponent),
+        // until the opponent's time arrives, in case latency held it up
+        (Some(_), None) => Some(true),
+        (None, Some(opponent)) => (elapsed > opponent).then_some(false),
+        (None, None) => None,
+    }
+}
+
+/// How much of what was left empty has been filled in, in percent
+pub fn progress(game: &Game) -> u8 {
+    let givens = game.givens().iter().filter(|&&value| value != 0).count();
+    let open = game.cell_count() - givens;
+    match open {
+        0 => 100,
+        open => ((game.filled_count() - givens) * 100 / open) as u8,
+    }
+}
+
+#[cfg(feature = "tui")]
+/// The opponent's progress as a bar, without any of their values
+pub struct RacePanel<'a> {
+    pub race: &'a Race,
+    pub appearance: Appearance,
+}
+
+#[cfg(feature = "tui")]
+impl RacePanel<'_> {
+    pub const HEIGHT: u16 = 4;
+}
+
+#[cfg(feature = "tui")]
+impl Widget for RacePanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let race = self.race;
+        let width = area.width.saturating_sub(2) as usize;
+        let filled = (width * race.opponent as usize / 100).min(width);
+        let bar = Line::from(vec![
+            Span::raw("█".repeat(filled)),
+            Span::styled("░".repeat(width - filled), self.appearance.muted_style()),
+        ]);
+        let status = match (race.won, race.opponent_time) {
+            (Some(true), _) => Line::from("You won!"),
+            (_, Some(time)) => Line::from(format!("Won in {}", format_duration(time))),
+            _ if race.opponent_left() => {
+                Line::styled("Left the race", self.appearance.muted_style())
+            }
+            _ => Line::from(format!("{}% filled", race.opponent)),
+        };
+        Paragraph::new(vec![bar, status])
+            .block(Block::default().title(" Opponent ").borders(Borders::ALL))
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_progress() {
+        let mut game = Game::new(vec![1, 0, 0, 0, 0, 0, 1, 0, 0, 1, 0, 0, 0, 0, 0, 1]).unwrap();
+        assert_eq!(progress(&game), 0);
+        game.add_entry((1, 0), 2).unwrap();
+        game.add_entry((2, 0), 3).unwrap();
+        assert_eq!(progress(&game), 16);
+        game.add_entry((1, 0), 0).unwrap();
+        assert_eq!(progress(&game), 8);
+    }
+
+    #[test]
+    fn compares_finish_times() {
+        let secs = Duration::from_secs;
+        assert_eq!(winner(None, None, secs(5)), None);
+        assert_eq!(winner(Some(secs(30)), None, secs(30)), Some(true));
+        // the opponent's message arriving first doesn't make them the winner
+        assert_eq!(winner(None, Some(secs(40)), secs(35)), None);
+        assert_eq!(winner(Some(secs(35)), Some(secs(40)), secs(35)), Some(true));
+        assert_eq!(winner(None, Some(secs(40)), secs(41)), Some(false));
+        assert_eq!(
+            winner(Some(secs(45)), Some(secs(40)), secs(45)),
+            Some(false)
+        );
+    }
+}
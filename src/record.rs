@@ -0,0 +1,211 @@
+use crate::{
+    difficulty::Difficulty,
+    format::text,
+    game::Game,
+    grid::{position_label, value_symbol},
+    stats,
+    timer::format_duration,
+};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// A finished solve written out move by move, like a chess game's PGN
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveRecord {
+    pub name: Option<String>,
+    pub difficulty: Option<Difficulty>,
+    /// Year, month and day the solve was recorded
+    pub date: (i64, u32, u32),
+    pub givens: Vec<usize>,
+    pub solution: Vec<usize>,
+    /// The entries that stand, undone ones left out, each with the time on
+    /// the clock when it was made
+    pub moves: Vec<Move>,
+    pub elapsed: Duration,
+    /// The clock when each box was finished, in reading order, or `None`
+    /// for a box that was given in full
+    pub splits: Vec<Option<Duration>>,
+    pub mistakes: usize,
+    pub hints_used: usize,
+    pub score: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Move {
+    pub cell: String,
+    /// 0 for clearing the cell
+    pub value: usize,
+    pub at: Duration,
+}
+
+/// The calendar date of a number of days since the Unix epoch
+fn civil_date(days: i64) -> (i64, u32, u32) {
+    // Howard Hinnant's algorithm, counting in 400-year eras from March 1st
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month + 2) / 5 + 1) as u32;
+    let month = if month < 10 { month + 3 } else { month - 9 } as u32;
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+impl SolveRecord {
+    pub fn new(game: &Game, name: Option<&str>) -> Self {
+        let moves = game
+            .entries()
+            .iter()
+            .map(|entry| Move {
+                cell: position_label(entry.position),
+                value: entry.value,
+                at: entry.at,
+            })
+            .collect();
+        Self {
+            name: name.map(str::to_string),
+            difficulty: game.difficulty,
+            date: civil_date(stats::today() as i64),
+            givens: game.givens(),
+            solution: game.values(),
+            moves,
+            elapsed: game.elapsed(),
+            splits: game.box_splits(),
+            mistakes: game.mistakes,
+            hints_used: game.hints_used,
+            score: game.score(),
+        }
+    }
+
+    fn date_label(&self) -> String {
+        let (year, month, day) = self.date;
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+
+    /// Tag pairs in brackets, then the numbered moves with their clock times
+    /// in braces, as in `1. r1c3 4 {00:08}`
+    pub fn to_text(&self) -> String {
+        let difficulty = self
+            .difficulty
+            .map_or("Unrated".to_string(), |difficulty| difficulty.to_string());
+        let splits: Vec<String> = self
+            .splits
+            .iter()
+            .map(|split| split.map_or("-".to_string(), format_duration))
+            .collect();
+        let tags = [
+            ("Puzzle", self.name.clone().unwrap_or("?".to_string())),
+            ("Difficulty", difficulty),
+            ("Date", self.date_label()),
+            ("Time", format_duration(self.elapsed)),
+            ("Splits", splits.join(" ")),
+            ("Mistakes", self.mistakes.to_string()),
+            ("Hints", self.hints_used.to_string()),
+            ("Score", self.score.to_string()),
+            ("Givens", text::write_line(&self.givens)),
+            ("Solution", text::write_line(&self.solution)),
+        ];
+        let mut output: String = tags
+            .iter()
+            .map(|(tag, value)| format!("[{tag} \"{value}\"]\n"))
+            .collect();
+        output.push('\n');
+        let moves: Vec<String> = self
+            .moves
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                let value = match step.value {
+                    0 => '-',
+                    value => value_symbol(value),
+                };
+                format!(
+                    "{}. {} {value} {{{}}}",
+                    i + 1,
+                    step.cell,
+                    format_duration(step.at)
+                )
+            })
+            .collect();
+        // a few moves to a line, so the record reads like a score sheet
+        for line in moves.chunks(4) {
+            output += &line.join(" ");
+            output.push('\n');
+        }
+        output
+    }
+
+    pub fn to_json(&self) -> Value {
+        let rows = |cells: &[usize]| json!(cells.chunks(cells.len().isqrt()).collect::<Vec<_>>());
+        let moves: Vec<Value> = self
+            .moves
+            .iter()
+            .map(|step| {
+                json!({
+                    "cell": step.cell,
+                    "value": step.value,
+                    "seconds": step.at.as_secs_f64(),
+                })
+            })
+            .collect();
+        json!({
+            "puzzle": self.name,
+            "difficulty": self.difficulty,
+            "date": self.date_label(),
+            "givens": rows(&self.givens),
+            "solution": rows(&self.solution),
+            "moves": moves,
+            "seconds": self.elapsed.as_secs_f64(),
+            "splits": self
+                .splits
+                .iter()
+                .map(|split| split.map(|split| split.as_secs_f64()))
+                .collect::<Vec<_>>(),
+            "mistakes": self.mistakes,
+            "hints_used": self.hints_used,
+            "score": self.score,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{library::PUZZLES, solver::solve_cells};
+
+    #[test]
+    fn records_a_solve() {
+        let mut game = PUZZLES[0].game().unwrap();
+        let solution = solve_cells(&game.givens()).unwrap();
+        let size = game.size();
+        for (i, &value) in solution.iter().enumerate() {
+            if game.get_cell((i % size, i / size)) == Ok(0) {
+                game.add_entry((i % size, i / size), value).unwrap();
+            }
+        }
+        let record = SolveRecord::new(&game, Some(PUZZLES[0].name));
+        let text = record.to_text();
+        assert!(text.starts_with(&format!("[Puzzle \"{}\"]\n", PUZZLES[0].name)));
+        assert!(text.contains(&format!("[Solution \"{}\"]", text::write_line(&solution))));
+        assert!(text.contains("\n1. r"));
+        let json = record.to_json();
+        assert_eq!(json["moves"].as_array().unwrap().len(), record.moves.len());
+        assert_eq!(json["solution"][0].as_array().unwrap().len(), size);
+        assert_eq!(json["splits"].as_array().unwrap().len(), size);
+        // every box had a cell to fill, the last finished as the clock stopped
+        assert!(record.splits.iter().all(Option::is_some));
+        let last = record.splits.iter().flatten().max().copied();
+        assert_eq!(last, record.moves.last().map(|step| step.at));
+        assert!(text.contains("[Splits \""));
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(civil_date(0), (1970, 1, 1));
+        assert_eq!(civil_date(19_723), (2024, 1, 1));
+        assert_eq!(civil_date(19_782), (2024, 2, 29));
+    }
+}
@@ -0,0 +1,379 @@
+//! Samurai sudoku: five 9x9 grids, the middle one sharing a corner box with
+//! each of the other four. Every row, column and box of every grid holds
+//! 1 to 9 once, so a shared cell answers to two grids at once.
+//!
+//! Boards are 21 cells a side with positions from the top left, as in a
+//! `Grid`, and the cells outside the five grids are always empty.
+
+use crate::grid::GridPosition;
+#[cfg(feature = "std")]
+use crate::{difficulty::Difficulty, rng::Rng};
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+
+/// Cells along each side of the board
+pub const SIDE: usize = 21;
+/// Top left cell of each grid: the four corners, then the middle
+pub const GRID_ORIGINS: [GridPosition; 5] = [(0, 0), (12, 0), (0, 12), (12, 12), (6, 6)];
+const SIZE: usize = 9;
+const BOX: usize = 3;
+
+/// Whether the cell belongs to any of the five grids
+pub fn contains((x, y): GridPosition) -> bool {
+    x < SIDE && y < SIDE && grids_at((x, y)).next().is_some()
+}
+
+/// The grids a cell belongs to, two for the cells of a shared box
+pub fn grids_at((x, y): GridPosition) -> impl Iterator<Item = usize> {
+    GRID_ORIGINS
+        .iter()
+        .enumerate()
+        .filter(move |(_, &(left, top))| {
+            (left..left + SIZE).contains(&x) && (top..top + SIZE).contains(&y)
+        })
+        .map(|(grid, _)| grid)
+}
+
+/// Every row, column and box of the five grids, as flat cell indices. The
+/// four shared boxes are listed once.
+pub fn units() -> Vec<Vec<usize>> {
+    let mut units: Vec<Vec<usize>> = vec![];
+    for &(left, top) in &GRID_ORIGINS {
+        for i in 0..SIZE {
+            units.push((0..SIZE).map(|x| (top + i) * SIDE + left + x).collect());
+            units.push((0..SIZE).map(|y| (top + y) * SIDE + left + i).collect());
+            let (box_left, box_top) = (left + i % BOX * BOX, top + i / BOX * BOX);
+            let square: Vec<usize> = (0..SIZE)
+                .map(|k| (box_top + k / BOX) * SIDE + box_left + k % BOX)
+                .collect();
+            if !units.contains(&square) {
+                units.push(square);
+            }
+        }
+    }
+    units
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SamuraiError {
+    /// The board isn't `SIDE` by `SIDE` cells
+    InvalidSize,
+    /// A value was given for a cell outside the five grids
+    CellOutOfBounds(GridPosition),
+    InvalidCellValue(usize),
+    ReadonlyCellMutation,
+}
+
+/// A samurai board being played: its givens and the values entered since
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Samurai {
+    givens: Vec<usize>,
+    cells: Vec<usize>,
+}
+
+impl Samurai {
+    pub fn new(givens: Vec<usize>) -> Result<Self, SamuraiError> {
+        if givens.len() != SIDE * SIDE {
+            return Err(SamuraiError::InvalidSize);
+        }
+        for (i, &value) in givens.iter().enumerate() {
+            let position = (i % SIDE, i / SIDE);
+            if value > SIZE {
+                return Err(SamuraiError::InvalidCellValue(value));
+            }
+            if value != 0 && !contains(position) {
+                return Err(SamuraiError::CellOutOfBounds(position));
+            }
+        }
+        Ok(Self {
+            cells: givens.clone(),
+            givens,
+        })
+    }
+
+    pub fn givens(&self) -> &[usize] {
+        &self.givens
+    }
+
+    /// Every cell's value, givens and entries alike
+    pub fn values(&self) -> &[usize] {
+        &self.cells
+    }
+
+    /// The cell's value, 0 if it's empty, or `None` outside the grids
+    pub fn get(&self, position: GridPosition) -> Option<usize> {
+        contains(position).then(|| self.cells[position.1 * SIDE + position.0])
+    }
+
+    pub fn is_given(&self, position: GridPosition) -> bool {
+        contains(position) && self.givens[position.1 * SIDE + position.0] != 0
+    }
+
+    /// Set a cell, 0 to clear it, returning the value it held
+    pub fn set(&mut self, position: GridPosition, value: usize) -> Result<usize, SamuraiError> {
+        if !contains(position) {
+            return Err(SamuraiError::CellOutOfBounds(position));
+        }
+        if value > SIZE {
+            return Err(SamuraiError::InvalidCellValue(value));
+        }
+        if self.is_given(position) {
+            return Err(SamuraiError::ReadonlyCellMutation);
+        }
+        let cell = &mut self.cells[position.1 * SIDE + position.0];
+        Ok(core::mem::replace(cell, value))
+    }
+
+    /// Cells sharing a value with another in a row, column or box of any grid
+    pub fn conflicts(&self) -> BTreeSet<GridPosition> {
+        let mut conflicts = BTreeSet::new();
+        for unit in units() {
+            for (k, &a) in unit.iter().enumerate() {
+                for &b in &unit[k + 1..] {
+                    if self.cells[a] != 0 && self.cells[a] == self.cells[b] {
+                        conflicts.insert((a % SIDE, a / SIDE));
+                        conflicts.insert((b % SIDE, b / SIDE));
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// The values of one grid, row by row like a `Grid`'s
+    pub fn grid_values(&self, grid: usize) -> Vec<usize> {
+        let (left, top) = GRID_ORIGINS[grid];
+        (0..SIZE * SIZE)
+            .map(|i| self.cells[(top + i / SIZE) * SIDE + left + i % SIZE])
+            .collect()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        (0..SIDE * SIDE).all(|i| self.cells[i] != 0 || !contains((i % SIDE, i / SIDE)))
+            && self.conflicts().is_empty()
+    }
+}
+
+/// Backtracking over the most constrained cell across all five grids, with
+/// a bitmask of the values used in each unit
+struct Search<'a> {
+    /// The units each cell is in, empty outside the grids
+    units_of: Vec<Vec<usize>>,
+    used: Vec<u32>,
+    value_order: &'a [usize],
+    limit: usize,
+    count: usize,
+    first_solution: Option<Vec<usize>>,
+}
+
+impl<'a> Search<'a> {
+    /// `None` if the board is the wrong size or its givens clash
+    fn new(cells: &[usize], value_order: &'a [usize], limit: usize) -> Option<Self> {
+        if cells.len() != SIDE * SIDE {
+            return None;
+        }
+        let units = units();
+        let mut units_of = vec![vec![]; SIDE * SIDE];
+        for (unit, members) in units.iter().enumerate() {
+            for &i in members {
+                units_of[i].push(unit);
+            }
+        }
+        let mut search = Self {
+            units_of,
+            used: vec![0; units.len()],
+            value_order,
+            limit,
+            count: 0,
+            first_solution: None,
+        };
+        for (i, &value) in cells.iter().enumerate() {
+            if value != 0 {
+                if value > SIZE
+                    || search.units_of[i].is_empty()
+                    || search.used(i) & (1 << value) != 0
+                {
+                    return None;
+                }
+                search.toggle(i, value);
+            }
+        }
+        Some(search)
+    }
+
+    fn used(&self, i: usize) -> u32 {
+        self.units_of[i]
+            .iter()
+            .fold(0, |used, &unit| used | self.used[unit])
+    }
+
+    fn toggle(&mut self, i: usize, value: usize) {
+        for k in 0..self.units_of[i].len() {
+            self.used[self.units_of[i][k]] ^= 1 << value;
+        }
+    }
+
+    fn run(&mut self, cells: &mut [usize]) {
+        // most constrained empty cell first
+        let mut best: Option<(usize, u32)> = None;
+        for (i, &value) in cells.iter().enumerate() {
+            if value != 0 || self.units_of[i].is_empty() {
+                continue;
+            }
+            let free = SIZE as u32 - (self.used(i) >> 1).count_ones();
+            if best.is_none_or(|(_, best_free)| free < best_free) {
+                best = Some((i, free));
+                if free <= 1 {
+                    break;
+                }
+            }
+        }
+        let Some((i, _)) = best else {
+            self.count += 1;
+            if self.first_solution.is_none() {
+                self.first_solution = Some(cells.to_vec());
+            }
+            return;
+        };
+        let used = self.used(i);
+        for &value in self.value_order {
+            if used & (1 << value) != 0 {
+                continue;
+            }
+            cells[i] = value;
+            self.toggle(i, value);
+            self.run(cells);
+            self.toggle(i, value);
+            cells[i] = 0;
+            if self.count >= self.limit {
+                return;
+            }
+        }
+    }
+}
+
+fn search<'a>(cells: &[usize], value_order: &'a [usize], limit: usize) -> Option<Search<'a>> {
+    let mut search = Search::new(cells, value_order, limit)?;
+    search.run(&mut cells.to_vec());
+    Some(search)
+}
+
+const VALUES: [usize; SIZE] = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+/// Solve a samurai board, as `SIDE * SIDE` cells with 0 for empty
+pub fn solve(cells: &[usize]) -> Option<Vec<usize>> {
+    search(cells, &VALUES, 1)?.first_solution
+}
+
+/// Count the solutions of a samurai board, stopping once `limit` is reached
+pub fn count_solutions(cells: &[usize], limit: usize) -> usize {
+    search(cells, &VALUES, limit).map_or(0, |search| search.count)
+}
+
+/// A samurai puzzle with a unique solution, and that solution
+#[cfg(feature = "std")]
+pub fn generate(difficulty: Difficulty, rng: &mut Rng) -> (Vec<usize>, Vec<usize>) {
+    let mut cells = vec![0; SIDE * SIDE];
+    // the boxes down the middle grid's diagonal share no rows or columns,
+    // so each can take any arrangement
+    for corner in [6, 9, 12] {
+        let mut values = VALUES;
+        rng.shuffle(&mut values);
+        for (k, value) in values.into_iter().enumerate() {
+            cells[(corner + k / BOX) * SIDE + corner + k % BOX] = value;
+        }
+    }
+    let mut value_order = VALUES;
+    rng.shuffle(&mut value_order);
+    let solution = search(&cells, &value_order, 1)
+        .and_then(|search| search.first_solution)
+        .expect("a middle grid's diagonal boxes always complete");
+
+    let percent = match difficulty {
+        Difficulty::Easy => 46,
+        Difficulty::Medium => 40,
+        Difficulty::Hard => 35,
+        Difficulty::Expert => 31,
+    };
+    let cell_count = (0..SIDE * SIDE)
+        .filter(|&i| contains((i % SIDE, i / SIDE)))
+        .count();
+    let target = cell_count * percent / 100;
+    let mut givens = solution.clone();
+    let mut given_count = cell_count;
+    // the shape is the same turned half way round, so givens go in pairs
+    let mut pairs: Vec<usize> = (0..givens.len().div_ceil(2))
+        .filter(|&i| solution[i] != 0)
+        .collect();
+    rng.shuffle(&mut pairs);
+    for i in pairs {
+        if given_count <= target {
+            break;
+        }
+        let mirror = givens.len() - 1 - i;
+        let removed = (givens[i], givens[mirror]);
+        givens[i] = 0;
+        givens[mirror] = 0;
+        if count_solutions(&givens, 2) == 1 {
+            given_count -= if i == mirror { 1 } else { 2 };
+        } else {
+            (givens[i], givens[mirror]) = removed;
+        }
+    }
+    (givens, solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shape() {
+        assert!(contains((0, 0)) && contains((20, 20)) && contains((10, 10)));
+        assert!(!contains((10, 0)) && !contains((0, 10)) && !contains((20, 9)));
+        assert_eq!(grids_at((7, 7)).collect::<Vec<_>>(), [0, 4]);
+        // 27 units a grid, less the four boxes shared
+        assert_eq!(units().len(), 5 * 27 - 4);
+    }
+
+    #[test]
+    fn solves_and_checks_shared_cells() {
+        let solution = solve(&vec![0; SIDE * SIDE]).unwrap();
+        let mut samurai = Samurai::new(solution.clone()).unwrap();
+        assert!(samurai.is_solved());
+        for grid in 0..5 {
+            let cells = samurai.grid_values(grid);
+            assert_eq!(crate::solver::count_solutions(&cells, 2), 1);
+        }
+
+        let mut givens = solution.clone();
+        givens[7 * SIDE + 7] = 0;
+        samurai = Samurai::new(givens.clone()).unwrap();
+        assert_eq!(count_solutions(&givens, 2), 1);
+        // a value clashing only with the middle grid
+        let clash = (1..=9)
+            .find(|&value| {
+                value != solution[7 * SIDE + 7]
+                    && solution[7 * SIDE + 10..7 * SIDE + 15].contains(&value)
+            })
+            .unwrap();
+        samurai.set((7, 7), clash).unwrap();
+        assert!(samurai.conflicts().contains(&(7, 7)));
+        assert_eq!(
+            samurai.set((0, 0), 1),
+            Err(SamuraiError::ReadonlyCellMutation)
+        );
+        assert_eq!(
+            samurai.set((10, 0), 1),
+            Err(SamuraiError::CellOutOfBounds((10, 0)))
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn generates_unique_puzzles() {
+        let (givens, solution) = generate(Difficulty::Easy, &mut Rng::new(5));
+        assert!(Samurai::new(solution.clone()).unwrap().is_solved());
+        assert_eq!(count_solutions(&givens, 2), 1);
+        assert_eq!(solve(&givens), Some(solution));
+    }
+}
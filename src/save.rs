@@ -0,0 +1,449 @@
+use crate::{
+    cage::Cage,
+    constraint::Constraint,
+    difficulty::Difficulty,
+    game::{Entry, Game},
+    grid::{GridError, GridPosition},
+    history::History,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    fs, io,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub const SAVE_FILE: &str = "save.json";
+/// Saves from before they moved to JSON, still read from the same directory
+/// if there's no JSON save
+pub const LEGACY_SAVE_FILE: &str = "save.toml";
+/// Version written to new saves. Bump it whenever the layout changes, and
+/// teach `SavedGame::from_json` to bring the old layout up to date.
+pub const SAVE_VERSION: u32 = 3;
+
+/// An unfinished game, written out when the player leaves it so it can be
+/// resumed from the menu.
+///
+/// Saves are JSON objects. Positions are `[x, y]` pairs from the top left,
+/// grids list every cell row by row with 0 for an empty cell, and times are
+/// whole milliseconds. The undo history is packed as `History::to_bytes`
+/// and written in hex:
+///
+/// ```json
+/// {
+///   "version": 3,
+///   "givens": [5, 3, 0, ...],
+///   "values": [5, 3, 4, ...],
+///   "selected": [2, 0],
+///   "history": "02000400b81f0000",
+///   "notes": [[[3, 0], [2, 6]]],
+///   "elapsed_ms": 64000,
+///   "mistakes": 0,
+///   "mistake_limit": 3,
+///   "zen": false,
+///   "hints_used": 1,
+///   "notes_mode": false,
+///   "diagonals": false,
+///   "windows": false,
+///   "cages": [],
+///   "constraints": [{ "kind": "arrow", "circle": [0, 0], "path": [[1, 1]] }],
+///   "metadata": { "puzzle": 1, "difficulty": "easy", "saved_at": 1760600000 }
+/// }
+/// ```
+///
+/// Version 1 was the TOML file of earlier releases: the same grid, notes
+/// and counters, with the clock as a `Duration`, the puzzle and difficulty
+/// at the top level, and no entries. Version 2 listed the entries as
+/// objects, like `{ "position": [2, 0], "value": 4, "previous_value": 0,
+/// "at_ms": 8120 }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub version: u32,
+    pub givens: Vec<usize>,
+    /// Every cell's value, givens included
+    pub values: Vec<usize>,
+    pub selected: GridPosition,
+    /// The undo history, oldest first, packed and in hex
+    #[serde(default)]
+    pub history: String,
+    #[serde(default)]
+    pub notes: Vec<(GridPosition, Vec<usize>)>,
+    pub elapsed_ms: u64,
+    #[serde(default)]
+    pub mistakes: usize,
+    /// The limit the game was started with, kept even if the setting has
+    /// changed since
+    #[serde(default)]
+    pub mistake_limit: Option<usize>,
+    /// Played on past the mistake limit, so a win doesn't count
+    #[serde(default)]
+    pub zen: bool,
+    #[serde(default)]
+    pub hints_used: usize,
+    /// Whether digits were going into pencil marks
+    #[serde(default)]
+    pub notes_mode: bool,
+    #[serde(default)]
+    pub diagonals: bool,
+    #[serde(default)]
+    pub windows: bool,
+    #[serde(default)]
+    pub cages: Vec<Cage>,
+    /// Rules between particular cells, like arrows
+    #[serde(default)]
+    pub constraints: Vec<Constraint>,
+    #[serde(default)]
+    pub metadata: Metadata,
+}
+
+/// One entry of the undo history in version 2 saves
+#[derive(Deserialize)]
+struct SavedEntryV2 {
+    position: GridPosition,
+    value: usize,
+    previous_value: usize,
+    at_ms: u64,
+}
+
+/// Where the game came from and when it was saved
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// Index into the library when the game is one of its puzzles
+    pub puzzle: Option<usize>,
+    pub difficulty: Option<Difficulty>,
+    /// Seconds since the Unix epoch
+    pub saved_at: Option<u64>,
+}
+
+/// The layout of version 1 saves
+#[derive(Deserialize)]
+struct SavedGameV1 {
+    givens: Vec<usize>,
+    values: Vec<usize>,
+    selected: GridPosition,
+    elapsed: Duration,
+    #[serde(default)]
+    mistakes: usize,
+    #[serde(default)]
+    hints_used: usize,
+    difficulty: Option<Difficulty>,
+    puzzle: Option<usize>,
+    #[serde(default)]
+    diagonals: bool,
+    #[serde(default)]
+    cages: Vec<Cage>,
+    #[serde(default)]
+    notes: Vec<(GridPosition, Vec<usize>)>,
+}
+
+impl From<SavedGameV1> for SavedGame {
+    fn from(saved: SavedGameV1) -> Self {
+        Self {
+            version: SAVE_VERSION,
+            givens: saved.givens,
+            values: saved.values,
+            selected: saved.selected,
+            history: String::new(),
+            notes: saved.notes,
+            elapsed_ms: saved.elapsed.as_millis() as u64,
+            mistakes: saved.mistakes,
+            mistake_limit: None,
+            zen: false,
+            hints_used: saved.hints_used,
+            notes_mode: false,
+            diagonals: saved.diagonals,
+            windows: false,
+            cages: saved.cages,
+            constraints: vec![],
+            metadata: Metadata {
+                puzzle: saved.puzzle,
+                difficulty: saved.difficulty,
+                saved_at: None,
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    ParseLegacy(toml::de::Error),
+    /// Written by a newer release than this one
+    UnsupportedVersion(u64),
+    InvalidGrid(GridError),
+    /// The packed move history doesn't decode, or doesn't fit the grid
+    History,
+}
+
+impl From<io::Error> for SaveError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Parse(error)
+    }
+}
+
+impl From<GridError> for SaveError {
+    fn from(error: GridError) -> Self {
+        Self::InvalidGrid(error)
+    }
+}
+
+impl SavedGame {
+    pub fn new(game: &Game, puzzle: Option<usize>) -> Self {
+        let mut notes: Vec<(GridPosition, Vec<usize>)> = game
+            .all_notes()
+            .map(|(position, notes)| (position, notes.iter().copied().collect()))
+            .collect();
+        notes.sort();
+        let saved_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_secs())
+            .ok();
+        Self {
+            version: SAVE_VERSION,
+            givens: game.givens(),
+            values: game.values(),
+            selected: game.selected,
+            history: to_hex(&game.entries().to_bytes()),
+            notes,
+            elapsed_ms: game.elapsed().as_millis() as u64,
+            mistakes: game.mistakes,
+            mistake_limit: game.mistake_limit(),
+            zen: game.is_zen(),
+            hints_used: game.hints_used,
+            notes_mode: false,
+            diagonals: game.has_diagonals(),
+            windows: game.has_windows(),
+            cages: game.cages().to_vec(),
+            constraints: game.constraints().to_vec(),
+            metadata: Metadata {
+                puzzle,
+                difficulty: game.difficulty,
+                saved_at,
+            },
+        }
+    }
+
+    /// The game as it was left, with its clock running again
+    pub fn game(&self) -> Result<Game, SaveError> {
+        let mut game = Game::new(self.givens.clone())?
+            .with_cages(self.cages.clone())
+            .with_constraints(self.constraints.clone());
+        if self.diagonals {
+            game = game.with_diagonals();
+        }
+        if self.windows {
+            game = game.with_windows();
+        }
+        game.resume_from(&self.values, Duration::from_millis(self.elapsed_ms))?;
+        let entries = from_hex(&self.history)
+            .and_then(|bytes| History::from_bytes(game.size(), &bytes))
+            .ok_or(SaveError::History)?;
+        game.restore_entries(entries)?;
+        for (position, values) in &self.notes {
+            for &value in values {
+                game.toggle_note(*position, value)?;
+            }
+        }
+        game.selected = self.selected;
+        game.difficulty = self.metadata.difficulty;
+        game.mistakes = self.mistakes;
+        game.hints_used = self.hints_used;
+        match self.zen {
+            true => game.resume_in_zen(),
+            false => game.set_mistake_limit(self.mistake_limit),
+        }
+        Ok(game)
+    }
+
+    /// Read a save of any version up to `SAVE_VERSION`, bringing older
+    /// layouts up to date
+    pub fn from_json(contents: &str) -> Result<Self, SaveError> {
+        let value: Value = serde_json::from_str(contents)?;
+        match value.get("version").and_then(Value::as_u64).unwrap_or(1) {
+            1 => Ok(serde_json::from_value::<SavedGameV1>(value)?.into()),
+            2 => Self::from_v2(value),
+            3 => Ok(serde_json::from_value(value)?),
+            version => Err(SaveError::UnsupportedVersion(version)),
+        }
+    }
+
+    /// Pack the entry objects of a version 2 save into the history
+    fn from_v2(mut value: Value) -> Result<Self, SaveError> {
+        let entries: Vec<SavedEntryV2> = match value.get_mut("entries") {
+            Some(entries) => serde_json::from_value(entries.take())?,
+            None => vec![],
+        };
+        let mut saved: Self = serde_json::from_value(value)?;
+        let mut history = History::new(saved.givens.len().isqrt());
+        for entry in entries {
+            history.push(Entry {
+                position: entry.position,
+                value: entry.value,
+                previous_value: entry.previous_value,
+                at: Duration::from_millis(entry.at_ms),
+            });
+        }
+        saved.version = SAVE_VERSION;
+        saved.history = to_hex(&history.to_bytes());
+        Ok(saved)
+    }
+
+    /// The saved game, or `None` if nothing has been saved. A TOML save left
+    /// by an earlier release is read when there's no JSON one.
+    pub fn load(path: impl AsRef<Path>) -> Result<Option<Self>, SaveError> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(contents) => return Self::from_json(&contents).map(Some),
+            Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error.into()),
+            Err(_) => {}
+        }
+        match fs::read_to_string(path.with_file_name(LEGACY_SAVE_FILE)) {
+            Ok(contents) => toml::from_str::<SavedGameV1>(&contents)
+                .map(|saved| Some(saved.into()))
+                .map_err(SaveError::ParseLegacy),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SaveError> {
+        let path = path.as_ref();
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Self::remove_file(path.with_file_name(LEGACY_SAVE_FILE))
+    }
+
+    /// Delete the save once its game is finished
+    pub fn remove(path: impl AsRef<Path>) -> Result<(), SaveError> {
+        let path = path.as_ref();
+        Self::remove_file(path)?;
+        Self::remove_file(path.with_file_name(LEGACY_SAVE_FILE))
+    }
+
+    fn remove_file(path: impl AsRef<Path>) -> Result<(), SaveError> {
+        match fs::remove_file(path) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{game::GameStatus, library::PUZZLES};
+
+    #[test]
+    fn round_trip() {
+        let mut game = PUZZLES[8].game().unwrap();
+        game.add_entry((0, 0), 3).unwrap();
+        game.toggle_note((1, 0), 4).unwrap();
+        game.toggle_note((1, 0), 7).unwrap();
+        game.selected = (1, 0);
+        let saved = SavedGame::new(&game, Some(8));
+        let contents = serde_json::to_string_pretty(&saved).unwrap();
+        let loaded = SavedGame::from_json(&contents).unwrap();
+        assert_eq!(loaded, saved);
+
+        let mut resumed = loaded.game().unwrap();
+        assert_eq!(resumed.get_cell((0, 0)), Ok(3));
+        assert_eq!(resumed.givens(), game.givens());
+        assert_eq!(resumed.cages(), game.cages());
+        assert_eq!(resumed.selected, (1, 0));
+        assert_eq!(
+            resumed.notes((1, 0)).unwrap().iter().collect::<Vec<_>>(),
+            [&4, &7]
+        );
+        assert!(resumed.elapsed().as_millis() as u64 >= saved.elapsed_ms);
+        assert_eq!(resumed.events().len(), 1);
+        resumed.undo_entry().unwrap();
+        assert_eq!(resumed.get_cell((0, 0)), Ok(0));
+
+        let arrows = PUZZLES[10].game().unwrap();
+        let contents = serde_json::to_string(&SavedGame::new(&arrows, Some(10))).unwrap();
+        let resumed = SavedGame::from_json(&contents).unwrap().game().unwrap();
+        assert_eq!(resumed.constraints(), arrows.constraints());
+
+        let mut limited = PUZZLES[1].game().unwrap();
+        limited.set_mistake_limit(Some(3));
+        let resumed = SavedGame::new(&limited, Some(1)).game().unwrap();
+        assert_eq!(resumed.mistake_limit(), Some(3));
+        limited.set_mistake_limit(Some(0));
+        limited.continue_in_zen();
+        let resumed = SavedGame::new(&limited, Some(1)).game().unwrap();
+        assert!(resumed.is_zen() && resumed.mistake_limit().is_none());
+        assert_eq!(resumed.status(), GameStatus::InProgress);
+    }
+
+    #[test]
+    fn migrates_old_saves() {
+        let givens = PUZZLES[1].cells();
+        let mut values = givens.clone();
+        values[2] = 4;
+        let v1 = serde_json::json!({
+            "givens": givens,
+            "values": values,
+            "selected": [2, 0],
+            "elapsed": { "secs": 90, "nanos": 0 },
+            "difficulty": "easy",
+            "puzzle": 1,
+        });
+        let saved = SavedGame::from_json(&v1.to_string()).unwrap();
+        assert_eq!(saved.version, SAVE_VERSION);
+        assert_eq!(saved.elapsed_ms, 90_000);
+        assert_eq!(saved.metadata.puzzle, Some(1));
+        assert_eq!(saved.game().unwrap().get_cell((2, 0)), Ok(4));
+
+        let v2 = serde_json::json!({
+            "version": 2,
+            "givens": givens,
+            "values": values,
+            "selected": [2, 0],
+            "entries": [{ "position": [2, 0], "value": 4, "previous_value": 0, "at_ms": 8120 }],
+            "elapsed_ms": 9000,
+        });
+        let saved = SavedGame::from_json(&v2.to_string()).unwrap();
+        assert_eq!(saved.version, SAVE_VERSION);
+        assert_eq!(saved.history, "02000400b81f0000");
+        let mut game = saved.game().unwrap();
+        let entry = game.undo_entry().unwrap();
+        assert_eq!(
+            (entry.position, entry.at),
+            ((2, 0), Duration::from_millis(8120))
+        );
+        assert_eq!(game.get_cell((2, 0)), Ok(0));
+
+        let future = serde_json::json!({ "version": SAVE_VERSION + 1 });
+        assert!(matches!(
+            SavedGame::from_json(&future.to_string()),
+            Err(SaveError::UnsupportedVersion(_))
+        ));
+
+        let corrupt = SavedGame {
+            history: "02zz".to_string(),
+            ..saved
+        };
+        assert!(matches!(corrupt.game(), Err(SaveError::History)));
+    }
+}
@@ -0,0 +1,107 @@
+use super::{game::GameScreen, pack::PackScreen, render_footer, Context, Screen, Transition};
+use crate::{
+    browser::FileBrowser,
+    format,
+    grid::Regions,
+    keymap::{Action, Hint},
+};
+use crossterm::event::KeyEvent;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, List, ListState},
+};
+
+pub const KEYS: &[(&str, Hint)] = &[
+    ("Move selection", Hint::Actions(&[Action::Down, Action::Up])),
+    ("Open", Hint::Actions(&[Action::Confirm])),
+    ("Help", Hint::Actions(&[Action::Help])),
+    ("Back", Hint::Actions(&[Action::Quit])),
+];
+
+const ACTIONS: &[Action] = &[Action::Quit, Action::Up, Action::Down, Action::Confirm];
+
+pub struct BrowserScreen {
+    browser: FileBrowser,
+    /// Extra regions of the variant chosen to play the opened puzzle as
+    regions: Regions,
+}
+
+impl BrowserScreen {
+    pub fn new(context: &Context) -> Self {
+        Self {
+            browser: FileBrowser::new(std::env::current_dir().unwrap_or_default())
+                .with_packs(context.paths.packs()),
+            regions: Regions::default(),
+        }
+    }
+
+    pub fn with_regions(mut self, regions: Regions) -> Self {
+        self.regions = regions;
+        self
+    }
+}
+
+impl Screen for BrowserScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        let browser = &mut self.browser;
+        match context.config.keys.action(key_event, ACTIONS) {
+            Some(Action::Quit) => return Transition::Pop(1),
+            Some(Action::Up) => browser.move_up(),
+            Some(Action::Down) => browser.move_down(),
+            Some(Action::Confirm) => {
+                if let Some(path) = browser.enter() {
+                    if format::is_collection(&path) {
+                        match format::load_collection(&path) {
+                            Ok(collection) => {
+                                return Transition::Replace(Box::new(PackScreen::new(
+                                    collection, path,
+                                )))
+                            }
+                            Err(error) => browser.error = Some(error.to_string()),
+                        }
+                        return Transition::None;
+                    }
+                    match format::load_game(&path) {
+                        Ok(game) => {
+                            let game = game.with_regions(self.regions);
+                            return GameScreen::start_checked(game, context, 1);
+                        }
+                        Err(error) => browser.error = Some(error.to_string()),
+                    }
+                }
+            }
+            _ => {}
+        }
+        Transition::None
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let appearance = context.config.appearance();
+        let browser = &self.browser;
+        let hints = context.config.keys.hints(KEYS);
+        let body = render_footer(
+            area,
+            buf,
+            " Open Puzzle File ",
+            &hints,
+            appearance.key_hint_style(),
+        );
+        let layout = Layout::new(
+            layout::Direction::Vertical,
+            [Constraint::Min(0), Constraint::Length(1)],
+        )
+        .split(body);
+        let items = browser.entries.iter().map(|entry| entry.label());
+        let title = format!(" {} ", browser.dir.display());
+        let list = List::new(items)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .highlight_style(appearance.selected_style())
+            .highlight_symbol("> ");
+        let mut state = ListState::default().with_selected(Some(browser.selected));
+        StatefulWidget::render(list, layout[0], buf, &mut state);
+        if let Some(error) = &browser.error {
+            Line::styled(error.as_str(), Style::new().fg(appearance.theme.conflict))
+                .render(layout[1], buf);
+        }
+    }
+}
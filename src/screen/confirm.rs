@@ -0,0 +1,61 @@
+use super::{Context, Screen, Transition};
+use crate::{
+    keymap::{Action, Hint},
+    popup::Popup,
+};
+use crossterm::event::KeyEvent;
+use ratatui::prelude::*;
+
+/// Yes/no question drawn over the screen that asked it
+pub struct ConfirmScreen {
+    message: &'static str,
+    /// Applied on yes, so it should also pop this screen
+    on_confirm: Option<Transition>,
+}
+
+impl ConfirmScreen {
+    pub fn new(message: &'static str, on_confirm: Transition) -> Self {
+        Self {
+            message,
+            on_confirm: Some(on_confirm),
+        }
+    }
+}
+
+impl Screen for ConfirmScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        match context
+            .config
+            .keys
+            .action(key_event, &[Action::Yes, Action::No])
+        {
+            Some(Action::Yes) => self.on_confirm.take().unwrap_or(Transition::Pop(1)),
+            Some(Action::No) => Transition::Pop(1),
+            _ => Transition::None,
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let key = context.config.appearance().key_hint_style();
+        let keys = &context.config.keys;
+        Popup {
+            title: " Confirm ",
+            lines: vec![
+                Line::from(self.message),
+                Line::default(),
+                Line::from(vec![
+                    " Yes ".into(),
+                    Span::styled(keys.describe(Hint::Actions(&[Action::Yes])), key),
+                    " No ".into(),
+                    Span::styled(keys.describe(Hint::Actions(&[Action::No])), key),
+                    " ".into(),
+                ]),
+            ],
+        }
+        .render(area, buf);
+    }
+}
@@ -0,0 +1,244 @@
+use super::{
+    game::GameScreen, min_grid_size, render_footer, Context, Screen, Transition, FOOTER_ALLOWANCE,
+};
+use crate::{
+    editor::{Editor, Verdict},
+    keymap::{Action, Hint},
+    motion::{apply_motion, MOTIONS},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use std::{collections::HashMap, fs};
+
+pub const KEYS: &[(&str, Hint)] = &[
+    (
+        "Move selection",
+        Hint::Actions(&[Action::Left, Action::Down, Action::Up, Action::Right]),
+    ),
+    ("Set given", Hint::Fixed("<1-9>")),
+    ("Clear cell", Hint::Actions(&[Action::Clear])),
+    ("Undo/redo", Hint::Actions(&[Action::Undo, Action::Redo])),
+    ("Check solutions", Hint::Actions(&[Action::CheckPuzzle])),
+    ("Save to file", Hint::Actions(&[Action::Export])),
+    ("Draw cages", Hint::Actions(&[Action::Cages])),
+    ("Play", Hint::Actions(&[Action::Confirm])),
+    ("Back", Hint::Actions(&[Action::Quit])),
+];
+
+const ACTIONS: &[Action] = &[
+    Action::Quit,
+    Action::Clear,
+    Action::Undo,
+    Action::Redo,
+    Action::CheckPuzzle,
+    Action::Export,
+    Action::Cages,
+    Action::Confirm,
+];
+
+pub const CAGE_KEYS: &[(&str, Hint)] = &[
+    (
+        "Move selection",
+        Hint::Actions(&[Action::Left, Action::Down, Action::Up, Action::Right]),
+    ),
+    ("Pick cell", Hint::Actions(&[Action::PickCell])),
+    ("Type sum", Hint::Fixed("<0-9>")),
+    ("Add cage", Hint::Actions(&[Action::Confirm])),
+    ("Erase sum or cage", Hint::Actions(&[Action::Clear])),
+    ("Check solutions", Hint::Actions(&[Action::CheckPuzzle])),
+    (
+        "Back to givens",
+        Hint::Actions(&[Action::Cages, Action::Quit]),
+    ),
+];
+
+const CAGE_ACTIONS: &[Action] = &[
+    Action::Quit,
+    Action::Cages,
+    Action::PickCell,
+    Action::Clear,
+    Action::CheckPuzzle,
+    Action::Confirm,
+];
+
+/// Digits a cage sum can have
+const SUM_DIGITS: usize = 2;
+
+/// Where the editor saves puzzles, in the working directory
+const SAVE_PATH: &str = "puzzle.sdk";
+
+/// Rows under the board for the given count and the last check
+const STATUS_HEIGHT: u16 = 2;
+
+/// Enter a puzzle by hand, then save it or play it
+pub struct EditorScreen {
+    editor: Editor,
+    /// Drawing cages rather than entering givens, with the digits of the
+    /// next cage's sum typed so far
+    cage_sum: Option<String>,
+}
+
+impl Default for EditorScreen {
+    fn default() -> Self {
+        Self {
+            editor: Editor::new(9).unwrap(),
+            cage_sum: None,
+        }
+    }
+}
+
+impl EditorScreen {
+    fn handle_cage_key(&mut self, key_event: KeyEvent, context: &mut Context) {
+        let keys = &context.config.keys;
+        let editor = &mut self.editor;
+        let Some(sum) = &mut self.cage_sum else {
+            return;
+        };
+        if let KeyCode::Char(digit @ '0'..='9') = key_event.code {
+            if sum.len() < SUM_DIGITS {
+                sum.push(digit);
+            }
+            return;
+        }
+        if let Some(action) = keys.action(key_event, MOTIONS) {
+            apply_motion(&mut editor.game, action, None, context.config.wrap_cursor);
+            return;
+        }
+        match keys.action(key_event, CAGE_ACTIONS) {
+            Some(Action::Quit | Action::Cages) => self.cage_sum = None,
+            Some(Action::PickCell) => {
+                if let Err(error) = editor.toggle_draft() {
+                    context.toast(format!("Could not pick the cell: {error}"));
+                }
+            }
+            Some(Action::Clear) => {
+                let erased = sum.pop().is_some() || editor.remove_cage();
+                if !erased {
+                    context.toast("No cage to remove");
+                }
+            }
+            Some(Action::CheckPuzzle) => {
+                editor.check();
+            }
+            Some(Action::Confirm) => match sum.parse() {
+                Ok(total) => match editor.add_cage(total) {
+                    Ok(()) => sum.clear(),
+                    Err(error) => context.toast(format!("Could not add the cage: {error}")),
+                },
+                Err(_) => context.toast("Type the cage's sum first"),
+            },
+            _ => {}
+        }
+    }
+}
+
+impl Screen for EditorScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        if self.cage_sum.is_some() {
+            self.handle_cage_key(key_event, context);
+            return Transition::None;
+        }
+        let keys = &context.config.keys;
+        let editor = &mut self.editor;
+        if let KeyCode::Char(digit @ '1'..='9') = key_event.code {
+            let _ = editor.set(digit as usize - '0' as usize);
+            return Transition::None;
+        }
+        if let Some(action) = keys.action(key_event, MOTIONS) {
+            apply_motion(&mut editor.game, action, None, context.config.wrap_cursor);
+            return Transition::None;
+        }
+        match keys.action(key_event, ACTIONS) {
+            Some(Action::Quit) => return Transition::Pop(1),
+            Some(Action::Clear) => {
+                let _ = editor.set(0);
+            }
+            Some(Action::Undo) => {
+                let undone = editor.undo();
+                if !undone {
+                    context.toast("Nothing to undo");
+                }
+            }
+            Some(Action::Redo) => {
+                let redone = editor.redo();
+                if !redone {
+                    context.toast("Nothing to redo");
+                }
+            }
+            Some(Action::CheckPuzzle) => {
+                editor.check();
+            }
+            Some(Action::Export) => match fs::write(SAVE_PATH, editor.to_sdk()) {
+                Ok(()) => context.toast(format!("Saved to {SAVE_PATH}")),
+                Err(_) => context.toast(format!("Could not write {SAVE_PATH}")),
+            },
+            Some(Action::Cages) => self.cage_sum = Some(String::new()),
+            Some(Action::Confirm) => match editor.puzzle() {
+                Ok(game) => return GameScreen::start_checked(game, context, 1),
+                Err(_) => context.toast("Could not start the puzzle"),
+            },
+            _ => {}
+        }
+        Transition::None
+    }
+
+    fn min_size(&self) -> (u16, u16) {
+        let (width, height) = min_grid_size(&self.editor.game);
+        (width, height + STATUS_HEIGHT + FOOTER_ALLOWANCE)
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let appearance = context.config.appearance();
+        let keys = match self.cage_sum {
+            Some(_) => CAGE_KEYS,
+            None => KEYS,
+        };
+        let hints = context.config.keys.hints(keys);
+        let body = render_footer(
+            area,
+            buf,
+            " Puzzle Editor ",
+            &hints,
+            appearance.key_hint_style(),
+        );
+        let [grid_area, status_area] = Layout::new(
+            layout::Direction::Vertical,
+            [Constraint::Min(0), Constraint::Length(STATUS_HEIGHT)],
+        )
+        .areas(body);
+        let editor = &self.editor;
+        // the cells picked for the next cage
+        let highlights = editor
+            .draft()
+            .iter()
+            .filter(|_| self.cage_sum.is_some())
+            .map(|&position| (position, appearance.theme.hint))
+            .collect::<HashMap<_, _>>();
+        editor
+            .game
+            .render_with_highlights(grid_area, buf, appearance, highlights);
+        let verdict = match editor.verdict() {
+            // the techniques it takes, as the rating is drawn from them
+            Some(verdict @ Verdict::Unique(rating)) if !rating.techniques.is_empty() => {
+                let techniques: Vec<String> =
+                    rating.techniques.iter().map(ToString::to_string).collect();
+                format!("{verdict} ({})", techniques.join(", "))
+            }
+            Some(verdict) => verdict.to_string(),
+            None => "Not checked yet".to_string(),
+        };
+        let counts = match &self.cage_sum {
+            Some(sum) => format!(
+                "{} cages | picked {} cells, sum {sum}_",
+                editor.game.cages().len(),
+                editor.draft().len()
+            ),
+            None => format!("{} givens", editor.given_count()),
+        };
+        let lines = vec![
+            Line::from(counts).centered(),
+            Line::from(verdict).centered(),
+        ];
+        Text::from(lines).render(status_area, buf);
+    }
+}
@@ -0,0 +1,1103 @@
+use super::{
+    confirm::ConfirmScreen, footer_layout, min_grid_size, render_footer, settings::SettingsScreen,
+    solver::SolverScreen, Context, Screen, Transition, FOOTER_ALLOWANCE,
+};
+use crate::{
+    announce::{describe_selection, Snapshot},
+    config::{Autosave, Config},
+    coop::Coop,
+    editor::Verdict,
+    format::{csv, sdk::SdkGame},
+    game::{CandidatePanel, DigitPanel, Game, GameStatus, HistoryPanel, StatusBar, FLASH_DURATION},
+    grid::parse_position,
+    hint::{self, find_hint},
+    keymap::{Action, Hint, Keymap},
+    library::PUZZLES,
+    motion::{apply_motion, MotionParser, COUNT_TIMEOUT, MOTIONS},
+    paths::fresh_name,
+    popup::Popup,
+    race::{Race, RacePanel},
+    record::SolveRecord,
+    rng::Rng,
+    save::{SaveError, SavedGame},
+    solver::solve_game,
+    stats::today,
+    timer::format_duration,
+};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{prelude::*, widgets::Paragraph};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+const MOVE: Hint = Hint::Actions(&[Action::Left, Action::Down, Action::Up, Action::Right]);
+const INSERT: Hint = Hint::Fixed("<1-9>");
+
+pub const KEYS: &[(&str, Hint)] = &[
+    ("Move selection", MOVE),
+    (
+        "Jump a box",
+        Hint::Actions(&[
+            Action::JumpLeft,
+            Action::JumpDown,
+            Action::JumpUp,
+            Action::JumpRight,
+        ]),
+    ),
+    (
+        "Row start/end",
+        Hint::Actions(&[Action::RowStart, Action::RowEnd]),
+    ),
+    (
+        "First (twice)/last row",
+        Hint::Actions(&[Action::FirstRow, Action::LastRow]),
+    ),
+    (
+        "Repeat the next move (number-first)",
+        Hint::Fixed("<count>"),
+    ),
+    ("Go to cell, like r4c7", Hint::Actions(&[Action::GoTo])),
+    ("Insert number", INSERT),
+    ("Place picked number", Hint::Actions(&[Action::Confirm])),
+    ("Clear cell", Hint::Actions(&[Action::Clear])),
+    ("Toggle notes mode", Hint::Actions(&[Action::Notes])),
+    ("Undo/redo", Hint::Actions(&[Action::Undo, Action::Redo])),
+    (
+        "Scroll history",
+        Hint::Actions(&[Action::HistoryBack, Action::HistoryForward]),
+    ),
+    ("Pause", Hint::Actions(&[Action::Pause])),
+    ("Hint", Hint::Actions(&[Action::Hint])),
+    ("Solve from here", Hint::Actions(&[Action::Solver])),
+    (
+        "Export to .sdk/.csv",
+        Hint::Actions(&[Action::Export, Action::ExportCsv]),
+    ),
+    ("Restart puzzle", Hint::Actions(&[Action::Restart])),
+    ("Give up and reveal", Hint::Actions(&[Action::Reveal])),
+    ("Settings", Hint::Actions(&[Action::Settings])),
+    ("Help", Hint::Actions(&[Action::Help])),
+    ("Quit to menu", Hint::Actions(&[Action::Quit])),
+];
+
+/// The short list shown under the board; the full list is in `KEYS`
+const FOOTER_KEYS: &[(&str, Hint)] = &[
+    ("Move selection", MOVE),
+    ("Insert number", INSERT),
+    ("Help", Hint::Actions(&[Action::Help])),
+    ("Quit to menu", Hint::Actions(&[Action::Quit])),
+];
+
+/// The keys shown once the solution is revealed, with the board left to look
+/// over
+const REVEALED_KEYS: &[(&str, Hint)] = &[
+    ("Move selection", MOVE),
+    ("Next puzzle", Hint::Actions(&[Action::NextPuzzle])),
+    ("Quit to menu", Hint::Actions(&[Action::Quit])),
+];
+
+/// Actions other than the motions in `MOTIONS`
+const ACTIONS: &[Action] = &[
+    Action::Quit,
+    Action::Settings,
+    Action::GoTo,
+    Action::Confirm,
+    Action::Clear,
+    Action::Notes,
+    Action::Pause,
+    Action::Restart,
+    Action::Reveal,
+    Action::Hint,
+    Action::Solver,
+    Action::Export,
+    Action::ExportCsv,
+    Action::Undo,
+    Action::Redo,
+    Action::HistoryBack,
+    Action::HistoryForward,
+];
+
+pub struct GameScreen {
+    game: Game,
+    previous_best: Option<Duration>,
+    /// Index into the library when playing one of its puzzles
+    puzzle: Option<usize>,
+    /// The collection file and position within it when playing from a pack
+    pack: Option<(PathBuf, usize)>,
+    /// How many events the history panel is scrolled back from the newest
+    history_scroll: usize,
+    /// The digit picked in number-first input
+    armed: Option<usize>,
+    /// Digits toggle pencil marks instead of placing values
+    notes_mode: bool,
+    motions: MotionParser,
+    /// Coordinates typed after the go-to key, while it's waiting for Enter
+    goto: Option<String>,
+    /// Asking whether to wipe the entries and start over
+    restarting: bool,
+    /// Asking whether to give up and fill in the solution
+    revealing: bool,
+    /// The last hint asked for, shown until the next input
+    hint: Option<hint::Hint>,
+    /// The opponent when racing another player over the network
+    race: Option<Race>,
+    /// The other player when sharing the board over the network
+    coop: Option<Coop>,
+    /// When the player last pressed a key or clicked, to stop the clock once
+    /// they've stepped away
+    last_input: Instant,
+    /// What the last action did, in words for screen readers
+    announcement: Option<String>,
+}
+
+impl GameScreen {
+    pub fn new(mut game: Game, context: &Context) -> Self {
+        let previous_best = context.stats.puzzle_best(&game);
+        if context.config.auto_notes && game.all_notes().next().is_none() {
+            game.fill_notes();
+        }
+        // a resumed game keeps the limit it was started with
+        if !game.is_zen() && game.mistake_limit().is_none() {
+            game.set_mistake_limit(context.config.mistake_limit.limit());
+        }
+        Self {
+            game,
+            previous_best,
+            puzzle: None,
+            pack: None,
+            history_scroll: 0,
+            armed: None,
+            notes_mode: false,
+            motions: MotionParser::default(),
+            goto: None,
+            restarting: false,
+            revealing: false,
+            hint: None,
+            race: None,
+            coop: None,
+            last_input: Instant::now(),
+            announcement: None,
+        }
+    }
+
+    pub fn from_library(puzzle: usize, context: &Context) -> Self {
+        Self {
+            puzzle: Some(puzzle),
+            ..Self::new(PUZZLES[puzzle].game().unwrap(), context)
+        }
+    }
+
+    pub fn from_pack(game: Game, file: PathBuf, index: usize, context: &Context) -> Self {
+        Self {
+            pack: Some((file, index)),
+            ..Self::new(game, context)
+        }
+    }
+
+    /// Race another player on the same puzzle
+    pub fn racing(game: Game, race: Race, context: &Context) -> Self {
+        Self {
+            race: Some(race),
+            ..Self::new(game, context)
+        }
+    }
+
+    /// Fill in the board together with another player
+    pub fn cooperating(game: Game, coop: Coop, context: &Context) -> Self {
+        Self {
+            coop: Some(coop),
+            ..Self::new(game, context)
+        }
+    }
+
+    /// Pick up the game left unfinished where the player left it
+    pub fn resume(saved: &SavedGame, context: &Context) -> Result<Self, SaveError> {
+        Ok(Self {
+            puzzle: saved.metadata.puzzle,
+            notes_mode: saved.notes_mode,
+            ..Self::new(saved.game()?, context)
+        })
+    }
+
+    /// Start a game on a puzzle entered by hand or imported, in place of the
+    /// top `replaced` screens. If the puzzle doesn't have exactly one
+    /// solution, the player is asked whether to play it anyway first.
+    pub fn start_checked(mut game: Game, context: &Context, replaced: usize) -> Transition {
+        let verdict = match game.cages() {
+            [] => Verdict::check_in(&game.givens(), game.regions()),
+            cages => Verdict::check_killer(&game.givens(), game.regions(), cages),
+        };
+        let question = match verdict {
+            Verdict::Unique(rating) => {
+                game.difficulty = game.difficulty.or(Some(rating.difficulty));
+                None
+            }
+            Verdict::Conflicting => {
+                Some("Some givens clash, so there are 0 solutions. Play anyway?")
+            }
+            Verdict::NoSolution => Some("This puzzle has 0 solutions. Play anyway?"),
+            Verdict::MultipleSolutions => Some("This puzzle has multiple solutions. Play anyway?"),
+            Verdict::InvalidCages(_) => Some("The cages don't tile the grid. Play anyway?"),
+            // the cage sums may well narrow it down to one
+            Verdict::Tiled => None,
+        };
+        let screen = Box::new(Self::new(game, context));
+        match question {
+            Some(question) => Transition::Push(Box::new(ConfirmScreen::new(
+                question,
+                Transition::Swap(replaced + 1, screen),
+            ))),
+            None => Transition::Swap(replaced, screen),
+        }
+    }
+
+    fn quit_to_menu() -> Transition {
+        Transition::Push(Box::new(ConfirmScreen::new(
+            "Quit to menu? Unsaved progress will be lost.",
+            Transition::Pop(2),
+        )))
+    }
+
+    /// Key instructions under the board, or the explanation of a hint
+    fn footer<'a>(&'a self, keys: &Keymap) -> Vec<(&'a str, String)> {
+        match &self.hint {
+            Some(hint) => vec![(hint.explanation.as_str(), String::new())],
+            None if self.game.status() == GameStatus::Revealed => keys.hints(REVEALED_KEYS),
+            None => keys.hints(FOOTER_KEYS),
+        }
+    }
+
+    fn next_puzzle(&self, context: &Context) -> Transition {
+        let next = self.puzzle.map_or(0, |puzzle| (puzzle + 1) % PUZZLES.len());
+        Transition::Replace(Box::new(GameScreen::from_library(next, context)))
+    }
+
+    /// Start the puzzle over, with the mistake limit back from any zen mode
+    fn restart(&mut self, context: &Context) {
+        self.game.reset();
+        self.game
+            .set_mistake_limit(context.config.mistake_limit.limit());
+        self.history_scroll = 0;
+    }
+
+    /// The picked digit, if number-first input is still turned on
+    fn armed(&self, context: &Context) -> Option<usize> {
+        self.armed.filter(|_| context.config.number_first)
+    }
+
+    fn play_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        let armed = self.armed(context);
+        let game = &mut self.game;
+        if context.config.reduced_motion {
+            // the rejected cell stayed marked until now
+            game.clear_rejected();
+        }
+        let keys = &context.config.keys;
+        if game.status() == GameStatus::Won {
+            const WON_ACTIONS: &[Action] = &[
+                Action::NextPuzzle,
+                Action::Shuffle,
+                Action::ExportRecord,
+                Action::Quit,
+            ];
+            return match keys.action(key_event, WON_ACTIONS) {
+                Some(Action::NextPuzzle) => self.next_puzzle(context),
+                // the same puzzle in disguise, to play again afresh
+                Some(Action::Shuffle) => {
+                    let game = game.shuffled(&mut Rng::from_time());
+                    Transition::Replace(Box::new(GameScreen::new(game, context)))
+                }
+                // the record as text to read and as JSON for other tools,
+                // kept with the saves
+                Some(Action::ExportRecord) => {
+                    let name = self.puzzle.map(|puzzle| PUZZLES[puzzle].name);
+                    let record = SolveRecord::new(game, name);
+                    let file = name.unwrap_or("sudoku").to_lowercase().replace(' ', "-");
+                    let dir = context.paths.records();
+                    // numbered past earlier solves of the same puzzle
+                    let file = fresh_name(&dir, &format!("{file}-solve"), &[".txt", ".json"]);
+                    let written = fs::create_dir_all(&dir)
+                        .and_then(|()| fs::write(dir.join(format!("{file}.txt")), record.to_text()))
+                        .and_then(|()| {
+                            let json = record.to_json().to_string();
+                            fs::write(dir.join(format!("{file}.json")), json)
+                        });
+                    match written {
+                        Ok(()) => {
+                            context.toast(format!("Saved the solve record to {}", dir.display()))
+                        }
+                        Err(_) => context.toast(format!("Could not write to {}", dir.display())),
+                    }
+                    Transition::None
+                }
+                Some(Action::Quit) => Transition::Pop(1),
+                _ => Transition::None,
+            };
+        }
+        if game.is_paused() {
+            return match keys.action(key_event, &[Action::Pause, Action::Quit]) {
+                Some(Action::Pause) => {
+                    game.resume();
+                    Transition::None
+                }
+                Some(Action::Quit) => Self::quit_to_menu(),
+                _ => Transition::None,
+            };
+        }
+        if game.status() == GameStatus::Revealed {
+            if let Some(action) = keys.action(key_event, MOTIONS) {
+                apply_motion(game, action, None, context.config.wrap_cursor);
+            }
+            return match keys.action(key_event, &[Action::NextPuzzle, Action::Quit]) {
+                Some(Action::NextPuzzle) => self.next_puzzle(context),
+                Some(Action::Quit) => Transition::Pop(1),
+                _ => Transition::None,
+            };
+        }
+        if game.status() == GameStatus::Lost {
+            match keys.action(key_event, &[Action::Zen, Action::Restart, Action::Quit]) {
+                Some(Action::Zen) => game.continue_in_zen(),
+                Some(Action::Restart) => self.restart(context),
+                Some(Action::Quit) => return Transition::Pop(1),
+                _ => {}
+            }
+            return Transition::None;
+        }
+        if self.restarting {
+            match keys.action(key_event, &[Action::Yes, Action::No]) {
+                Some(Action::Yes) => {
+                    self.restart(context);
+                    self.restarting = false;
+                }
+                Some(Action::No) => self.restarting = false,
+                _ => {}
+            }
+            return Transition::None;
+        }
+        if self.revealing {
+            match keys.action(key_event, &[Action::Yes, Action::No]) {
+                Some(Action::Yes) => {
+                    match solve_game(game) {
+                        Some(solution) => {
+                            game.reveal(&solution).unwrap();
+                            record_outcome(game, context);
+                        }
+                        None => context.toast("This puzzle has no solution to reveal"),
+                    }
+                    self.hint = None;
+                    self.revealing = false;
+                }
+                Some(Action::No) => self.revealing = false,
+                _ => {}
+            }
+            return Transition::None;
+        }
+        let (rules, wrap) = (EntryRules::new(&context.config), context.config.wrap_cursor);
+        let notes = self.notes_mode;
+        self.hint = None;
+        if let Some(text) = &mut self.goto {
+            match key_event.code {
+                KeyCode::Enter => {
+                    match parse_position(text, game.size()) {
+                        Some(position) => game.selected = position,
+                        None => context.toast(format!("No cell \"{text}\"")),
+                    }
+                    self.goto = None;
+                }
+                KeyCode::Esc => self.goto = None,
+                KeyCode::Backspace => {
+                    text.pop();
+                }
+                KeyCode::Char(c) => text.push(c),
+                _ => {}
+            }
+            return Transition::None;
+        }
+        // digits only make counts while they pick a number rather than
+        // place it, so entering values never waits on a motion
+        let counts = context.config.number_first;
+        if let KeyCode::Char(digit @ '0'..='9') = key_event.code {
+            if self.motions.push_digit(digit as usize - '0' as usize) {
+                let size = game.size();
+                if let Some(value) = (!counts).then(|| self.motions.complete(size)).flatten() {
+                    type_value(game, &mut self.armed, value, notes, context);
+                    record_outcome(game, context);
+                }
+                return Transition::None;
+            }
+        }
+        if let Some(action) = keys.action(key_event, MOTIONS) {
+            if let Some(value) = (!counts).then(|| self.motions.flush()).flatten() {
+                type_value(game, &mut self.armed, value, notes, context);
+                record_outcome(game, context);
+            }
+            if let Some((action, count)) = self.motions.motion(action) {
+                apply_motion(game, action, count, wrap);
+            }
+            return Transition::None;
+        }
+        // the number typed wasn't a count after all
+        if let Some(value) = self.motions.flush() {
+            type_value(game, &mut self.armed, value, notes, context);
+        }
+        match keys.action(key_event, ACTIONS) {
+            Some(Action::Quit) => return Self::quit_to_menu(),
+            Some(Action::Settings) => {
+                return Transition::Push(Box::new(SettingsScreen::new(context)))
+            }
+            Some(Action::GoTo) => self.goto = Some(String::new()),
+            Some(Action::Confirm) => {
+                if let Some(digit) = armed {
+                    place_armed(game, digit, notes, rules);
+                }
+            }
+            Some(Action::Clear) if notes => game.clear_notes(game.selected),
+            Some(Action::Clear) => enter_value(game, 0, rules),
+            Some(Action::Notes) => self.notes_mode = !notes,
+            Some(Action::Pause) => game.pause(),
+            Some(Action::Restart) if self.coop.is_some() => {
+                context.toast("The board is shared, so it can't be restarted")
+            }
+            Some(Action::Restart) => self.restarting = true,
+            Some(Action::Reveal) if self.race.is_some() || self.coop.is_some() => {
+                context.toast("The solution can't be revealed in a game over the network")
+            }
+            Some(Action::Reveal) => self.revealing = true,
+            Some(Action::Solver) if !game.is_valid() => {
+                context.toast("Fix the conflicts before solving")
+            }
+            Some(Action::Solver) => {
+                return Transition::Push(Box::new(SolverScreen::new(game.snapshot())))
+            }
+            Some(action @ (Action::Export | Action::ExportCsv)) => {
+                let name = self.puzzle.map_or("sudoku", |puzzle| PUZZLES[puzzle].name);
+                let name = name.to_lowercase().replace(' ', "-");
+                // with the saves, under a name not taken by an earlier export
+                let dir = context.paths.exports();
+                let (extension, contents) = match action {
+                    Action::Export => ("sdk", SdkGame::from_game(game).write()),
+                    _ => ("csv", csv::write(&game.values())),
+                };
+                let name = fresh_name(&dir, &name, &[&format!(".{extension}")]);
+                let path = dir.join(format!("{name}.{extension}"));
+                let written = fs::create_dir_all(&dir).and_then(|()| fs::write(&path, contents));
+                match written {
+                    Ok(()) => context.toast(format!("Exported to {}", path.display())),
+                    Err(_) => context.toast(format!("Could not write {}", path.display())),
+                }
+            }
+            Some(Action::Hint) => match find_hint(game) {
+                Some(hint) => {
+                    game.hints_used += 1;
+                    game.selected = hint.position;
+                    self.hint = Some(hint);
+                }
+                None => context.toast("No hint available"),
+            },
+            Some(Action::Undo) => {
+                let undone = game.undo_entry();
+                if undone.is_none() {
+                    context.toast("Nothing to undo");
+                }
+            }
+            Some(Action::Redo) => {
+                let redone = game.redo_entry();
+                if redone.is_none() {
+                    context.toast("Nothing to redo");
+                }
+            }
+            Some(Action::HistoryBack) => scroll_history(&mut self.history_scroll, game, 1),
+            Some(Action::HistoryForward) => scroll_history(&mut self.history_scroll, game, -1),
+            _ => {}
+        }
+        record_outcome(game, context);
+        Transition::None
+    }
+}
+
+impl Screen for GameScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        self.last_input = Instant::now();
+        let before = Snapshot::take(&self.game, self.notes_mode);
+        let transition = self.play_key(key_event, context);
+        if context.config.screen_reader {
+            self.announcement = before.announce(&self.game, self.notes_mode);
+        }
+        transition
+    }
+
+    fn handle_mouse(&mut self, mouse_event: MouseEvent, context: &mut Context) -> Transition {
+        self.last_input = Instant::now();
+        let asking = self.restarting || self.revealing;
+        if self.game.is_paused() || self.game.status() != GameStatus::InProgress || asking {
+            return Transition::None;
+        }
+        // laid out as last drawn, before a click dismisses the hint
+        let footer = self.footer(&context.config.keys);
+        let [grid_area, panel_area, _, mut history_area, _] =
+            game_areas(context.area, &self.game, &footer, &context.config);
+        if self.race.is_some() {
+            history_area = split_race_area(history_area)[1];
+        }
+        // enter a typed number before the click moves the selection
+        if let MouseEventKind::Down(_) = mouse_event.kind {
+            self.hint = None;
+            if let Some(value) = self.motions.flush() {
+                let notes = self.notes_mode;
+                type_value(&mut self.game, &mut self.armed, value, notes, context);
+            }
+        }
+        let (column, row) = (mouse_event.column, mouse_event.row);
+        if history_area.intersects(Rect::new(column, row, 1, 1)) {
+            let (scroll, game) = (&mut self.history_scroll, &self.game);
+            match mouse_event.kind {
+                MouseEventKind::ScrollUp => scroll_history(scroll, game, -1),
+                MouseEventKind::ScrollDown => scroll_history(scroll, game, 1),
+                _ => {}
+            }
+            return Transition::None;
+        }
+        let armed = self.armed(context);
+        let notes = self.notes_mode;
+        let game = &mut self.game;
+        let rules = EntryRules::new(&context.config);
+        match mouse_event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(position) = game.position_at(grid_area, column, row) {
+                    game.selected = position;
+                    if let Some(digit) = armed {
+                        place_armed(game, digit, notes, rules);
+                    }
+                } else if let Some(digit) =
+                    DigitPanel::digit_at(panel_area, game.size(), column, row)
+                {
+                    match context.config.number_first {
+                        true => arm(&mut self.armed, digit),
+                        false => input(game, digit, notes, rules),
+                    }
+                }
+            }
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                let Some(position) = game.position_at(grid_area, column, row) else {
+                    return Transition::None;
+                };
+                game.selected = position;
+                let (value, values) = (game.get_cell(position).unwrap(), game.size() + 1);
+                let value = match mouse_event.kind {
+                    MouseEventKind::ScrollUp => (value + 1) % values,
+                    _ => (value + values - 1) % values,
+                };
+                enter_value(game, value, rules);
+            }
+            _ => {}
+        }
+        record_outcome(game, context);
+        Transition::None
+    }
+
+    fn on_tick(&mut self, context: &mut Context) -> Transition {
+        let before = Snapshot::take(&self.game, self.notes_mode);
+        if let Some(value) = self
+            .motions
+            .expired()
+            .filter(|_| self.game.status() == GameStatus::InProgress)
+        {
+            let notes = self.notes_mode;
+            type_value(&mut self.game, &mut self.armed, value, notes, context);
+            record_outcome(&self.game, context);
+        }
+        if let Some(race) = &mut self.race {
+            race.update(&self.game);
+        }
+        if let Some(broadcast) = &mut context.broadcast {
+            broadcast.update(&self.game);
+        }
+        if let Some(coop) = &mut self.coop {
+            let finished = self.game.status() != GameStatus::InProgress;
+            coop.update(&mut self.game);
+            if coop.partner_left() {
+                context.toast("The other player has left");
+                self.coop = None;
+            } else if !finished {
+                // the other player's entry may have finished the puzzle
+                record_outcome(&self.game, context);
+            }
+        }
+        // a networked game goes on without this player, so its clock keeps
+        // running
+        let networked = self.race.is_some() || self.coop.is_some();
+        if let Some(idle) = context.config.idle_pause.duration() {
+            if !networked && self.last_input.elapsed() >= idle {
+                self.game.pause_idle(idle);
+            }
+        }
+        // a typed number or the other player's entry, which no key announced
+        if let Some(text) = before
+            .announce(&self.game, self.notes_mode)
+            .filter(|_| context.config.screen_reader)
+        {
+            self.announcement = Some(text);
+        }
+        Transition::None
+    }
+
+    fn on_exit(&mut self, context: &mut Context) {
+        if let Some((file, index)) = self
+            .pack
+            .as_ref()
+            .filter(|_| self.game.status() == GameStatus::Won)
+        {
+            context.progress.mark_solved(file, *index);
+            if context.progress.save(context.paths.progress()).is_err() {
+                context.toast("Could not save pack progress");
+            }
+        }
+        if self.game.status() != GameStatus::Won {
+            context.streak = 0;
+        }
+        if self.game.status() == GameStatus::InProgress {
+            if context.config.autosave == Autosave::Off {
+                return;
+            }
+            let saved = SavedGame {
+                notes_mode: self.notes_mode,
+                ..SavedGame::new(&self.game, self.puzzle)
+            };
+            match saved.save(context.paths.save()) {
+                Ok(()) => context.saved = Some(saved),
+                Err(_) => context.toast("Could not save the game"),
+            }
+        } else if context
+            .saved
+            .as_ref()
+            .is_some_and(|saved| saved.givens == self.game.givens())
+        {
+            // the saved game is the one just finished
+            if SavedGame::remove(context.paths.save()).is_ok() {
+                context.saved = None;
+            }
+        }
+    }
+
+    fn tick_rate(&self) -> Duration {
+        match self.game.flashed() {
+            Some(_) => FLASH_DURATION,
+            // check back often enough to enter a typed number on time
+            None if self.motions.is_pending() => COUNT_TIMEOUT / 4,
+            None => super::TICK_RATE,
+        }
+    }
+
+    fn min_size(&self) -> (u16, u16) {
+        let (width, height) = min_grid_size(&self.game);
+        // grid beside the digit panel, with the status bar below
+        (width + DigitPanel::WIDTH, height + 1 + FOOTER_ALLOWANCE)
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let game = &self.game;
+        let appearance = context.config.appearance();
+        let (assist, armed) = (context.config.assist, self.armed(context));
+        let key = appearance.key_hint_style();
+        let keys = &context.config.keys;
+        let footer = self.footer(keys);
+        render_footer(area, buf, " Sudoku Game ", &footer, key);
+        let [grid_area, panel_area, candidate_area, history_area, status_area] =
+            game_areas(area, game, &footer, &context.config);
+        if context.config.screen_reader {
+            let announcement = self.announcement.as_deref().unwrap_or_default();
+            let lines = vec![
+                Line::from(describe_selection(game)),
+                Line::from(announcement),
+            ];
+            let text_area = Rect {
+                y: status_area.y + 1,
+                height: status_area.height.saturating_sub(1),
+                ..status_area
+            };
+            Paragraph::new(lines).render(text_area, buf);
+        }
+        let status_area = Rect {
+            height: 1,
+            ..status_area
+        };
+        match &self.goto {
+            Some(text) => Line::from(vec![
+                "Go to cell ".into(),
+                Span::styled(format!("{text}_"), key),
+            ])
+            .centered()
+            .render(status_area, buf),
+            None => StatusBar {
+                game,
+                notes_mode: self.notes_mode,
+            }
+            .render(status_area, buf),
+        }
+        if game.is_paused() {
+            let mut lines = vec![Line::from(vec![
+                " Resume ".into(),
+                Span::styled(keys.describe(Hint::Actions(&[Action::Pause])), key),
+                " Quit to menu ".into(),
+                Span::styled(keys.describe(Hint::Actions(&[Action::Quit])), key),
+                " ".into(),
+            ])];
+            if game.idle_time() > Duration::ZERO {
+                let idle = format_duration(game.idle_time());
+                lines.push(Line::from(format!("Idle time left off the clock: {idle}")));
+            }
+            Popup {
+                title: " Paused ",
+                lines,
+            }
+            .render(grid_area.union(panel_area), buf);
+            return;
+        }
+        match &self.hint {
+            Some(hint) => {
+                let highlights = hint.highlights(&appearance.theme);
+                game.render_with_assist(grid_area, buf, appearance, highlights, assist, armed);
+            }
+            None => {
+                game.render_with_assist(grid_area, buf, appearance, HashMap::new(), assist, armed)
+            }
+        }
+        DigitPanel {
+            game,
+            appearance,
+            armed,
+            assist,
+        }
+        .render(panel_area, buf);
+        if candidate_area.height == CandidatePanel::height(game) {
+            CandidatePanel {
+                game,
+                appearance,
+                assist,
+            }
+            .render(candidate_area, buf);
+        }
+        let history_area = match &self.race {
+            Some(race) => {
+                let [race_area, history_area] = split_race_area(history_area);
+                RacePanel { race, appearance }.render(race_area, buf);
+                history_area
+            }
+            None => history_area,
+        };
+        if history_area.height > 2 {
+            HistoryPanel {
+                game,
+                appearance,
+                scroll: self.history_scroll,
+            }
+            .render(history_area, buf);
+        }
+        if game.status() == GameStatus::Won {
+            win_popup(game, self.previous_best, keys, key).render(area, buf);
+        } else if game.status() == GameStatus::Lost {
+            Popup {
+                title: " Game Over ",
+                lines: vec![
+                    Line::from(format!("You made {} mistakes, the limit.", game.mistakes)),
+                    Line::default(),
+                    Line::from(vec![
+                        " Zen mode ".into(),
+                        Span::styled(keys.describe(Hint::Actions(&[Action::Zen])), key),
+                        " Retry ".into(),
+                        Span::styled(keys.describe(Hint::Actions(&[Action::Restart])), key),
+                        " Menu ".into(),
+                        Span::styled(keys.describe(Hint::Actions(&[Action::Quit])), key),
+                        " ".into(),
+                    ]),
+                ],
+            }
+            .render(area, buf);
+        } else if self.restarting {
+            Popup {
+                title: " Restart ",
+                lines: vec![
+                    Line::from("Restart this puzzle? Your entries will be lost."),
+                    Line::default(),
+                    Line::from(vec![
+                        " Yes ".into(),
+                        Span::styled(keys.describe(Hint::Actions(&[Action::Yes])), key),
+                        " No ".into(),
+                        Span::styled(keys.describe(Hint::Actions(&[Action::No])), key),
+                        " ".into(),
+                    ]),
+                ],
+            }
+            .render(area, buf);
+        } else if self.revealing {
+            Popup {
+                title: " Reveal Solution ",
+                lines: vec![
+                    Line::from("Give up and fill in the solution? It won't count as a win."),
+                    Line::default(),
+                    Line::from(vec![
+                        " Yes ".into(),
+                        Span::styled(keys.describe(Hint::Actions(&[Action::Yes])), key),
+                        " No ".into(),
+                        Span::styled(keys.describe(Hint::Actions(&[Action::No])), key),
+                        " ".into(),
+                    ]),
+                ],
+            }
+            .render(area, buf);
+        }
+    }
+}
+
+/// Areas of the grid, digit panel, candidate panel, history panel and status
+/// bar in the game screen
+fn game_areas(area: Rect, game: &Game, footer: &[(&str, String)], config: &Config) -> [Rect; 5] {
+    let (body, _) = footer_layout(area, footer);
+    // screen readers get the selection and the last action in words too
+    let status_height = if config.screen_reader { 3 } else { 1 };
+    let layout = Layout::new(
+        layout::Direction::Vertical,
+        [Constraint::Min(0), Constraint::Length(status_height)],
+    )
+    .split(body);
+    let columns = Layout::new(
+        layout::Direction::Horizontal,
+        [Constraint::Min(0), Constraint::Length(DigitPanel::WIDTH)],
+    )
+    .split(layout[0]);
+    let panels = Layout::new(
+        layout::Direction::Vertical,
+        [
+            Constraint::Length(game.size() as u16 + 2),
+            Constraint::Length(CandidatePanel::height(game)),
+            Constraint::Min(0),
+        ],
+    )
+    .split(columns[1]);
+    [columns[0], panels[0], panels[1], panels[2], layout[1]]
+}
+
+/// Areas of the race panel and the history panel below it
+fn split_race_area(area: Rect) -> [Rect; 2] {
+    let layout = Layout::new(
+        layout::Direction::Vertical,
+        [Constraint::Length(RacePanel::HEIGHT), Constraint::Min(0)],
+    )
+    .split(area);
+    [layout[0], layout[1]]
+}
+
+/// Scroll the history panel back (positive) or forward (negative), stopping
+/// at the oldest event
+fn scroll_history(scroll: &mut usize, game: &Game, delta: isize) {
+    let last = game.events().len().saturating_sub(1);
+    *scroll = scroll.saturating_add_signed(delta).min(last);
+}
+
+/// Pick a digit in number-first input, or put it down if already picked
+fn arm(armed: &mut Option<usize>, digit: usize) {
+    *armed = (*armed != Some(digit)).then_some(digit);
+}
+
+/// Update the best time, streak and statistics if the last input won the
+/// game, or count the failure if it reached the mistake limit. A win in zen
+/// mode goes unrecorded, as the failure already counted.
+fn record_outcome(game: &Game, context: &mut Context) {
+    let stats = &mut context.stats;
+    match game.status() {
+        GameStatus::Won if !game.is_zen() => {
+            context.streak += 1;
+            stats.record(
+                game,
+                game.difficulty,
+                game.elapsed(),
+                game.hints_used,
+                today(),
+            );
+        }
+        // giving up on a puzzle by revealing it counts the same as losing,
+        // unless it was already lost before going on in zen mode
+        GameStatus::Lost | GameStatus::Revealed if !game.is_zen() => {
+            context.streak = 0;
+            stats.record_failure(game.difficulty);
+        }
+        _ => return,
+    }
+    if stats.save(context.paths.stats()).is_err() {
+        context.toast("Could not save statistics");
+    }
+}
+
+/// The settings that decide how a value goes into the grid
+#[derive(Clone, Copy)]
+struct EntryRules {
+    bell: bool,
+    strict: bool,
+    auto_notes: bool,
+}
+
+impl EntryRules {
+    fn new(config: &Config) -> Self {
+        Self {
+            bell: config.bell,
+            strict: config.strict_entry,
+            auto_notes: config.auto_notes,
+        }
+    }
+}
+
+/// Enter a value at the selected cell, ringing the bell if it's rejected
+fn enter_value(game: &mut Game, value: usize, rules: EntryRules) {
+    let entered = match rules.strict {
+        true => game.add_strict_entry_at_selected(value),
+        false => game.add_entry_at_selected(value),
+    };
+    match entered {
+        Ok(entry) if rules.auto_notes && value != 0 => {
+            game.remove_peer_notes(entry.position, value)
+        }
+        Ok(_) => {}
+        Err(_) if rules.bell => ring_bell(),
+        Err(_) => {}
+    }
+}
+
+/// Enter a number typed on the keyboard, or pick it in number-first input
+fn type_value(
+    game: &mut Game,
+    armed: &mut Option<usize>,
+    value: usize,
+    notes: bool,
+    context: &Context,
+) {
+    match context.config.number_first {
+        true if value <= game.size() => arm(armed, value),
+        true => {}
+        false => input(game, value, notes, EntryRules::new(&context.config)),
+    }
+}
+
+/// Place a digit at the selected cell, or toggle it as a pencil mark there
+fn input(game: &mut Game, digit: usize, notes: bool, rules: EntryRules) {
+    if !notes {
+        enter_value(game, digit, rules);
+    } else if game.toggle_note_at_selected(digit).is_err() && rules.bell {
+        ring_bell();
+    }
+}
+
+/// Place the picked digit at the selected cell, or clear the cell if it
+/// already holds it
+fn place_armed(game: &mut Game, digit: usize, notes: bool, rules: EntryRules) {
+    match game.get_cell(game.selected) {
+        _ if notes => input(game, digit, notes, rules),
+        Ok(value) if value == digit => enter_value(game, 0, rules),
+        _ => enter_value(game, digit, rules),
+    }
+}
+
+fn ring_bell() {
+    let mut stdout = io::stdout();
+    // the bell is only a nicety, so a failed write is ignored
+    let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+}
+
+fn win_popup(
+    game: &Game,
+    previous_best: Option<Duration>,
+    keys: &Keymap,
+    key: Style,
+) -> Popup<'static> {
+    let elapsed = game.elapsed();
+    let best = match previous_best {
+        Some(best) if elapsed < best => format!("New best time! (was {})", format_duration(best)),
+        Some(best) => format!("Your best: {}", format_duration(best)),
+        None => "First solve of this puzzle".to_string(),
+    };
+    let mut lines = vec![
+        Line::from(format!("Time {}", format_duration(elapsed))),
+        Line::from(format!("Mistakes {}", game.mistakes)),
+        Line::from(format!("Hints {}", game.hints_used)),
+        Line::from(format!("Score {}", game.score())),
+        Line::from(best),
+        Line::default(),
+        Line::from("Box splits").bold(),
+    ];
+    // the time each box was finished, laid out like the boxes on the grid
+    let splits = game.box_splits();
+    lines.extend(splits.chunks(game.box_size()).map(|row| {
+        let times: Vec<String> = row
+            .iter()
+            .map(|split| split.map_or("  -  ".to_string(), format_duration))
+            .collect();
+        Line::from(times.join("  "))
+    }));
+    lines.extend([
+        Line::default(),
+        Line::from(vec![
+            " New puzzle ".into(),
+            Span::styled(keys.describe(Hint::Actions(&[Action::NextPuzzle])), key),
+            " Replay shuffled ".into(),
+            Span::styled(keys.describe(Hint::Actions(&[Action::Shuffle])), key),
+            " Save record ".into(),
+            Span::styled(keys.describe(Hint::Actions(&[Action::ExportRecord])), key),
+            " Menu ".into(),
+            Span::styled(keys.describe(Hint::Actions(&[Action::Quit])), key),
+            " ".into(),
+        ]),
+    ]);
+    Popup {
+        title: " Puzzle solved! ",
+        lines,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{difficulty::Difficulty, paths::Paths, progress::PackProgress, stats::Stats};
+
+    fn context(dir: &std::path::Path) -> Context {
+        Context {
+            config: Config::default(),
+            config_error: None,
+            area: Rect::default(),
+            toast: None,
+            streak: 3,
+            saved: None,
+            progress: PackProgress::default(),
+            stats: Stats::default(),
+            paths: Paths::portable(dir),
+            broadcast: None,
+        }
+    }
+
+    #[test]
+    fn revealing_counts_as_a_failure() {
+        let dir = std::env::temp_dir().join(format!("sudoku-outcome-{}", std::process::id()));
+        let mut context = context(&dir);
+        context.paths.create_dirs().unwrap();
+        let easy = Some(Difficulty::Easy);
+        let mut game = PUZZLES[0].game().unwrap();
+        game.difficulty = easy;
+        let solution = solve_game(&game).unwrap();
+        game.reveal(&solution).unwrap();
+        record_outcome(&game, &mut context);
+        assert_eq!(context.streak, 0);
+        assert_eq!(context.stats.for_difficulty(easy).failed, 1);
+        assert_eq!(context.stats.for_difficulty(easy).solved, 0);
+        assert_eq!(Stats::load(context.paths.stats()).unwrap(), context.stats);
+
+        // a lost game going on in zen mode was counted when it was lost
+        let mut game = PUZZLES[0].game().unwrap();
+        game.resume_in_zen();
+        game.reveal(&solution).unwrap();
+        record_outcome(&game, &mut context);
+        assert_eq!(context.stats.total().failed, 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,56 @@
+use super::{
+    browser, editor, game, menu, new_game, samurai, settings, solver, stats, tutorial, variant,
+    Context, Screen, Transition,
+};
+use crate::popup::Popup;
+use crossterm::event::KeyEvent;
+use ratatui::{prelude::*, widgets::Clear};
+
+/// Every screen's keybindings, drawn over whatever screen is open
+pub struct HelpScreen;
+
+impl Screen for HelpScreen {
+    fn handle_key(&mut self, _key_event: KeyEvent, _context: &mut Context) -> Transition {
+        Transition::Pop(1)
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let key = context.config.appearance().key_hint_style();
+        let sections = [
+            ("Game", game::KEYS),
+            ("Solver", solver::KEYS),
+            ("Settings", settings::KEYS),
+            ("Open file", browser::KEYS),
+            ("Variant", variant::KEYS),
+            ("New game", new_game::KEYS),
+            ("Samurai", samurai::KEYS),
+            ("Puzzle editor", editor::KEYS),
+            ("Cage editor", editor::CAGE_KEYS),
+            ("Statistics", stats::KEYS),
+            ("Tutorial", tutorial::KEYS),
+            ("Menu", menu::KEYS),
+        ];
+        let mut lines = vec![];
+        for (name, keys) in sections {
+            lines.push(Line::from(name.bold().underlined()));
+            for (label, binding) in context.config.keys.hints(keys) {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{binding:>16}"), key),
+                    format!("  {label:<16}").into(),
+                ]));
+            }
+            lines.push(Line::default());
+        }
+        lines.push(Line::from("Press any key to close"));
+        Clear.render(area, buf);
+        Popup {
+            title: " Help ",
+            lines,
+        }
+        .render(area, buf);
+    }
+}
@@ -0,0 +1,212 @@
+use super::{
+    browser::BrowserScreen, editor::EditorScreen, game::GameScreen, render_footer,
+    settings::SettingsScreen, solver::SolverScreen, stats::StatsScreen, tutorial::TutorialScreen,
+    variant::VariantScreen, Context, Screen, Transition,
+};
+#[cfg(feature = "online")]
+use super::{online::FetchingScreen, pack::PackScreen};
+#[cfg(feature = "online")]
+use crate::online;
+use crate::{
+    clipboard,
+    format::{lenient, FormatError},
+    game::Game,
+    keymap::{Action, Hint},
+    library::PUZZLES,
+    save::SavedGame,
+    timer::format_duration,
+    tui,
+};
+use crossterm::event::KeyEvent;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, List, ListState},
+};
+
+pub const KEYS: &[(&str, Hint)] = &[
+    ("Move selection", Hint::Actions(&[Action::Down, Action::Up])),
+    ("Play", Hint::Actions(&[Action::Confirm])),
+    ("New game", Hint::Actions(&[Action::NewGame])),
+    ("Open file", Hint::Actions(&[Action::OpenFile])),
+    ("Paste puzzle", Hint::Actions(&[Action::Paste])),
+    ("Puzzle editor", Hint::Actions(&[Action::Editor])),
+    ("Solver", Hint::Actions(&[Action::Solver])),
+    ("Settings", Hint::Actions(&[Action::Settings])),
+    ("Statistics", Hint::Actions(&[Action::Statistics])),
+    ("Tutorial", Hint::Actions(&[Action::Tutorial])),
+    ("Help", Hint::Actions(&[Action::Help])),
+    ("Quit", Hint::Actions(&[Action::Quit])),
+];
+
+const ACTIONS: &[Action] = &[
+    Action::Quit,
+    Action::Up,
+    Action::Down,
+    Action::Confirm,
+    Action::OpenFile,
+    Action::Paste,
+    Action::Editor,
+    Action::NewGame,
+    Action::Solver,
+    Action::Settings,
+    Action::Statistics,
+    Action::Tutorial,
+];
+
+/// Entries listed after the library puzzles: the online pack, when it's
+/// built in
+const EXTRA_ENTRIES: usize = cfg!(feature = "online") as usize;
+
+pub struct MenuScreen {
+    /// The selected library puzzle
+    selected: usize,
+    /// "Resume last game" is selected instead, when there is a saved game
+    resume_selected: bool,
+}
+
+impl Default for MenuScreen {
+    fn default() -> Self {
+        Self {
+            selected: 0,
+            resume_selected: true,
+        }
+    }
+}
+
+impl MenuScreen {
+    fn saved_selected<'a>(&self, context: &'a Context) -> Option<&'a SavedGame> {
+        context.saved.as_ref().filter(|_| self.resume_selected)
+    }
+}
+
+impl Screen for MenuScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        match context.config.keys.action(key_event, ACTIONS) {
+            Some(Action::Quit) => Transition::Quit,
+            Some(Action::Up) => {
+                match self.selected {
+                    _ if self.saved_selected(context).is_some() => {}
+                    0 => self.resume_selected = context.saved.is_some(),
+                    _ => self.selected -= 1,
+                }
+                Transition::None
+            }
+            Some(Action::Down) => {
+                if self.saved_selected(context).is_none() {
+                    self.selected = (self.selected + 1).min(PUZZLES.len() + EXTRA_ENTRIES - 1);
+                }
+                self.resume_selected = false;
+                Transition::None
+            }
+            Some(Action::Confirm) => match self
+                .saved_selected(context)
+                .map(|saved| GameScreen::resume(saved, context))
+            {
+                Some(Ok(screen)) => Transition::Push(Box::new(screen)),
+                Some(Err(_)) => {
+                    context.toast("Could not resume the saved game");
+                    Transition::None
+                }
+                #[cfg(feature = "online")]
+                None if self.selected == PUZZLES.len() => online_pack(context),
+                None => {
+                    Transition::Push(Box::new(GameScreen::from_library(self.selected, context)))
+                }
+            },
+            Some(Action::OpenFile) => Transition::Push(Box::new(BrowserScreen::new(context))),
+            Some(Action::Paste) => match pasted_game() {
+                Ok(game) => GameScreen::start_checked(game, context, 0),
+                Err(message) => {
+                    context.toast(message);
+                    Transition::None
+                }
+            },
+            Some(Action::Editor) => Transition::Push(Box::new(EditorScreen::default())),
+            Some(Action::NewGame) => Transition::Push(Box::new(VariantScreen::default())),
+            Some(Action::Solver) => {
+                let game = match self.saved_selected(context).map(SavedGame::game) {
+                    Some(Ok(game)) => game.snapshot(),
+                    _ => match PUZZLES.get(self.selected) {
+                        Some(puzzle) => puzzle.game().unwrap(),
+                        None => return Transition::None,
+                    },
+                };
+                Transition::Push(Box::new(SolverScreen::new(game)))
+            }
+            Some(Action::Settings) => Transition::Push(Box::new(SettingsScreen::new(context))),
+            Some(Action::Statistics) => Transition::Push(Box::new(StatsScreen)),
+            Some(Action::Tutorial) => Transition::Push(Box::new(TutorialScreen::default())),
+            _ => Transition::None,
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let appearance = context.config.appearance();
+        let key = appearance.key_hint_style();
+        let hints = context.config.keys.hints(KEYS);
+        let body = render_footer(area, buf, " Sudoku Main Menu ", &hints, key);
+        let resume = context.saved.as_ref().map(|saved| {
+            let name = saved
+                .metadata
+                .puzzle
+                .map_or("last game", |puzzle| PUZZLES[puzzle].name);
+            format!("  Resume {name}")
+        });
+        let puzzles = PUZZLES.iter().map(|puzzle| {
+            let best = puzzle
+                .game()
+                .ok()
+                .and_then(|game| context.stats.puzzle_best(&game));
+            let (solved, best) = match best {
+                Some(best) => ("✓", format!("your best: {}", format_duration(best))),
+                None => (" ", String::new()),
+            };
+            format!(
+                "{solved} {:<16} {:<7} {best}",
+                puzzle.name, puzzle.difficulty
+            )
+        });
+        let online = cfg!(feature = "online").then(|| "  Online pack".to_string());
+        let items: Vec<String> = resume.into_iter().chain(puzzles).chain(online).collect();
+        let selected = match (&context.saved, self.resume_selected) {
+            (Some(_), true) => 0,
+            (Some(_), false) => self.selected + 1,
+            (None, _) => self.selected,
+        };
+        let items_len = items.len() as u16;
+        let list = List::new(items)
+            .block(Block::default().title(" Puzzles ").borders(Borders::ALL))
+            .highlight_style(appearance.selected_style())
+            .highlight_symbol("> ");
+        let mut state = ListState::default().with_selected(Some(selected));
+        StatefulWidget::render(
+            list,
+            tui::centered_rect(body, 50, items_len + 2),
+            buf,
+            &mut state,
+        );
+    }
+}
+
+/// The puzzles of the online source, or those fetched before if there's no
+/// source set
+#[cfg(feature = "online")]
+fn online_pack(context: &mut Context) -> Transition {
+    let cache = context.paths.packs().join(online::CACHE_FILE);
+    match (&context.config.online_source, online::cached(&cache)) {
+        (Some(url), _) => Transition::Push(Box::new(FetchingScreen::start(url.clone(), cache))),
+        (None, Ok(collection)) => Transition::Push(Box::new(PackScreen::new(collection, cache))),
+        (None, Err(_)) => {
+            context.toast("Set online_source in the settings file to fetch puzzles");
+            Transition::None
+        }
+    }
+}
+
+/// A game on the puzzle in the clipboard
+pub fn pasted_game() -> Result<Game, String> {
+    let text = clipboard::read_text().map_err(|error| format!("Could not paste: {error}"))?;
+    lenient::parse(&text)
+        .and_then(|cells| Game::new(cells).map_err(FormatError::InvalidGrid))
+        .map_err(|error| format!("Could not paste: {error}"))
+}
@@ -0,0 +1,182 @@
+use crate::{
+    broadcast::Broadcast, config::Config, game::Game, grid::CellScale, paths::Paths,
+    progress::PackProgress, save::SavedGame, stats::Stats, toast::Toast,
+};
+use crossterm::event::{KeyEvent, MouseEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{block::Title, Block, Borders, Paragraph},
+};
+use std::time::Duration;
+
+pub mod browser;
+pub mod confirm;
+pub mod editor;
+pub mod game;
+pub mod help;
+pub mod menu;
+pub mod new_game;
+#[cfg(feature = "online")]
+pub mod online;
+pub mod pack;
+pub mod samurai;
+pub mod settings;
+pub mod solver;
+pub mod splash;
+pub mod stats;
+pub mod tutorial;
+pub mod variant;
+pub mod watch;
+
+/// Smallest terminal for screens without a grid
+pub const MIN_WIDTH: u16 = 40;
+pub const MIN_HEIGHT: u16 = 12;
+
+/// Cells each way that must fit before a scrolling grid is drawn
+const MIN_VISIBLE_CELLS: u16 = 9;
+/// Rows kept free below the grid for the title and key instructions
+const FOOTER_ALLOWANCE: u16 = 6;
+
+/// Default redraw interval, often enough to keep the game clock current
+pub const TICK_RATE: Duration = Duration::from_millis(250);
+
+/// State shared by every screen on the stack
+pub struct Context {
+    pub config: Config,
+    /// Why the config file couldn't be read, in which case settings changed
+    /// this session aren't written over it
+    pub config_error: Option<String>,
+    /// Terminal area of the last draw, used to hit-test mouse events
+    pub area: Rect,
+    pub toast: Option<Toast>,
+    /// Puzzles won in a row without leaving one unfinished
+    pub streak: usize,
+    /// The last game left unfinished, as written to the save file
+    pub saved: Option<SavedGame>,
+    /// Puzzles solved from each collection file
+    pub progress: PackProgress,
+    pub stats: Stats,
+    pub paths: Paths,
+    /// Where games are sent to spectators, if anyone may watch
+    pub broadcast: Option<Broadcast>,
+}
+
+impl Context {
+    /// Briefly show a message over whichever screen is open
+    pub fn toast(&mut self, message: impl Into<String>) {
+        self.toast = Some(Toast::new(message));
+    }
+}
+
+/// What the app should do with the screen stack after handling an event
+pub enum Transition {
+    None,
+    Push(Box<dyn Screen>),
+    /// Pop this many screens off the top of the stack
+    Pop(usize),
+    /// Swap the top screen for another
+    Replace(Box<dyn Screen>),
+    /// Pop this many screens off the top of the stack, then push another
+    Swap(usize, Box<dyn Screen>),
+    Quit,
+}
+
+pub trait Screen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition;
+
+    fn handle_mouse(&mut self, _mouse_event: MouseEvent, _context: &mut Context) -> Transition {
+        Transition::None
+    }
+
+    fn on_tick(&mut self, _context: &mut Context) -> Transition {
+        Transition::None
+    }
+
+    /// Called as the screen is popped or replaced
+    fn on_exit(&mut self, _context: &mut Context) {}
+
+    /// How often the screen is updated and redrawn without a keypress
+    fn tick_rate(&self) -> Duration {
+        TICK_RATE
+    }
+
+    /// Smallest terminal the screen can be drawn in without clipping
+    fn min_size(&self) -> (u16, u16) {
+        (MIN_WIDTH, MIN_HEIGHT)
+    }
+
+    /// Overlays are drawn on top of the screen below instead of replacing it
+    fn is_overlay(&self) -> bool {
+        false
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context);
+}
+
+/// Larger grids scroll to follow the selection, so only part of them has to fit
+fn min_grid_size(game: &Game) -> (u16, u16) {
+    let (width, height) = game.render_size(CellScale::Compact);
+    let scale = CellScale::Compact;
+    (
+        width.min(MIN_VISIBLE_CELLS * scale.width() as u16),
+        height.min(MIN_VISIBLE_CELLS * scale.height() as u16),
+    )
+}
+
+fn instruction_lines<'a>(
+    instructions: &'a [(&'a str, String)],
+    width: u16,
+    key: Style,
+) -> Vec<Line<'a>> {
+    let mut lines = vec![];
+    let mut spans: Vec<Span> = vec![];
+    let mut line_width = 0;
+    for (label, binding) in instructions {
+        let item_width = label.len() + binding.len() + 3;
+        if !spans.is_empty() && line_width + item_width > width as usize {
+            lines.push(Line::from(std::mem::take(&mut spans)));
+            line_width = 0;
+        }
+        spans.push(format!(" {label} ").into());
+        spans.push(Span::styled(binding.as_str(), key));
+        spans.push(" ".into());
+        line_width += item_width;
+    }
+    if !spans.is_empty() {
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+/// Split `area` into the screen body and the footer listing `instructions`
+fn footer_layout(area: Rect, instructions: &[(&str, String)]) -> (Rect, Rect) {
+    let lines = instruction_lines(instructions, area.width.saturating_sub(2), Style::new());
+    let height = (lines.len() as u16 + 2).min(area.height / 2);
+    let layout = Layout::new(
+        layout::Direction::Vertical,
+        [Constraint::Min(0), Constraint::Length(height)],
+    )
+    .split(area);
+    (layout[0], layout[1])
+}
+
+/// Render the screen title and key instructions at the bottom of `area`,
+/// returning the area left for the screen body
+fn render_footer(
+    area: Rect,
+    buf: &mut Buffer,
+    title: &str,
+    instructions: &[(&str, String)],
+    key: Style,
+) -> Rect {
+    let lines = instruction_lines(instructions, area.width.saturating_sub(2), key);
+    let (body, footer) = footer_layout(area, instructions);
+    let block = Block::default()
+        .title(Title::from(title.bold()).alignment(Alignment::Center))
+        .borders(Borders::ALL);
+    Paragraph::new(lines)
+        .centered()
+        .block(block)
+        .render(footer, buf);
+    body
+}
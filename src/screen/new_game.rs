@@ -0,0 +1,200 @@
+use super::{game::GameScreen, render_footer, samurai::SamuraiScreen, Context, Screen, Transition};
+use crate::{
+    difficulty::Difficulty,
+    game::Game,
+    generator::{self, Generated},
+    keymap::{Action, Hint},
+    popup::Popup,
+    rng::Rng,
+    samurai::{self, Samurai},
+    tui,
+    variant::Variant,
+};
+use crossterm::event::KeyEvent;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, List, ListState},
+};
+use std::{
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::Duration,
+};
+
+pub const KEYS: &[(&str, Hint)] = &[
+    (
+        "Change difficulty",
+        Hint::Actions(&[Action::Left, Action::Right]),
+    ),
+    ("Generate", Hint::Actions(&[Action::Confirm])),
+    ("Help", Hint::Actions(&[Action::Help])),
+    ("Back", Hint::Actions(&[Action::Quit])),
+];
+
+const ACTIONS: &[Action] = &[Action::Quit, Action::Left, Action::Right, Action::Confirm];
+
+pub const CANCEL_KEYS: &[(&str, Hint)] = &[("Cancel", Hint::Actions(&[Action::Quit]))];
+
+pub const SPINNER: [&str; 4] = ["|", "/", "-", "\\"];
+pub const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Pick the difficulty of a puzzle in the variant chosen before
+pub struct NewGameScreen {
+    difficulty: usize,
+    /// Index into `Variant::ALL`, or past it for samurai
+    variant: usize,
+}
+
+impl NewGameScreen {
+    pub fn new(context: &Context, variant: usize) -> Self {
+        let difficulty = Difficulty::ALL
+            .iter()
+            .position(|&difficulty| difficulty == context.config.default_difficulty)
+            .unwrap_or(0);
+        Self {
+            difficulty,
+            variant,
+        }
+    }
+}
+
+impl Screen for NewGameScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        let count = Difficulty::ALL.len();
+        match context.config.keys.action(key_event, ACTIONS) {
+            Some(Action::Quit) => return Transition::Pop(1),
+            Some(Action::Left) => self.difficulty = (self.difficulty + count - 1) % count,
+            Some(Action::Right) => self.difficulty = (self.difficulty + 1) % count,
+            Some(Action::Confirm) => {
+                let difficulty = Difficulty::ALL[self.difficulty];
+                let screen = match Variant::ALL.get(self.variant) {
+                    Some(&variant) => GeneratingScreen::start(difficulty, variant),
+                    None => GeneratingScreen::start_samurai(difficulty),
+                };
+                return Transition::Replace(Box::new(screen));
+            }
+            _ => {}
+        }
+        Transition::None
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let appearance = context.config.appearance();
+        let key = appearance.key_hint_style();
+        let hints = context.config.keys.hints(KEYS);
+        let body = render_footer(area, buf, " New Game ", &hints, key);
+        let variant = Variant::ALL
+            .get(self.variant)
+            .map_or("Samurai".to_string(), Variant::to_string);
+        let items = [format!(
+            "Difficulty  < {} >",
+            Difficulty::ALL[self.difficulty]
+        )];
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(format!(" {variant} "))
+                    .borders(Borders::ALL),
+            )
+            .highlight_style(appearance.selected_style())
+            .highlight_symbol("> ");
+        let mut state = ListState::default().with_selected(Some(0));
+        StatefulWidget::render(list, tui::centered_rect(body, 36, 3), buf, &mut state);
+    }
+}
+
+/// A puzzle finished in the background
+enum Puzzle {
+    /// A puzzle from the generator, with the variant it was made for
+    Classic(Variant, Generated),
+    /// The givens of a samurai board
+    Samurai(Vec<usize>),
+}
+
+/// Waits on a puzzle generated in the background, then starts the game
+pub struct GeneratingScreen {
+    difficulty: Difficulty,
+    receiver: Receiver<Puzzle>,
+    frame: usize,
+}
+
+impl GeneratingScreen {
+    pub fn start(difficulty: Difficulty, variant: Variant) -> Self {
+        Self::spawn(difficulty, move |rng| {
+            Puzzle::Classic(variant, generator::generate_best(variant, difficulty, rng))
+        })
+    }
+
+    pub fn start_samurai(difficulty: Difficulty) -> Self {
+        Self::spawn(difficulty, move |rng| {
+            Puzzle::Samurai(samurai::generate(difficulty, rng).0)
+        })
+    }
+
+    fn spawn(
+        difficulty: Difficulty,
+        generate: impl FnOnce(&mut Rng) -> Puzzle + Send + 'static,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let puzzle = generate(&mut Rng::from_time());
+            // the receiver is gone if the player cancelled, which is fine
+            let _ = sender.send(puzzle);
+        });
+        Self {
+            difficulty,
+            receiver,
+            frame: 0,
+        }
+    }
+}
+
+impl Screen for GeneratingScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        match context.config.keys.action(key_event, &[Action::Quit]) {
+            Some(Action::Quit) => Transition::Pop(1),
+            _ => Transition::None,
+        }
+    }
+
+    fn on_tick(&mut self, context: &mut Context) -> Transition {
+        match self.receiver.try_recv() {
+            Ok(Puzzle::Classic(variant, generated)) => match Game::new(generated.givens) {
+                Ok(game) => {
+                    let mut game = game.with_regions(variant.regions());
+                    game.difficulty = Some(self.difficulty);
+                    Transition::Replace(Box::new(GameScreen::new(game, context)))
+                }
+                Err(_) => Transition::Pop(1),
+            },
+            Ok(Puzzle::Samurai(givens)) => match Samurai::new(givens) {
+                Ok(samurai) => Transition::Replace(Box::new(SamuraiScreen::new(samurai))),
+                Err(_) => Transition::Pop(1),
+            },
+            Err(TryRecvError::Empty) => {
+                self.frame += 1;
+                Transition::None
+            }
+            Err(TryRecvError::Disconnected) => Transition::Pop(1),
+        }
+    }
+
+    fn tick_rate(&self) -> Duration {
+        SPINNER_INTERVAL
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let key = context.config.appearance().key_hint_style();
+        let hints = context.config.keys.hints(CANCEL_KEYS);
+        let body = render_footer(area, buf, " New Game ", &hints, key);
+        Popup {
+            title: " Generating ",
+            lines: vec![Line::from(format!(
+                "{} Generating {} puzzle",
+                SPINNER[self.frame % SPINNER.len()],
+                self.difficulty
+            ))],
+        }
+        .render(body, buf);
+    }
+}
@@ -0,0 +1,106 @@
+use super::{
+    new_game::{CANCEL_KEYS, SPINNER, SPINNER_INTERVAL},
+    pack::PackScreen,
+    render_footer, Context, Screen, Transition,
+};
+use crate::{
+    format::Collection,
+    keymap::Action,
+    online::{self, OnlineError},
+    popup::Popup,
+};
+use crossterm::event::KeyEvent;
+use ratatui::prelude::*;
+use std::{
+    fmt::Display,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::Duration,
+};
+
+/// Waits on puzzles fetched from the online source in the background, then
+/// lists them as a pack. Without a connection, the puzzles fetched before
+/// are listed instead.
+pub struct FetchingScreen {
+    receiver: Receiver<Result<Collection, OnlineError>>,
+    /// Where fetched puzzles are kept
+    cache: PathBuf,
+    frame: usize,
+}
+
+impl FetchingScreen {
+    pub fn start(url: String, cache: PathBuf) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let file = cache.clone();
+        thread::spawn(move || {
+            // the receiver is gone if the player cancelled, which is fine
+            let _ = sender.send(online::refresh(&url, &file));
+        });
+        Self {
+            receiver,
+            cache,
+            frame: 0,
+        }
+    }
+
+    /// Fall back on the puzzles fetched before, or go back with the error if
+    /// there are none
+    fn cached(&self, context: &mut Context, error: impl Display) -> Transition {
+        match online::cached(&self.cache) {
+            Ok(collection) => {
+                context.toast(format!(
+                    "Online pack: {error}, showing those fetched before"
+                ));
+                let screen = PackScreen::new(collection, self.cache.clone());
+                Transition::Replace(Box::new(screen))
+            }
+            Err(_) => {
+                context.toast(format!("Online pack: {error}"));
+                Transition::Pop(1)
+            }
+        }
+    }
+}
+
+impl Screen for FetchingScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        match context.config.keys.action(key_event, &[Action::Quit]) {
+            Some(Action::Quit) => Transition::Pop(1),
+            _ => Transition::None,
+        }
+    }
+
+    fn on_tick(&mut self, context: &mut Context) -> Transition {
+        match self.receiver.try_recv() {
+            Ok(Ok(collection)) => {
+                let screen = PackScreen::new(collection, self.cache.clone());
+                Transition::Replace(Box::new(screen))
+            }
+            Ok(Err(error)) => self.cached(context, error),
+            Err(TryRecvError::Empty) => {
+                self.frame += 1;
+                Transition::None
+            }
+            Err(TryRecvError::Disconnected) => self.cached(context, "could not fetch puzzles"),
+        }
+    }
+
+    fn tick_rate(&self) -> Duration {
+        SPINNER_INTERVAL
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let key = context.config.appearance().key_hint_style();
+        let hints = context.config.keys.hints(CANCEL_KEYS);
+        let body = render_footer(area, buf, " Online Pack ", &hints, key);
+        Popup {
+            title: " Fetching ",
+            lines: vec![Line::from(format!(
+                "{} Fetching puzzles",
+                SPINNER[self.frame % SPINNER.len()],
+            ))],
+        }
+        .render(body, buf);
+    }
+}
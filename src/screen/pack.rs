@@ -0,0 +1,105 @@
+use super::{game::GameScreen, render_footer, Context, Screen, Transition};
+use crate::{
+    format::Collection,
+    game::Game,
+    keymap::{Action, Hint},
+    timer::format_duration,
+};
+use crossterm::event::KeyEvent;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, List, ListState},
+};
+use std::path::PathBuf;
+
+pub const KEYS: &[(&str, Hint)] = &[
+    ("Move selection", Hint::Actions(&[Action::Down, Action::Up])),
+    ("Play", Hint::Actions(&[Action::Confirm])),
+    ("Help", Hint::Actions(&[Action::Help])),
+    ("Back", Hint::Actions(&[Action::Quit])),
+];
+
+const ACTIONS: &[Action] = &[Action::Quit, Action::Up, Action::Down, Action::Confirm];
+
+/// The puzzles of a collection file, to pick one to play
+pub struct PackScreen {
+    collection: Collection,
+    /// Where the collection was read from, to keep track of which puzzles
+    /// are solved
+    file: PathBuf,
+    selected: usize,
+}
+
+impl PackScreen {
+    pub fn new(collection: Collection, file: PathBuf) -> Self {
+        Self {
+            collection,
+            file,
+            selected: 0,
+        }
+    }
+}
+
+impl Screen for PackScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        match context.config.keys.action(key_event, ACTIONS) {
+            Some(Action::Quit) => return Transition::Pop(1),
+            Some(Action::Up) => self.selected = self.selected.saturating_sub(1),
+            Some(Action::Down) => {
+                self.selected = (self.selected + 1).min(self.collection.puzzles.len() - 1)
+            }
+            Some(Action::Confirm) => {
+                match Game::new(self.collection.puzzles[self.selected].clone()) {
+                    Ok(game) => {
+                        let file = self.file.clone();
+                        let screen = GameScreen::from_pack(game, file, self.selected, context);
+                        return Transition::Push(Box::new(screen));
+                    }
+                    Err(_) => context.toast("Invalid puzzle"),
+                }
+            }
+            _ => {}
+        }
+        Transition::None
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let appearance = context.config.appearance();
+        let hints = context.config.keys.hints(KEYS);
+        let body = render_footer(
+            area,
+            buf,
+            " Puzzle Pack ",
+            &hints,
+            appearance.key_hint_style(),
+        );
+        let items = self
+            .collection
+            .puzzles
+            .iter()
+            .enumerate()
+            .map(|(i, cells)| {
+                let game = Game::new(cells.clone()).ok();
+                let best = game.and_then(|game| context.stats.puzzle_best(&game));
+                let solved = match context.progress.is_solved(&self.file, i) || best.is_some() {
+                    true => "✓",
+                    false => " ",
+                };
+                let best = best.map_or(String::new(), |best| {
+                    format!("your best: {}", format_duration(best))
+                });
+                format!("{solved} Puzzle {:<6} {best}", i + 1)
+            });
+        let name = self.collection.name.as_deref().unwrap_or("Puzzles");
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(format!(" {name} "))
+                    .borders(Borders::ALL),
+            )
+            .highlight_style(appearance.selected_style())
+            .highlight_symbol("> ");
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        StatefulWidget::render(list, body, buf, &mut state);
+    }
+}
@@ -0,0 +1,212 @@
+use super::{render_footer, Context, Screen, Transition, FOOTER_ALLOWANCE};
+use crate::{
+    grid::{value_symbol, GridPosition},
+    keymap::{Action, Hint},
+    popup::Popup,
+    samurai::{self, Samurai, SIDE},
+    timer::{format_duration, Timer},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use std::collections::BTreeSet;
+
+pub const KEYS: &[(&str, Hint)] = &[
+    (
+        "Move selection",
+        Hint::Actions(&[Action::Left, Action::Down, Action::Up, Action::Right]),
+    ),
+    (
+        "Jump a box",
+        Hint::Actions(&[
+            Action::JumpLeft,
+            Action::JumpDown,
+            Action::JumpUp,
+            Action::JumpRight,
+        ]),
+    ),
+    ("Insert number", Hint::Fixed("<1-9>")),
+    ("Clear cell", Hint::Actions(&[Action::Clear])),
+    ("Undo", Hint::Actions(&[Action::Undo])),
+    ("Quit to menu", Hint::Actions(&[Action::Quit])),
+];
+
+const ACTIONS: &[Action] = &[
+    Action::Quit,
+    Action::Left,
+    Action::Right,
+    Action::Up,
+    Action::Down,
+    Action::JumpLeft,
+    Action::JumpRight,
+    Action::JumpUp,
+    Action::JumpDown,
+    Action::Clear,
+    Action::Undo,
+];
+
+/// Columns each cell takes, a digit and a space
+const CELL_WIDTH: u16 = 2;
+/// Boxes along each side of the board
+const BOXES: u16 = SIDE as u16 / 3;
+/// Board size on screen, with a blank column and row between boxes
+const BOARD_WIDTH: u16 = (SIDE as u16 - 1) * CELL_WIDTH + 1 + BOXES - 1;
+const BOARD_HEIGHT: u16 = SIDE as u16 + BOXES - 1;
+
+/// Play a samurai board, moving freely across the five grids
+pub struct SamuraiScreen {
+    samurai: Samurai,
+    selected: GridPosition,
+    /// Cells set, with the value each held before, most recent last
+    undo: Vec<(GridPosition, usize)>,
+    conflicts: BTreeSet<GridPosition>,
+    timer: Timer,
+}
+
+impl SamuraiScreen {
+    pub fn new(samurai: Samurai) -> Self {
+        Self {
+            conflicts: samurai.conflicts(),
+            samurai,
+            selected: (0, 0),
+            undo: vec![],
+            timer: Timer::start(),
+        }
+    }
+
+    fn set(&mut self, value: usize) {
+        if let Ok(previous) = self.samurai.set(self.selected, value) {
+            if previous != value {
+                self.undo.push((self.selected, previous));
+            }
+            self.changed();
+        }
+    }
+
+    fn changed(&mut self) {
+        self.conflicts = self.samurai.conflicts();
+        if self.samurai.is_solved() {
+            self.timer.pause();
+        }
+    }
+
+    /// Move `distance` cells across, or further to land back on the board
+    /// after crossing the gap between two grids
+    fn shift(&mut self, (dx, dy): (isize, isize), distance: isize) {
+        let (mut x, mut y) = (self.selected.0 as isize, self.selected.1 as isize);
+        let mut moved = 0;
+        loop {
+            x += dx;
+            y += dy;
+            moved += 1;
+            if !(0..SIDE as isize).contains(&x) || !(0..SIDE as isize).contains(&y) {
+                return;
+            }
+            let position = (x as usize, y as usize);
+            if moved >= distance && samurai::contains(position) {
+                self.selected = position;
+                return;
+            }
+        }
+    }
+}
+
+impl Screen for SamuraiScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        let solved = self.samurai.is_solved();
+        if let KeyCode::Char(digit @ '1'..='9') = key_event.code {
+            if !solved {
+                self.set(digit as usize - '0' as usize);
+            }
+            return Transition::None;
+        }
+        match context.config.keys.action(key_event, ACTIONS) {
+            Some(Action::Quit) => return Transition::Pop(1),
+            Some(Action::Left) => self.shift((-1, 0), 1),
+            Some(Action::Right) => self.shift((1, 0), 1),
+            Some(Action::Up) => self.shift((0, -1), 1),
+            Some(Action::Down) => self.shift((0, 1), 1),
+            Some(Action::JumpLeft) => self.shift((-1, 0), 3),
+            Some(Action::JumpRight) => self.shift((1, 0), 3),
+            Some(Action::JumpUp) => self.shift((0, -1), 3),
+            Some(Action::JumpDown) => self.shift((0, 1), 3),
+            Some(Action::Clear) if !solved => self.set(0),
+            Some(Action::Undo) if !solved => match self.undo.pop() {
+                Some((position, value)) => {
+                    self.samurai.set(position, value).unwrap();
+                    self.selected = position;
+                    self.changed();
+                }
+                None => context.toast("Nothing to undo"),
+            },
+            _ => {}
+        }
+        Transition::None
+    }
+
+    fn min_size(&self) -> (u16, u16) {
+        (BOARD_WIDTH + 2, BOARD_HEIGHT + 2 + FOOTER_ALLOWANCE)
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let appearance = context.config.appearance();
+        let key = appearance.key_hint_style();
+        let hints = context.config.keys.hints(KEYS);
+        let title = format!(" Samurai Sudoku {} ", format_duration(self.timer.elapsed()));
+        let body = render_footer(area, buf, &title, &hints, key);
+        let left = body.x + body.width.saturating_sub(BOARD_WIDTH) / 2;
+        let top = body.y + body.height.saturating_sub(BOARD_HEIGHT) / 2;
+        let theme = appearance.theme;
+        for y in 0..SIDE {
+            for x in 0..SIDE {
+                let position = (x, y);
+                let Some(value) = self.samurai.get(position) else {
+                    continue;
+                };
+                let column = left + (x as u16) * CELL_WIDTH + x as u16 / 3;
+                let row = top + y as u16 + y as u16 / 3;
+                if column >= body.right() || row >= body.bottom() {
+                    continue;
+                }
+                let mut style = match self.samurai.is_given(position) {
+                    true if appearance.colorblind => Style::new().bold(),
+                    true => Style::new().fg(theme.given).bold(),
+                    false if appearance.colorblind => Style::new().italic(),
+                    false => Style::new().fg(theme.entry),
+                };
+                if self.conflicts.contains(&position) {
+                    style = match appearance.colorblind {
+                        true => style.underlined().crossed_out(),
+                        false => style.bg(theme.conflict).fg(theme.conflict_text),
+                    };
+                }
+                if position == self.selected {
+                    style = style.patch(appearance.selected_style());
+                }
+                let symbol = match value {
+                    0 if appearance.ascii => ".".to_string(),
+                    0 => "·".to_string(),
+                    value => value_symbol(value).to_string(),
+                };
+                buf.set_string(column, row, symbol, style);
+            }
+        }
+        if self.samurai.is_solved() {
+            Popup {
+                title: " Puzzle solved! ",
+                lines: vec![
+                    Line::from(format!("Time {}", format_duration(self.timer.elapsed()))),
+                    Line::default(),
+                    Line::from(vec![
+                        " Menu ".into(),
+                        Span::styled(
+                            context.config.keys.describe(Hint::Actions(&[Action::Quit])),
+                            key,
+                        ),
+                        " ".into(),
+                    ]),
+                ],
+            }
+            .render(body, buf);
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use super::{render_footer, Context, Screen, Transition};
+use crate::{
+    config::{Assist, Autosave, Charset, IdlePause, MistakeLimit},
+    difficulty::Difficulty,
+    keymap::{Action, Hint, Preset},
+    theme::Theme,
+};
+use crossterm::event::KeyEvent;
+use ratatui::{
+    prelude::*,
+    widgets::{List, ListState},
+};
+
+pub const KEYS: &[(&str, Hint)] = &[
+    ("Move selection", Hint::Actions(&[Action::Down, Action::Up])),
+    ("Apply", Hint::Actions(&[Action::Confirm])),
+    ("Help", Hint::Actions(&[Action::Help])),
+    ("Back", Hint::Actions(&[Action::Quit])),
+];
+
+const ACTIONS: &[Action] = &[Action::Quit, Action::Up, Action::Down, Action::Confirm];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Setting {
+    Theme(usize),
+    ColorblindMode,
+    Bell,
+    WrapCursor,
+    NumberFirst,
+    Charset,
+    DefaultDifficulty,
+    AutoNotes,
+    StrictEntry,
+    Assist,
+    MistakeLimit,
+    Autosave,
+    IdlePause,
+    ScreenReader,
+    ReducedMotion,
+    KeyPreset,
+}
+
+fn settings() -> Vec<Setting> {
+    (0..Theme::BUILTIN.len())
+        .map(Setting::Theme)
+        .chain([
+            Setting::ColorblindMode,
+            Setting::Bell,
+            Setting::WrapCursor,
+            Setting::NumberFirst,
+            Setting::Charset,
+            Setting::DefaultDifficulty,
+            Setting::AutoNotes,
+            Setting::StrictEntry,
+            Setting::Assist,
+            Setting::MistakeLimit,
+            Setting::Autosave,
+            Setting::IdlePause,
+            Setting::ScreenReader,
+            Setting::ReducedMotion,
+            Setting::KeyPreset,
+        ])
+        .collect()
+}
+
+pub struct SettingsScreen {
+    selected: usize,
+}
+
+impl SettingsScreen {
+    pub fn new(context: &Context) -> Self {
+        let selected = Theme::BUILTIN
+            .iter()
+            .position(|(_, theme)| *theme == context.config.theme)
+            .unwrap_or(0);
+        Self { selected }
+    }
+}
+
+fn apply_setting(setting: Setting, context: &mut Context) {
+    let config = &mut context.config;
+    match setting {
+        Setting::Theme(i) => config.theme = Theme::BUILTIN[i].1,
+        Setting::ColorblindMode => config.colorblind_mode = !config.colorblind_mode,
+        Setting::Bell => config.bell = !config.bell,
+        Setting::WrapCursor => config.wrap_cursor = !config.wrap_cursor,
+        Setting::NumberFirst => config.number_first = !config.number_first,
+        Setting::Charset => {
+            let next = Charset::ALL
+                .iter()
+                .position(|charset| *charset == config.charset)
+                .map_or(0, |i| (i + 1) % Charset::ALL.len());
+            config.charset = Charset::ALL[next];
+        }
+        Setting::DefaultDifficulty => {
+            let next = Difficulty::ALL
+                .iter()
+                .position(|difficulty| *difficulty == config.default_difficulty)
+                .map_or(0, |i| (i + 1) % Difficulty::ALL.len());
+            config.default_difficulty = Difficulty::ALL[next];
+        }
+        Setting::AutoNotes => config.auto_notes = !config.auto_notes,
+        Setting::StrictEntry => config.strict_entry = !config.strict_entry,
+        Setting::Assist => {
+            let next = Assist::ALL
+                .iter()
+                .position(|assist| *assist == config.assist)
+                .map_or(0, |i| (i + 1) % Assist::ALL.len());
+            config.assist = Assist::ALL[next];
+        }
+        Setting::MistakeLimit => {
+            let next = MistakeLimit::CHOICES
+                .iter()
+                .position(|limit| *limit == config.mistake_limit)
+                .map_or(0, |i| (i + 1) % MistakeLimit::CHOICES.len());
+            config.mistake_limit = MistakeLimit::CHOICES[next];
+        }
+        Setting::Autosave => {
+            config.autosave = match config.autosave {
+                Autosave::OnExit => Autosave::Off,
+                Autosave::Off => Autosave::OnExit,
+            }
+        }
+        Setting::IdlePause => {
+            let next = IdlePause::CHOICES
+                .iter()
+                .position(|idle| *idle == config.idle_pause)
+                .map_or(0, |i| (i + 1) % IdlePause::CHOICES.len());
+            config.idle_pause = IdlePause::CHOICES[next];
+        }
+        Setting::ScreenReader => config.screen_reader = !config.screen_reader,
+        Setting::ReducedMotion => config.reduced_motion = !config.reduced_motion,
+        Setting::KeyPreset => {
+            let next = Preset::ALL
+                .iter()
+                .position(|preset| *preset == config.keys.current_preset())
+                .map_or(0, |i| (i + 1) % Preset::ALL.len());
+            config.keys.set_preset(Preset::ALL[next]);
+        }
+    }
+    // the setting still applies for this session if the config can't be written
+    if let Some(error) = &context.config_error {
+        context.toast(format!("Not saved, the config file has an error: {error}"));
+        return;
+    }
+    match config.save(context.paths.config()) {
+        Ok(()) => context.toast("Saved"),
+        Err(_) => context.toast("Could not save settings"),
+    }
+}
+
+fn setting_label(setting: Setting, context: &Context) -> String {
+    let config = &context.config;
+    match setting {
+        Setting::Theme(i) => {
+            let (name, theme) = Theme::BUILTIN[i];
+            if theme == config.theme {
+                format!("Theme: {name} (active)")
+            } else {
+                format!("Theme: {name}")
+            }
+        }
+        Setting::ColorblindMode => match config.colorblind_mode {
+            true => "Colorblind mode: on".to_string(),
+            false => "Colorblind mode: off".to_string(),
+        },
+        Setting::Bell => match config.bell {
+            true => "Bell on rejected input: on".to_string(),
+            false => "Bell on rejected input: off".to_string(),
+        },
+        Setting::WrapCursor => match config.wrap_cursor {
+            true => "Wrap cursor at edges: on".to_string(),
+            false => "Wrap cursor at edges: off".to_string(),
+        },
+        Setting::NumberFirst => match config.number_first {
+            true => "Number-first input: on".to_string(),
+            false => "Number-first input: off".to_string(),
+        },
+        Setting::Charset => format!("Characters: {}", config.charset),
+        Setting::DefaultDifficulty => format!("Default difficulty: {}", config.default_difficulty),
+        Setting::AutoNotes => match config.auto_notes {
+            true => "Automatic pencil marks: on".to_string(),
+            false => "Automatic pencil marks: off".to_string(),
+        },
+        Setting::StrictEntry => match config.strict_entry {
+            true => "Reject conflicting values: on".to_string(),
+            false => "Reject conflicting values: off".to_string(),
+        },
+        Setting::Assist => format!("Elimination assist: {}", config.assist),
+        Setting::MistakeLimit => format!("Mistake limit: {}", config.mistake_limit),
+        Setting::Autosave => format!("Save unfinished games: {}", config.autosave),
+        Setting::IdlePause => format!("Stop the clock when idle: {}", config.idle_pause),
+        Setting::ScreenReader => match config.screen_reader {
+            true => "Screen reader announcements: on".to_string(),
+            false => "Screen reader announcements: off".to_string(),
+        },
+        Setting::ReducedMotion => match config.reduced_motion {
+            true => "Reduce motion: on".to_string(),
+            false => "Reduce motion: off".to_string(),
+        },
+        Setting::KeyPreset => format!("Key layout: {}", config.keys.current_preset()),
+    }
+}
+
+impl Screen for SettingsScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        match context.config.keys.action(key_event, ACTIONS) {
+            Some(Action::Quit) => return Transition::Pop(1),
+            Some(Action::Up) => self.selected = self.selected.saturating_sub(1),
+            Some(Action::Down) => self.selected = (self.selected + 1).min(settings().len() - 1),
+            Some(Action::Confirm) => apply_setting(settings()[self.selected], context),
+            _ => {}
+        }
+        Transition::None
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let appearance = context.config.appearance();
+        let hints = context.config.keys.hints(KEYS);
+        let body = render_footer(area, buf, " Settings ", &hints, appearance.key_hint_style());
+        let items = settings()
+            .into_iter()
+            .map(|setting| setting_label(setting, context));
+        let list = List::new(items)
+            .highlight_style(appearance.selected_style())
+            .highlight_symbol("> ");
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        StatefulWidget::render(list, body, buf, &mut state);
+    }
+}
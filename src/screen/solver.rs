@@ -0,0 +1,154 @@
+use super::{min_grid_size, render_footer, Context, Screen, Transition, FOOTER_ALLOWANCE};
+use crate::{
+    game::Game,
+    keymap::{Action, Hint},
+    solver::{Solver, SolverPanel},
+    tui,
+};
+use crossterm::event::{KeyEvent, KeyEventKind};
+use ratatui::prelude::*;
+use std::time::{Duration, Instant};
+
+pub const KEYS: &[(&str, Hint)] = &[
+    (
+        "Next (hold to fast-forward)",
+        Hint::Actions(&[Action::Step]),
+    ),
+    ("100 steps", Hint::Actions(&[Action::FastForward])),
+    ("Run/pause", Hint::Actions(&[Action::Run])),
+    ("Speed", Hint::Actions(&[Action::Faster, Action::Slower])),
+    ("Heatmap", Hint::Actions(&[Action::Heatmap])),
+    ("Help", Hint::Actions(&[Action::Help])),
+    ("Back", Hint::Actions(&[Action::Quit])),
+];
+
+const ACTIONS: &[Action] = &[
+    Action::Quit,
+    Action::Step,
+    Action::FastForward,
+    Action::Run,
+    Action::Faster,
+    Action::Slower,
+    Action::Heatmap,
+];
+
+const SOLVER_TICK: Duration = Duration::from_millis(50);
+/// Solver steps taken per tick at each speed setting
+const SOLVER_SPEEDS: [usize; 6] = [1, 4, 16, 64, 256, 1024];
+/// Steps closer together than this come from a held key rather than presses
+const HOLD_INTERVAL: Duration = Duration::from_millis(100);
+/// Repeats of a held step key before it moves up to the next speed
+const REPEATS_PER_SPEED: usize = 10;
+const FAST_FORWARD_STEPS: usize = 100;
+
+pub struct SolverScreen {
+    solver: Solver,
+    running: bool,
+    speed: usize,
+    last_step: Option<Instant>,
+    /// Step key repeats in a row while it's held down
+    repeats: usize,
+}
+
+impl SolverScreen {
+    pub fn new(game: Game) -> Self {
+        Self {
+            solver: Solver::new(game),
+            running: false,
+            speed: 0,
+            last_step: None,
+            repeats: 0,
+        }
+    }
+
+    /// Take up to `count` steps, stopping early once the search is over
+    fn step(&mut self, count: usize) {
+        for _ in 0..count {
+            if self.solver.game.is_correct() || self.solver.is_exhausted() {
+                self.running = false;
+                break;
+            }
+            self.solver.next();
+        }
+    }
+}
+
+impl Screen for SolverScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        let solver = &mut self.solver;
+        match context.config.keys.action(key_event, ACTIONS) {
+            Some(Action::Quit) => return Transition::Pop(1),
+            Some(Action::Step | Action::FastForward | Action::Run) if solver.game.is_correct() => {
+                context.toast("Already solved")
+            }
+            Some(Action::Step | Action::FastForward | Action::Run) if solver.is_exhausted() => {
+                context.toast("No solution from here")
+            }
+            Some(Action::Step) => {
+                // a held key fast-forwards, more steps at a time the longer
+                // it's held
+                let held = key_event.kind == KeyEventKind::Repeat
+                    || self
+                        .last_step
+                        .is_some_and(|at| at.elapsed() < HOLD_INTERVAL);
+                self.repeats = if held { self.repeats + 1 } else { 0 };
+                self.last_step = Some(Instant::now());
+                let speed = (self.repeats / REPEATS_PER_SPEED).min(SOLVER_SPEEDS.len() - 1);
+                self.step(SOLVER_SPEEDS[speed]);
+            }
+            Some(Action::FastForward) => self.step(FAST_FORWARD_STEPS),
+            // without animations there's nothing to watch, so go to the end
+            Some(Action::Run) if context.config.reduced_motion => self.step(usize::MAX),
+            Some(Action::Run) => self.running = !self.running && !solver.game.is_correct(),
+            Some(Action::Faster) => self.speed = (self.speed + 1).min(SOLVER_SPEEDS.len() - 1),
+            Some(Action::Slower) => self.speed = self.speed.saturating_sub(1),
+            Some(Action::Heatmap) => solver.show_heatmap = !solver.show_heatmap,
+            _ => {}
+        }
+        Transition::None
+    }
+
+    fn on_tick(&mut self, _context: &mut Context) -> Transition {
+        if self.running {
+            self.step(SOLVER_SPEEDS[self.speed]);
+        }
+        Transition::None
+    }
+
+    fn tick_rate(&self) -> Duration {
+        match self.running {
+            true => SOLVER_TICK,
+            false => super::TICK_RATE,
+        }
+    }
+
+    fn min_size(&self) -> (u16, u16) {
+        let (width, height) = min_grid_size(&self.solver.game);
+        (
+            width + SolverPanel::WIDTH,
+            height.max(SolverPanel::HEIGHT) + FOOTER_ALLOWANCE,
+        )
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let solver = &self.solver;
+        let mut appearance = context.config.appearance();
+        let title = format!(
+            " Sudoku Solver ({} x{}) ",
+            if self.running { "running" } else { "paused" },
+            SOLVER_SPEEDS[self.speed]
+        );
+        let hints = context.config.keys.hints(KEYS);
+        let body = render_footer(area, buf, &title, &hints, appearance.key_hint_style());
+        let columns = Layout::new(
+            layout::Direction::Horizontal,
+            [Constraint::Min(0), Constraint::Length(SolverPanel::WIDTH)],
+        )
+        .split(body);
+        solver.render(columns[0], buf, &mut appearance);
+        SolverPanel { solver, appearance }.render(
+            tui::centered_rect(columns[1], SolverPanel::WIDTH, SolverPanel::HEIGHT),
+            buf,
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use super::{Context, Screen, Transition};
+use crate::tui;
+use crossterm::event::{KeyEvent, MouseEvent, MouseEventKind};
+use ratatui::{prelude::*, widgets::Paragraph};
+
+const TITLE: [&str; 4] = [
+    r" ___ _   _ ___   ___  _  ___   _ ",
+    r"/ __| | | |   \ / _ \| |/ / | | |",
+    r"\__ \ |_| | |) | (_) | ' <| |_| |",
+    r"|___/\___/|___/ \___/|_|\_\\___/ ",
+];
+
+/// Title shown over the menu at startup, dismissed by any key or click
+pub struct SplashScreen {
+    /// How loading the settings went
+    status: String,
+}
+
+impl SplashScreen {
+    pub fn new(status: impl Into<String>) -> Self {
+        Self {
+            status: status.into(),
+        }
+    }
+}
+
+impl Screen for SplashScreen {
+    fn handle_key(&mut self, _key_event: KeyEvent, _context: &mut Context) -> Transition {
+        Transition::Pop(1)
+    }
+
+    fn handle_mouse(&mut self, mouse_event: MouseEvent, _context: &mut Context) -> Transition {
+        match mouse_event.kind {
+            MouseEventKind::Down(_) => Transition::Pop(1),
+            _ => Transition::None,
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let appearance = context.config.appearance();
+        let mut lines: Vec<Line> = TITLE.iter().map(|row| Line::from(row.bold())).collect();
+        lines.extend([
+            Line::from(format!("v{}", env!("CARGO_PKG_VERSION"))),
+            Line::default(),
+            Line::from(format!(
+                "Puzzles solved {}   Streak {}",
+                context.stats.puzzles_solved(),
+                context.streak
+            )),
+            Line::styled(self.status.as_str(), appearance.muted_style()),
+            Line::default(),
+            Line::styled("Press any key", appearance.key_hint_style()),
+        ]);
+        let height = lines.len() as u16;
+        Paragraph::new(lines)
+            .centered()
+            .render(tui::centered_rect(area, area.width, height), buf);
+    }
+}
@@ -0,0 +1,125 @@
+use super::{render_footer, Context, Screen, Transition, FOOTER_ALLOWANCE};
+use crate::{
+    difficulty::Difficulty,
+    keymap::{Action, Hint},
+    stats::{self, SolveStats, RECENT_SOLVES},
+    timer::format_duration,
+    tui,
+};
+use crossterm::event::KeyEvent;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph, Sparkline},
+};
+use std::time::Duration;
+
+pub const KEYS: &[(&str, Hint)] = &[
+    ("Help", Hint::Actions(&[Action::Help])),
+    ("Back", Hint::Actions(&[Action::Quit])),
+];
+
+/// Width of the table of solves, which everything else lines up with
+const WIDTH: u16 = 51;
+/// Rows of the table and the streak below it: the heading, a row for each
+/// difficulty, unrated and total ones, a gap and the streak
+const TABLE_HEIGHT: u16 = Difficulty::ALL.len() as u16 + 5;
+/// Rows of the recent solve times chart, borders included
+const CHART_HEIGHT: u16 = 6;
+
+/// Every game won or lost so far, from the stats file
+pub struct StatsScreen;
+
+fn row(name: &str, solves: SolveStats) -> String {
+    let time = |duration: Option<Duration>| duration.map_or("-".to_string(), format_duration);
+    format!(
+        "{name:<10} {:>6} {:>8} {:>8} {:>6} {:>6}",
+        solves.solved,
+        time(solves.best()),
+        time(solves.average()),
+        solves.hints_used,
+        solves.failed
+    )
+}
+
+impl Screen for StatsScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        match context.config.keys.action(key_event, &[Action::Quit]) {
+            Some(Action::Quit) => Transition::Pop(1),
+            _ => Transition::None,
+        }
+    }
+
+    fn min_size(&self) -> (u16, u16) {
+        (
+            WIDTH + 2,
+            TABLE_HEIGHT + 1 + CHART_HEIGHT + FOOTER_ALLOWANCE,
+        )
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let appearance = context.config.appearance();
+        let hints = context.config.keys.hints(KEYS);
+        let body = render_footer(
+            area,
+            buf,
+            " Statistics ",
+            &hints,
+            appearance.key_hint_style(),
+        );
+        let stats = &context.stats;
+        let mut lines = vec![Line::from(format!(
+            "{:<10} {:>6} {:>8} {:>8} {:>6} {:>6}",
+            "", "Solved", "Best", "Average", "Hints", "Failed"
+        ))
+        .bold()];
+        lines.extend(Difficulty::ALL.into_iter().map(|difficulty| {
+            Line::from(row(
+                &difficulty.to_string(),
+                stats.for_difficulty(Some(difficulty)),
+            ))
+        }));
+        lines.push(Line::from(row("Unrated", stats.for_difficulty(None))));
+        lines.push(Line::from(row("Total", stats.total())).bold());
+        lines.push(Line::default());
+        lines.push(Line::from(format!(
+            "Daily streak: {} (longest {})",
+            stats.current_streak(stats::today()),
+            stats.streak.best
+        )));
+
+        let recent: Vec<u64> = stats.recent().map(|time| time.as_secs()).collect();
+        let layout = Layout::new(
+            layout::Direction::Vertical,
+            [
+                Constraint::Length(TABLE_HEIGHT),
+                Constraint::Length(1),
+                Constraint::Length(CHART_HEIGHT),
+            ],
+        )
+        .split(tui::centered_rect(
+            body,
+            WIDTH,
+            TABLE_HEIGHT + 1 + CHART_HEIGHT,
+        ));
+        Paragraph::new(lines).render(layout[0], buf);
+        let block = Block::default()
+            .title(format!(" Last {RECENT_SOLVES} solve times "))
+            .borders(Borders::ALL);
+        if recent.is_empty() {
+            Paragraph::new(Line::styled(
+                "No puzzles solved yet",
+                appearance.muted_style(),
+            ))
+            .centered()
+            .block(block)
+            .render(layout[2], buf);
+        } else {
+            // slower solves draw taller bars
+            Sparkline::default()
+                .data(&recent)
+                .style(Style::new().fg(appearance.theme.entry))
+                .block(block)
+                .render(layout[2], buf);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use super::{min_grid_size, render_footer, Context, Screen, Transition, FOOTER_ALLOWANCE};
+use crate::{
+    keymap::{Action, Hint},
+    motion::{apply_motion, MOTIONS},
+    tutorial::Tutorial,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+use std::collections::HashMap;
+
+pub const KEYS: &[(&str, Hint)] = &[
+    (
+        "Move selection",
+        Hint::Actions(&[Action::Left, Action::Down, Action::Up, Action::Right]),
+    ),
+    ("Insert number", Hint::Fixed("<1-9>")),
+    ("Continue", Hint::Actions(&[Action::Confirm])),
+    ("Leave tutorial", Hint::Actions(&[Action::Quit])),
+];
+
+const ACTIONS: &[Action] = &[Action::Quit, Action::Confirm];
+
+/// Rows for the tutorial's text under the board
+const TEXT_HEIGHT: u16 = 5;
+
+/// Walks a new player through the rules and a first puzzle
+#[derive(Default)]
+pub struct TutorialScreen {
+    tutorial: Tutorial,
+}
+
+impl Screen for TutorialScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        let keys = &context.config.keys;
+        let tutorial = &mut self.tutorial;
+        if tutorial.is_waiting_for_entry() {
+            if let KeyCode::Char(digit @ '1'..='9') = key_event.code {
+                tutorial.enter(digit as usize - '0' as usize);
+                return Transition::None;
+            }
+            if let Some(action) = keys.action(key_event, MOTIONS) {
+                apply_motion(&mut tutorial.game, action, None, context.config.wrap_cursor);
+                return Transition::None;
+            }
+        }
+        match keys.action(key_event, ACTIONS) {
+            Some(Action::Quit) => Transition::Pop(1),
+            Some(Action::Confirm) => {
+                tutorial.advance();
+                match tutorial.is_finished() {
+                    true => Transition::Pop(1),
+                    false => Transition::None,
+                }
+            }
+            _ => Transition::None,
+        }
+    }
+
+    fn min_size(&self) -> (u16, u16) {
+        let (width, height) = min_grid_size(&self.tutorial.game);
+        (width, height + TEXT_HEIGHT + FOOTER_ALLOWANCE)
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let appearance = context.config.appearance();
+        let hints = context.config.keys.hints(KEYS);
+        let body = render_footer(
+            area,
+            buf,
+            " Sudoku Tutorial ",
+            &hints,
+            appearance.key_hint_style(),
+        );
+        let [grid_area, text_area] = Layout::new(
+            layout::Direction::Vertical,
+            [Constraint::Min(0), Constraint::Length(TEXT_HEIGHT)],
+        )
+        .areas(body);
+        let overlay = self.tutorial.overlay();
+        let mut highlights: HashMap<_, _> = overlay
+            .region
+            .iter()
+            .map(|&position| (position, appearance.theme.hint))
+            .collect();
+        if let Some(target) = overlay.target {
+            highlights.insert(target, appearance.theme.key_hint);
+        }
+        self.tutorial
+            .game
+            .render_with_highlights(grid_area, buf, appearance, highlights);
+        Paragraph::new(overlay.text)
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::ALL))
+            .render(text_area, buf);
+    }
+}
@@ -0,0 +1,98 @@
+use super::{
+    browser::BrowserScreen, game::GameScreen, menu::pasted_game, new_game::NewGameScreen,
+    render_footer, Context, Screen, Transition,
+};
+use crate::{
+    keymap::{Action, Hint},
+    tui,
+    variant::Variant,
+};
+use crossterm::event::KeyEvent;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, List, ListState},
+};
+
+pub const KEYS: &[(&str, Hint)] = &[
+    ("Move selection", Hint::Actions(&[Action::Down, Action::Up])),
+    ("Generate", Hint::Actions(&[Action::Confirm])),
+    ("Open file", Hint::Actions(&[Action::OpenFile])),
+    ("Paste puzzle", Hint::Actions(&[Action::Paste])),
+    ("Help", Hint::Actions(&[Action::Help])),
+    ("Back", Hint::Actions(&[Action::Quit])),
+];
+
+const ACTIONS: &[Action] = &[
+    Action::Quit,
+    Action::Up,
+    Action::Down,
+    Action::Confirm,
+    Action::OpenFile,
+    Action::Paste,
+];
+
+/// Choices in the list: the generator's variants, then samurai
+const CHOICES: usize = Variant::ALL.len() + 1;
+
+/// Pick the rules to play by, then generate a puzzle or bring one in
+#[derive(Default)]
+pub struct VariantScreen {
+    /// Index into `Variant::ALL`, or past it for samurai
+    selected: usize,
+}
+
+impl Screen for VariantScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        let variant = Variant::ALL.get(self.selected).copied();
+        match context.config.keys.action(key_event, ACTIONS) {
+            Some(Action::Quit) => return Transition::Pop(1),
+            Some(Action::Up) => self.selected = self.selected.saturating_sub(1),
+            Some(Action::Down) => self.selected = (self.selected + 1).min(CHOICES - 1),
+            Some(Action::Confirm) => {
+                let screen = NewGameScreen::new(context, self.selected);
+                return Transition::Replace(Box::new(screen));
+            }
+            // a samurai board doesn't fit the formats puzzles come in
+            Some(Action::OpenFile | Action::Paste) if variant.is_none() => {
+                context.toast("Samurai puzzles can only be generated");
+            }
+            Some(Action::OpenFile) => {
+                let regions = variant.unwrap().regions();
+                let screen = BrowserScreen::new(context).with_regions(regions);
+                return Transition::Replace(Box::new(screen));
+            }
+            Some(Action::Paste) => match pasted_game() {
+                Ok(game) => {
+                    let game = game.with_regions(variant.unwrap().regions());
+                    return GameScreen::start_checked(game, context, 1);
+                }
+                Err(message) => context.toast(message),
+            },
+            _ => {}
+        }
+        Transition::None
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let appearance = context.config.appearance();
+        let key = appearance.key_hint_style();
+        let hints = context.config.keys.hints(KEYS);
+        let body = render_footer(area, buf, " Choose Variant ", &hints, key);
+        let variants = Variant::ALL
+            .iter()
+            .map(|variant| format!("{:<13} {}", variant.to_string(), variant.description()));
+        let samurai = format!("{:<13} Five grids overlapping at the corners", "Samurai");
+        let items: Vec<String> = variants.chain([samurai]).collect();
+        let list = List::new(items)
+            .block(Block::default().title(" Variant ").borders(Borders::ALL))
+            .highlight_style(appearance.selected_style())
+            .highlight_symbol("> ");
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        StatefulWidget::render(
+            list,
+            tui::centered_rect(body, 60, CHOICES as u16 + 2),
+            buf,
+            &mut state,
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use super::{min_grid_size, render_footer, Context, Screen, Transition, FOOTER_ALLOWANCE};
+use crate::{
+    game::{Game, StatusBar},
+    keymap::{Action, Hint},
+    net::{Message, Peer},
+    popup::Popup,
+    timer::format_duration,
+};
+use crossterm::event::KeyEvent;
+use ratatui::prelude::*;
+use std::time::Duration;
+
+pub const KEYS: &[(&str, Hint)] = &[
+    ("Help", Hint::Actions(&[Action::Help])),
+    ("Stop watching", Hint::Actions(&[Action::Quit])),
+];
+
+/// Someone else's game followed live from their broadcast, without any way
+/// to change it
+pub struct WatchScreen {
+    peer: Peer,
+    /// The board as last sent, once the first one has arrived
+    game: Option<Game>,
+    /// How long the player took, once they've solved it
+    solved: Option<Duration>,
+}
+
+impl WatchScreen {
+    pub fn new(peer: Peer) -> Self {
+        Self {
+            peer,
+            game: None,
+            solved: None,
+        }
+    }
+
+    fn receive(&mut self, message: Message) {
+        match message {
+            Message::Board {
+                givens,
+                values,
+                millis,
+            } => {
+                let Ok(mut game) = Game::new(givens) else {
+                    return;
+                };
+                if game
+                    .resume_from(&values, Duration::from_millis(millis))
+                    .is_ok()
+                {
+                    self.game = Some(game);
+                    self.solved = None;
+                }
+            }
+            Message::Entry {
+                position,
+                value,
+                by_host,
+            } => {
+                if let Some(game) = &mut self.game {
+                    game.selected = position;
+                    // entries from the broadcasting player's partner keep
+                    // their color
+                    let _ = match by_host {
+                        true => game.add_entry(position, value),
+                        false => game.add_partner_entry(position, value),
+                    };
+                }
+            }
+            Message::Solved { millis } => self.solved = Some(Duration::from_millis(millis)),
+            Message::Puzzle { .. } | Message::Progress { .. } => {}
+        }
+    }
+}
+
+impl Screen for WatchScreen {
+    fn handle_key(&mut self, key_event: KeyEvent, context: &mut Context) -> Transition {
+        match context.config.keys.action(key_event, &[Action::Quit]) {
+            Some(Action::Quit) => Transition::Pop(1),
+            _ => Transition::None,
+        }
+    }
+
+    fn on_tick(&mut self, _context: &mut Context) -> Transition {
+        while let Some(message) = self.peer.try_recv() {
+            self.receive(message);
+        }
+        Transition::None
+    }
+
+    fn min_size(&self) -> (u16, u16) {
+        match &self.game {
+            Some(game) => {
+                let (width, height) = min_grid_size(game);
+                (width, height + 1 + FOOTER_ALLOWANCE)
+            }
+            None => (super::MIN_WIDTH, super::MIN_HEIGHT),
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer, context: &Context) {
+        let mut appearance = context.config.appearance();
+        let hints = context.config.keys.hints(KEYS);
+        let body = render_footer(area, buf, " Watching ", &hints, appearance.key_hint_style());
+        let Some(game) = &self.game else {
+            let message = match self.peer.is_closed() {
+                true => "The game has ended",
+                false => "Waiting for the game to start",
+            };
+            Popup {
+                title: " Watching ",
+                lines: vec![Line::from(message)],
+            }
+            .render(body, buf);
+            return;
+        };
+        let layout = Layout::new(
+            layout::Direction::Vertical,
+            [Constraint::Min(0), Constraint::Length(1)],
+        )
+        .split(body);
+        game.render(layout[0], buf, &mut appearance);
+        match (self.solved, self.peer.is_closed()) {
+            (Some(time), _) => Line::from(format!("Solved in {}", format_duration(time)))
+                .centered()
+                .render(layout[1], buf),
+            (None, true) => Line::styled("The game has ended", appearance.muted_style())
+                .centered()
+                .render(layout[1], buf),
+            (None, false) => StatusBar {
+                game,
+                notes_mode: false,
+            }
+            .render(layout[1], buf),
+        }
+    }
+}
@@ -0,0 +1,345 @@
+use crate::{
+    difficulty::Difficulty,
+    format::lenient,
+    generator, logic,
+    rng::Rng,
+    solver::{self, OutOfSteps, Validity},
+    variant::Variant,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+// Every endpoint takes a POST with a JSON body and answers in JSON. Grids
+// come back as arrays of rows, and errors as `{ "error": "..." }` with a 4xx
+// status.
+
+/// The largest body read, well above any grid
+const MAX_BODY: usize = 64 * 1024;
+
+/// The most read of the request line and headers together
+const MAX_HEAD: u64 = 8 * 1024;
+
+/// How long a connection may stall on a read or write before it's dropped
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The most connections answered at once, past which new ones are turned
+/// away with a 503
+const MAX_CONNECTIONS: usize = 32;
+
+/// The most values a search tries before giving up with a 503, enough for
+/// any puzzle a person would set
+const SOLVER_BUDGET: usize = 2_000_000;
+
+/// A puzzle as sent: written out in any of the usual shapes, as rows, or as
+/// every cell in one array
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Puzzle {
+    Text(String),
+    Rows(Vec<Vec<usize>>),
+    Cells(Vec<usize>),
+}
+
+impl Puzzle {
+    fn cells(self) -> Result<Vec<usize>, String> {
+        let cells = match self {
+            Puzzle::Text(text) => return lenient::parse(&text).map_err(|error| error.to_string()),
+            Puzzle::Rows(rows) => rows.concat(),
+            Puzzle::Cells(cells) => cells,
+        };
+        let size = cells.len().isqrt();
+        if ![4, 9, 16].contains(&size) || size * size != cells.len() {
+            return Err(format!(
+                "expected 16, 81 or 256 cells, found {}",
+                cells.len()
+            ));
+        }
+        if let Some(value) = cells.iter().find(|&&value| value > size) {
+            return Err(format!("{value} is too large for a {size}x{size} grid"));
+        }
+        Ok(cells)
+    }
+}
+
+#[derive(Deserialize)]
+struct PuzzleRequest {
+    puzzle: Puzzle,
+}
+
+#[derive(Deserialize)]
+struct GenerateRequest {
+    #[serde(default)]
+    difficulty: Difficulty,
+    #[serde(default = "default_size")]
+    size: usize,
+    seed: Option<u64>,
+}
+
+fn default_size() -> usize {
+    9
+}
+
+/// A grid as JSON, an array of rows
+fn rows(cells: &[usize]) -> Value {
+    json!(cells.chunks(cells.len().isqrt()).collect::<Vec<_>>())
+}
+
+fn error(status: u16, message: impl Into<String>) -> (u16, Value) {
+    (status, json!({ "error": message.into() }))
+}
+
+fn out_of_steps(_: OutOfSteps) -> (u16, Value) {
+    error(503, "gave up searching for a solution")
+}
+
+/// Read a JSON body into the request type, or the error to answer with
+fn body<T: for<'a> Deserialize<'a>>(body: &[u8]) -> Result<T, (u16, Value)> {
+    serde_json::from_slice(body).map_err(|e| error(400, format!("invalid request: {e}")))
+}
+
+/// Answer a request, as the status and the JSON to send back
+pub fn handle(method: &str, path: &str, request: &[u8]) -> (u16, Value) {
+    if !matches!(path, "/solve" | "/generate" | "/rate" | "/check") {
+        return error(404, format!("no endpoint at {path}"));
+    }
+    if method != "POST" {
+        return error(405, format!("{path} takes a POST"));
+    }
+    match route(path, request) {
+        Ok(response) => (200, response),
+        Err(response) => response,
+    }
+}
+
+fn route(path: &str, request: &[u8]) -> Result<Value, (u16, Value)> {
+    if path == "/generate" {
+        let request: GenerateRequest = body(request)?;
+        let variant = Variant::ALL
+            .into_iter()
+            .find(|variant| variant.size() == request.size)
+            .ok_or_else(|| error(422, format!("no {0}x{0} puzzles", request.size)))?;
+        let mut rng = request.seed.map_or_else(Rng::from_time, Rng::new);
+        let generated = generator::generate(variant, request.difficulty, &mut rng);
+        return Ok(json!({
+            "givens": rows(&generated.givens),
+            "solution": rows(&generated.solution),
+        }));
+    }
+    let request: PuzzleRequest = body(request)?;
+    let cells = request.puzzle.cells().map_err(|e| error(422, e))?;
+    match path {
+        "/solve" => {
+            let solution =
+                solver::solve_cells_within(&cells, SOLVER_BUDGET).map_err(out_of_steps)?;
+            Ok(json!({ "solution": solution.as_deref().map(rows) }))
+        }
+        "/check" => {
+            let validity = solver::validate_within(&cells, SOLVER_BUDGET).map_err(out_of_steps)?;
+            let consistent = validity != Validity::Inconsistent;
+            let solvable = consistent && validity != Validity::Unsolvable;
+            let unique = validity == Validity::Unique;
+            Ok(json!({ "consistent": consistent, "solvable": solvable, "unique": unique }))
+        }
+        _ => {
+            match solver::validate_within(&cells, SOLVER_BUDGET).map_err(out_of_steps)? {
+                Validity::Unique => {}
+                Validity::MultipleSolutions => return Err(error(422, "more than one solution")),
+                _ => return Err(error(422, "no solution")),
+            }
+            let rating = logic::rate(&cells).ok_or_else(|| error(422, "no solution"))?;
+            let techniques: Vec<String> = rating.techniques.iter().map(|t| t.to_string()).collect();
+            Ok(json!({
+                "difficulty": rating.difficulty,
+                "techniques": techniques,
+                "solved_by_logic": rating.solved,
+            }))
+        }
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Unprocessable Entity",
+    }
+}
+
+/// Read one HTTP/1.1 request off the connection and answer it
+fn respond(stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(&stream).take(MAX_HEAD);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+    let mut length = 0;
+    let mut complete = line.ends_with('\n');
+    while complete {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        complete = header.ends_with('\n');
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let (status, response) = if !complete {
+        error(431, "request headers too large")
+    } else if length > MAX_BODY {
+        error(413, "request body too large")
+    } else {
+        let mut request = vec![0; length];
+        reader.set_limit(length as u64);
+        reader.read_exact(&mut request)?;
+        handle(&method, &path, &request)
+    };
+    send(&stream, status, &response)
+}
+
+/// Write a response and close the connection
+fn send(mut stream: &TcpStream, status: u16, response: &Value) -> io::Result<()> {
+    let response = response.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+        reason(status),
+        response.len()
+    )?;
+    stream.flush()
+}
+
+/// A connection being answered, counted until it's dropped
+struct Slot(Arc<AtomicUsize>);
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Answer requests on the listener until it fails, each connection on its
+/// own thread, up to `MAX_CONNECTIONS` at once
+pub fn serve(listener: TcpListener) -> io::Result<()> {
+    let open = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let stream = stream?;
+        if open.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            open.fetch_sub(1, Ordering::SeqCst);
+            let (status, response) = error(503, "too many connections");
+            let sent = stream
+                .set_write_timeout(Some(TIMEOUT))
+                .and_then(|()| send(&stream, status, &response));
+            if let Err(error) = sent {
+                eprintln!("serve: {error}");
+            }
+            continue;
+        }
+        let slot = Slot(Arc::clone(&open));
+        thread::spawn(move || {
+            let _slot = slot;
+            if let Err(error) = respond(stream) {
+                eprintln!("serve: {error}");
+            }
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{format::text, library::PUZZLES};
+
+    #[test]
+    fn answers_each_endpoint() {
+        let line = text::write_line(&PUZZLES[1].cells());
+        let puzzle = json!({ "puzzle": line }).to_string();
+
+        let (status, solved) = handle("POST", "/solve", puzzle.as_bytes());
+        assert_eq!(status, 200);
+        assert_eq!(solved["solution"].as_array().unwrap().len(), 9);
+
+        let (_, checked) = handle("POST", "/check", puzzle.as_bytes());
+        assert_eq!(checked["unique"], true);
+
+        let (_, rated) = handle("POST", "/rate", puzzle.as_bytes());
+        assert_eq!(rated["difficulty"], "easy");
+
+        let rows = json!({ "puzzle": rows(&PUZZLES[1].cells()) }).to_string();
+        assert_eq!(handle("POST", "/solve", rows.as_bytes()).1, solved);
+
+        let request = json!({ "size": 4, "difficulty": "easy", "seed": 7 }).to_string();
+        let (status, generated) = handle("POST", "/generate", request.as_bytes());
+        assert_eq!(status, 200);
+        assert_eq!(generated["givens"].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn reports_bad_requests() {
+        assert_eq!(handle("POST", "/nowhere", b"{}").0, 404);
+        assert_eq!(handle("GET", "/solve", b"").0, 405);
+        assert_eq!(handle("POST", "/solve", b"not json").0, 400);
+        let (status, response) = handle("POST", "/check", br#"{"puzzle": "123"}"#);
+        assert_eq!(status, 422);
+        assert!(response["error"].is_string());
+        assert_eq!(handle("POST", "/generate", br#"{"size": 5}"#).0, 422);
+    }
+
+    #[test]
+    fn speaks_http() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || respond(listener.accept().unwrap().0));
+        let mut client = TcpStream::connect(address).unwrap();
+        let body = json!({ "puzzle": text::write_line(&PUZZLES[1].cells()) }).to_string();
+        write!(
+            client,
+            "POST /check HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        server.join().unwrap().unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(r#"{"consistent":true,"solvable":true,"unique":true}"#));
+    }
+
+    #[test]
+    fn caps_the_headers() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || respond(listener.accept().unwrap().0));
+        let mut client = TcpStream::connect(address).unwrap();
+        // a header that runs to the cap without ending
+        let mut request = "POST /check HTTP/1.1\r\nX-Padding: ".to_string();
+        request.extend(std::iter::repeat_n('a', MAX_HEAD as usize - request.len()));
+        client.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        server.join().unwrap().unwrap();
+        assert!(response.starts_with("HTTP/1.1 431 "));
+    }
+}
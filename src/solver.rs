@@ -1,15 +1,48 @@
+use crate::{
+    cage::Cage,
+    checker::Checker,
+    constraint::Constraint,
+    dlx,
+    grid::{Grid, Regions},
+};
+#[cfg(feature = "std")]
 use crate::{
     game::{Entry, Game},
     grid::GridPosition,
+    history::History,
+    timer::Timer,
+};
+#[cfg(feature = "tui")]
+use crate::{grid::position_label, theme::Appearance};
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "tui")]
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Color,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, StatefulWidget, Widget},
 };
-use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+#[cfg(feature = "tui")]
+use std::collections::HashMap;
 
+/// Steps through a game one value at a time, to watch backtracking happen
+#[cfg(feature = "std")]
 pub struct Solver {
     pub game: Game,
     empty_positions: Vec<GridPosition>,
-    entries_added: Vec<Entry>,
+    entries_added: History,
+    pub show_heatmap: bool,
+    steps: usize,
+    backtracks: usize,
+    /// How many times each cell has been written, indexed like the grid cells
+    rewrites: Vec<usize>,
+    timer: Timer,
+    /// Every value has been tried without finding a solution
+    exhausted: bool,
 }
 
+#[cfg(feature = "std")]
 impl Solver {
     pub fn new(game: Game) -> Self {
         // TODO: handle game with entries
@@ -28,56 +61,639 @@ impl Solver {
             })
             .collect();
         Self {
-            game,
             empty_positions,
-            entries_added: Vec::new(),
+            entries_added: History::new(game.size()),
+            show_heatmap: false,
+            steps: 0,
+            backtracks: 0,
+            rewrites: vec![0; game.cell_count()],
+            timer: Timer::new(),
+            exhausted: false,
+            game,
         }
     }
 
     pub fn next(&mut self) {
-        if self.game.is_correct() {
+        if self.game.is_correct() || self.exhausted {
+            self.timer.pause();
             return;
         }
-        if self.game.invalid_subsections.len() == 0 {
+        self.timer.resume();
+        self.steps += 1;
+        if self.game.is_valid() {
             let position = self.empty_positions.pop().unwrap();
             self.game.selected = position;
-            self.entries_added
-                .push(self.game.add_entry(position, 1).unwrap());
-        }
-        let Entry {
-            position, value, ..
-        } = self
-            .entries_added
-            .pop()
-            // TODO: handle this better
-            .expect("Game isn't solvable or was given in invalid state");
-        self.game.selected = position;
-        let next_value = if value + 1 <= self.game.size() {
-            value + 1
-        } else {
+            self.write(position, 1);
+            return;
+        }
+        loop {
+            let Some(Entry {
+                position, value, ..
+            }) = self.entries_added.pop()
+            else {
+                // nothing left to backtrack into, so there's no solution
+                self.exhausted = true;
+                self.timer.pause();
+                return;
+            };
+            self.game.selected = position;
+            if value < self.game.size() {
+                self.write(position, value + 1);
+                break;
+            }
+            // every value failed here, so clear the cell and try the next
+            // value in the cell before it
+            self.game.add_entry(position, 0).unwrap();
+            self.rewrites[position.1 * self.game.size() + position.0] += 1;
             self.empty_positions.push(position);
-            0
-        };
+            self.backtracks += 1;
+        }
+        if self.game.is_correct() {
+            self.timer.pause();
+        }
+    }
+
+    fn write(&mut self, position: GridPosition, value: usize) {
         self.entries_added
-            .push(self.game.add_entry(position, next_value).unwrap());
+            .push(self.game.add_entry(position, value).unwrap());
+        self.rewrites[position.1 * self.game.size() + position.0] += 1;
+    }
+
+    pub fn rewrites(&self, position: GridPosition) -> usize {
+        self.rewrites[position.1 * self.game.size() + position.0]
+    }
+
+    #[cfg(feature = "tui")]
+    /// Heat colors for every rewritten cell, scaled against the most
+    /// rewritten one
+    pub fn heatmap(&self) -> HashMap<GridPosition, Color> {
+        let size = self.game.size();
+        let hottest = self.rewrites.iter().copied().max().unwrap_or(0).max(1);
+        self.rewrites
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(i, count)| ((i % size, i / size), heat_color(*count, hottest)))
+            .collect()
+    }
+
+    /// Number of cells currently filled in by the search
+    pub fn depth(&self) -> usize {
+        self.entries_added.len()
+    }
+
+    /// The most recent cell tried by the search and the value it holds
+    pub fn current(&self) -> Option<Entry> {
+        self.entries_added.last()
+    }
+
+    /// Whether the search has run out of values to try, leaving the game
+    /// unsolved
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    pub fn backtracks(&self) -> usize {
+        self.backtracks
+    }
+
+    pub fn steps_per_second(&self) -> f64 {
+        let seconds = self.timer.elapsed().as_secs_f64();
+        if seconds > 0.0 {
+            self.steps as f64 / seconds
+        } else {
+            0.0
+        }
     }
 
     pub fn solve(game: Game) -> Game {
         let mut solver = Self::new(game);
-        while !solver.game.is_correct() {
+        while !solver.game.is_correct() && !solver.exhausted {
             solver.next();
         }
         solver.game
     }
 }
 
-impl Widget for &Solver {
+/// Candidate bitmasks per row, column, square and extra region for solving
+/// a flat list of cells directly, without the per-step entries that `Solver`
+/// records
+struct Candidates {
+    side_size: usize,
+    sub_square_size: usize,
+    rows: Vec<u32>,
+    columns: Vec<u32>,
+    squares: Vec<u32>,
+    regions: Vec<u32>,
+    /// The extra regions each cell is in, indexed like the cells
+    regions_of: Vec<Vec<usize>>,
+}
+
+impl Candidates {
+    /// `None` if the cells don't form a valid grid or the givens clash
+    fn new(cells: &[usize], regions: Regions) -> Option<Self> {
+        let grid = Grid::new(cells.to_vec()).ok()?;
+        let side_size = grid.size();
+        let units = regions.units(side_size);
+        let mut regions_of = vec![vec![]; cells.len()];
+        for (region, unit) in units.iter().enumerate() {
+            for &i in unit {
+                regions_of[i].push(region);
+            }
+        }
+        let mut candidates = Self {
+            side_size,
+            sub_square_size: grid.sub_square_size(),
+            rows: vec![0; side_size],
+            columns: vec![0; side_size],
+            squares: vec![0; side_size],
+            regions: vec![0; units.len()],
+            regions_of,
+        };
+        for (i, &value) in cells.iter().enumerate() {
+            if value != 0 {
+                if candidates.used(i) & (1 << value) != 0 {
+                    return None;
+                }
+                candidates.toggle(i, value);
+            }
+        }
+        Some(candidates)
+    }
+
+    fn indices(&self, i: usize) -> (usize, usize, usize) {
+        let (x, y) = (i % self.side_size, i / self.side_size);
+        let square = (y / self.sub_square_size) * self.sub_square_size + x / self.sub_square_size;
+        (y, x, square)
+    }
+
+    fn used(&self, i: usize) -> u32 {
+        let (row, column, square) = self.indices(i);
+        let used = self.rows[row] | self.columns[column] | self.squares[square];
+        self.regions_of[i]
+            .iter()
+            .fold(used, |used, &region| used | self.regions[region])
+    }
+
+    fn toggle(&mut self, i: usize, value: usize) {
+        let (row, column, square) = self.indices(i);
+        self.rows[row] ^= 1 << value;
+        self.columns[column] ^= 1 << value;
+        self.squares[square] ^= 1 << value;
+        for &region in &self.regions_of[i] {
+            self.regions[region] ^= 1 << value;
+        }
+    }
+}
+
+struct Search<'a> {
+    candidates: Candidates,
+    constraints: &'a [Constraint],
+    /// The constraints each cell is part of, indexed like the cells
+    constraints_of: Vec<Vec<usize>>,
+    cages: &'a [Cage],
+    /// The cage each cell is in, indexed like the cells
+    cage_of: Vec<Option<usize>>,
+    checker: Checker,
+    value_order: &'a [usize],
+    limit: usize,
+    count: usize,
+    first_solution: Option<Vec<usize>>,
+    /// Values tried so far
+    steps: usize,
+    /// Values to try before giving up
+    budget: usize,
+}
+
+impl Search<'_> {
+    /// Whether `value` in cell `i` leaves every constraint on the cell, and
+    /// its cage, satisfiable with the cells filled so far
+    fn allows(&mut self, cells: &mut [usize], i: usize, value: usize) -> bool {
+        let size = self.candidates.side_size;
+        cells[i] = value;
+        let allowed = self.constraints_of[i].iter().all(|&c| {
+            let constraint = &self.constraints[c];
+            let values: Vec<usize> = constraint
+                .cells()
+                .into_iter()
+                .map(|(x, y)| cells[y * size + x])
+                .collect();
+            self.checker.check_constraint(constraint, &values).valid
+        }) && self.cage_of[i]
+            .is_none_or(|c| cage_allows(&self.cages[c], cells, size));
+        cells[i] = 0;
+        allowed
+    }
+
+    fn run(&mut self, cells: &mut [usize]) {
+        // most constrained empty cell first
+        let mut best: Option<(usize, u32)> = None;
+        for (i, &value) in cells.iter().enumerate() {
+            if value != 0 {
+                continue;
+            }
+            let used = self.candidates.used(i);
+            let free = self.candidates.side_size as u32 - (used >> 1).count_ones();
+            if best.is_none_or(|(_, best_free)| free < best_free) {
+                best = Some((i, free));
+                if free <= 1 {
+                    break;
+                }
+            }
+        }
+        let Some((i, _)) = best else {
+            self.count += 1;
+            if self.first_solution.is_none() {
+                self.first_solution = Some(cells.to_vec());
+            }
+            return;
+        };
+        let used = self.candidates.used(i);
+        for &value in self.value_order {
+            if used & (1 << value) != 0 || !self.allows(cells, i, value) {
+                continue;
+            }
+            if self.steps >= self.budget {
+                return;
+            }
+            cells[i] = value;
+            self.steps += 1;
+            self.candidates.toggle(i, value);
+            self.run(cells);
+            self.candidates.toggle(i, value);
+            cells[i] = 0;
+            if self.count >= self.limit {
+                return;
+            }
+        }
+    }
+}
+
+/// Whether the cage's empty cells could still make up the rest of its sum,
+/// with distinct values not yet used in it
+fn cage_allows(cage: &Cage, cells: &[usize], size: usize) -> bool {
+    let (mut used, mut total, mut empty) = (0u32, 0, 0);
+    for &(x, y) in &cage.cells {
+        match cells[y * size + x] {
+            0 => empty += 1,
+            value if used & (1 << value) != 0 => return false,
+            value => {
+                used |= 1 << value;
+                total += value;
+            }
+        }
+    }
+    let Some(rest) = cage.sum.checked_sub(total) else {
+        return false;
+    };
+    let unused: Vec<usize> = (1..=size)
+        .filter(|value| used & (1 << value) == 0)
+        .collect();
+    let least: usize = unused.iter().take(empty).sum();
+    let most: usize = unused.iter().rev().take(empty).sum();
+    empty <= unused.len() && (least..=most).contains(&rest)
+}
+
+/// Run a search to `limit` solutions, or `None` if the givens clash
+fn search<'a>(
+    cells: &[usize],
+    regions: Regions,
+    constraints: &'a [Constraint],
+    cages: &'a [Cage],
+    value_order: &'a [usize],
+    limit: usize,
+) -> Option<Search<'a>> {
+    search_within(
+        cells,
+        regions,
+        constraints,
+        cages,
+        value_order,
+        limit,
+        usize::MAX,
+    )
+}
+
+/// Run a search that also gives up once `budget` values have been tried
+fn search_within<'a>(
+    cells: &[usize],
+    regions: Regions,
+    constraints: &'a [Constraint],
+    cages: &'a [Cage],
+    value_order: &'a [usize],
+    limit: usize,
+    budget: usize,
+) -> Option<Search<'a>> {
+    let size = cells.len().isqrt();
+    let mut constraints_of = vec![vec![]; cells.len()];
+    for (c, constraint) in constraints.iter().enumerate() {
+        for (x, y) in constraint.cells() {
+            constraints_of.get_mut(y * size + x)?.push(c);
+        }
+    }
+    let mut cage_of = vec![None; cells.len()];
+    for (c, cage) in cages.iter().enumerate() {
+        for &(x, y) in &cage.cells {
+            *cage_of.get_mut(y * size + x)? = Some(c);
+        }
+    }
+    let mut search = Search {
+        candidates: Candidates::new(cells, regions)?,
+        constraints,
+        constraints_of,
+        cages,
+        cage_of,
+        checker: Checker::new(),
+        value_order,
+        limit,
+        count: 0,
+        first_solution: None,
+        steps: 0,
+        budget,
+    };
+    search.run(&mut cells.to_vec());
+    Some(search)
+}
+
+/// Solve a flat list of cells (0 for empty) by bitmask backtracking
+pub fn solve_cells(cells: &[usize]) -> Option<Vec<usize>> {
+    solve_cells_with_steps(cells).0
+}
+
+/// Solve a flat list of cells, also giving the number of values tried
+pub fn solve_cells_with_steps(cells: &[usize]) -> (Option<Vec<usize>>, usize) {
+    let value_order: Vec<usize> = (1..=cells.len().isqrt()).collect();
+    search(cells, Regions::default(), &[], &[], &value_order, 1)
+        .map_or((None, 0), |search| (search.first_solution, search.steps))
+}
+
+/// Solve trying values in the given order, used to produce varied solutions
+#[cfg(feature = "std")]
+pub(crate) fn solve_cells_with_order(
+    cells: &[usize],
+    regions: Regions,
+    value_order: &[usize],
+) -> Option<Vec<usize>> {
+    search(cells, regions, &[], &[], value_order, 1)?.first_solution
+}
+
+/// The search gave up before settling the puzzle either way
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfSteps;
+
+/// Solve a flat list of cells, giving up once `budget` values have been tried
+pub fn solve_cells_within(
+    cells: &[usize],
+    budget: usize,
+) -> Result<Option<Vec<usize>>, OutOfSteps> {
+    let value_order: Vec<usize> = (1..=cells.len().isqrt()).collect();
+    let regions = Regions::default();
+    match search_within(cells, regions, &[], &[], &value_order, 1, budget) {
+        Some(search) if search.count == 0 && search.steps >= budget => Err(OutOfSteps),
+        search => Ok(search.and_then(|search| search.first_solution)),
+    }
+}
+
+/// Count the solutions of a flat list of cells, stopping once `limit` is reached
+pub fn count_solutions(cells: &[usize], limit: usize) -> usize {
+    count_solutions_in(cells, Regions::default(), limit)
+}
+
+/// Count the solutions where the extra `regions` also hold every value once
+pub fn count_solutions_in(cells: &[usize], regions: Regions, limit: usize) -> usize {
+    count_solutions_with(cells, regions, &[], limit)
+}
+
+/// Count the solutions that also keep to `constraints`, ruling out each
+/// value that would break one as soon as it's tried
+pub fn count_solutions_with(
+    cells: &[usize],
+    regions: Regions,
+    constraints: &[Constraint],
+    limit: usize,
+) -> usize {
+    let value_order: Vec<usize> = (1..=cells.len().isqrt()).collect();
+    search(cells, regions, constraints, &[], &value_order, limit).map_or(0, |search| search.count)
+}
+
+/// Solve a game's puzzle from its givens, keeping to every rule it's played
+/// by: its extra regions, its constraints and its killer cages
+#[cfg(feature = "std")]
+pub fn solve_game(game: &Game) -> Option<Vec<usize>> {
+    let value_order: Vec<usize> = (1..=game.size()).collect();
+    let (regions, constraints, cages) = (game.regions(), game.constraints(), game.cages());
+    search(&game.givens(), regions, constraints, cages, &value_order, 1)?.first_solution
+}
+
+/// Solve the values on a grid, givens and entries alike, without setting up
+/// a `Game`
+pub fn solve_grid(grid: &Grid) -> Option<Vec<usize>> {
+    solve_cells(&grid.values())
+}
+
+/// The ways a grid can be solved, for comparing them against each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Backtracking over the cells in order, checking each value against
+    /// its row, column and box
+    Naive,
+    /// Backtracking over the most constrained cell, with bitmasks of the
+    /// values used
+    Bitmask,
+    /// Dancing links over sudoku as an exact cover problem
+    Dlx,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 3] = [Algorithm::Naive, Algorithm::Bitmask, Algorithm::Dlx];
+
+    /// Solve a flat list of cells (0 for empty)
+    pub fn solve(self, cells: &[usize]) -> Option<Vec<usize>> {
+        match self {
+            Algorithm::Naive => solve_cells_naive(cells),
+            Algorithm::Bitmask => solve_cells(cells),
+            Algorithm::Dlx => dlx::solve_cells(cells),
+        }
+    }
+}
+
+/// Solve a flat list of cells by trying each value in each empty cell in
+/// turn, the baseline the other algorithms are measured against
+pub fn solve_cells_naive(cells: &[usize]) -> Option<Vec<usize>> {
+    let grid = Grid::new(cells.to_vec()).ok()?;
+    let (size, box_size) = (grid.size(), grid.sub_square_size());
+    let fits = |cells: &[usize], i: usize, value: usize| {
+        let (x, y) = (i % size, i / size);
+        let (left, top) = (x - x % box_size, y - y % box_size);
+        (0..size).all(|k| {
+            let square = (top + k / box_size) * size + left + k % box_size;
+            [y * size + k, k * size + x, square]
+                .iter()
+                .all(|&other| other == i || cells[other] != value)
+        })
+    };
+    if (0..cells.len()).any(|i| cells[i] != 0 && !fits(cells, i, cells[i])) {
+        return None;
+    }
+    fn fill(
+        cells: &mut [usize],
+        size: usize,
+        fits: &impl Fn(&[usize], usize, usize) -> bool,
+    ) -> bool {
+        let Some(i) = cells.iter().position(|&value| value == 0) else {
+            return true;
+        };
+        for value in 1..=size {
+            if fits(cells, i, value) {
+                cells[i] = value;
+                if fill(cells, size, fits) {
+                    return true;
+                }
+            }
+        }
+        cells[i] = 0;
+        false
+    }
+    let mut solved = cells.to_vec();
+    fill(&mut solved, size, &fits).then_some(solved)
+}
+
+/// What a set of givens amounts to as a puzzle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Validity {
+    Unique,
+    MultipleSolutions,
+    Unsolvable,
+    /// Two givens in a row, column or square share a value
+    Inconsistent,
+}
+
+pub fn validate(cells: &[usize]) -> Validity {
+    if Candidates::new(cells, Regions::default()).is_none() {
+        return Validity::Inconsistent;
+    }
+    match count_solutions(cells, 2) {
+        0 => Validity::Unsolvable,
+        1 => Validity::Unique,
+        _ => Validity::MultipleSolutions,
+    }
+}
+
+/// Validate a puzzle, giving up once `budget` values have been tried
+pub fn validate_within(cells: &[usize], budget: usize) -> Result<Validity, OutOfSteps> {
+    let value_order: Vec<usize> = (1..=cells.len().isqrt()).collect();
+    let regions = Regions::default();
+    let Some(search) = search_within(cells, regions, &[], &[], &value_order, 2, budget) else {
+        return Ok(Validity::Inconsistent);
+    };
+    if search.count < 2 && search.steps >= budget {
+        return Err(OutOfSteps);
+    }
+    match search.count {
+        0 => Ok(Validity::Unsolvable),
+        1 => Ok(Validity::Unique),
+        _ => Ok(Validity::MultipleSolutions),
+    }
+}
+
+#[cfg(feature = "tui")]
+impl StatefulWidget for &Solver {
+    type State = Appearance;
+
+    fn render(self, area: Rect, buf: &mut Buffer, appearance: &mut Self::State) {
+        if self.show_heatmap {
+            self.game
+                .render_with_highlights(area, buf, *appearance, self.heatmap());
+        } else {
+            self.game.render(area, buf, appearance);
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+/// Blend from a cool blue for rarely rewritten cells to red for the hottest
+fn heat_color(count: usize, hottest: usize) -> Color {
+    let heat = count as f64 / hottest as f64;
+    let blend = |cold: u8, hot: u8| (cold as f64 + (hot as f64 - cold as f64) * heat) as u8;
+    Color::Rgb(blend(0x1d, 0xc0), blend(0x35, 0x20), blend(0x57, 0x1a))
+}
+
+#[cfg(feature = "tui")]
+/// Side panel describing where the step-by-step search currently is
+pub struct SolverPanel<'a> {
+    pub solver: &'a Solver,
+    pub appearance: Appearance,
+}
+
+#[cfg(feature = "tui")]
+impl SolverPanel<'_> {
+    pub const WIDTH: u16 = 30;
+    pub const HEIGHT: u16 = 11;
+}
+
+#[cfg(feature = "tui")]
+impl Widget for SolverPanel<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        self.game.render(area, buf);
+        let solver = self.solver;
+        let label = self.appearance.key_hint_style();
+        let row = |name: &'static str, value: String| {
+            Line::from(vec![
+                Span::styled(format!("{name:<11}"), label),
+                value.into(),
+            ])
+        };
+        let (cell, trying, candidates) = match solver.current() {
+            Some(entry) => {
+                let candidates = solver
+                    .game
+                    .candidates_for_cell(entry.position)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                (
+                    position_label(entry.position),
+                    entry.value.to_string(),
+                    candidates,
+                )
+            }
+            None => ("-".into(), "-".into(), "-".into()),
+        };
+        let hottest = (0..solver.game.size())
+            .flat_map(|y| (0..solver.game.size()).map(move |x| (x, y)))
+            .max_by_key(|position| solver.rewrites(*position))
+            .filter(|position| solver.rewrites(*position) > 0)
+            .map(|position| {
+                format!(
+                    "{} ({})",
+                    position_label(position),
+                    solver.rewrites(position)
+                )
+            })
+            .unwrap_or_else(|| "-".into());
+        let lines = vec![
+            row("Depth", solver.depth().to_string()),
+            row("Cell", cell),
+            row("Trying", trying),
+            row("Candidates", candidates),
+            Line::default(),
+            row("Steps", solver.steps().to_string()),
+            row("Backtracks", solver.backtracks().to_string()),
+            row("Hottest", hottest),
+            row("Steps/s", format!("{:.0}", solver.steps_per_second())),
+        ];
+        Paragraph::new(lines)
+            .block(Block::default().title(" Search ").borders(Borders::ALL))
+            .render(area, buf);
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
@@ -115,4 +731,178 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn backtracks_into_the_cell_before() {
+        let cells = crate::library::PUZZLES[2].cells();
+        let mut solver = Solver::new(Game::new(cells).unwrap());
+        let mut depth = 0;
+        while solver.backtracks() == 0 {
+            depth = solver.depth();
+            solver.next();
+        }
+        // the cell out of values is cleared and left to fill again, and the
+        // one filled before it moves on to its next value in the same step
+        let cleared = *solver.empty_positions.last().unwrap();
+        assert_eq!(solver.game.get_cell(cleared), Ok(0));
+        assert_eq!(solver.depth(), depth - solver.backtracks());
+        let current = solver.current().unwrap();
+        assert_eq!(solver.game.selected, current.position);
+        assert!(current.value > 1);
+    }
+
+    #[test]
+    fn stops_when_unsolvable() {
+        // r1c4 can't hold anything, though nothing clashes yet
+        let game = Game::new(vec![
+            1, 2, 0, 0, //
+            0, 0, 0, 3, //
+            0, 0, 0, 4, //
+            0, 0, 0, 0, //
+        ])
+        .unwrap();
+        let mut solver = Solver::new(game);
+        while !solver.is_exhausted() {
+            solver.next();
+        }
+        assert!(!solver.game.is_correct());
+        assert_eq!(solver.depth(), 0);
+    }
+
+    #[test]
+    fn solve_cells() {
+        let cells: Vec<usize> = crate::library::PUZZLES[7].cells();
+        let solution = super::solve_cells(&cells).unwrap();
+        let solved = Game::new(solution).unwrap();
+        assert!(solved.is_correct());
+        assert_eq!(
+            super::solve_cells(&[1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+            None
+        );
+    }
+
+    #[test]
+    fn solve_game() {
+        for puzzle in crate::library::PUZZLES {
+            let mut game = puzzle.game().unwrap();
+            let solution = super::solve_game(&game).unwrap();
+            game.resume_from(&solution, Default::default()).unwrap();
+            assert!(game.is_correct(), "{}", puzzle.name);
+        }
+    }
+
+    #[test]
+    fn count_solutions() {
+        // variant puzzles are only unique once their extra rules are counted
+        let puzzles = crate::library::PUZZLES.into_iter();
+        for puzzle in puzzles.filter(|puzzle| puzzle.is_classic()) {
+            assert_eq!(
+                super::count_solutions(&puzzle.cells(), 2),
+                1,
+                "{}",
+                puzzle.name
+            );
+        }
+        let diagonal = crate::library::PUZZLES
+            .iter()
+            .find(|puzzle| puzzle.diagonals);
+        let regions = Regions {
+            diagonals: true,
+            windows: false,
+        };
+        assert_eq!(
+            count_solutions_in(&diagonal.unwrap().cells(), regions, 2),
+            1
+        );
+        let constrained = crate::library::PUZZLES
+            .iter()
+            .filter(|puzzle| !puzzle.constraints.is_empty());
+        for puzzle in constrained {
+            let cells = puzzle.cells();
+            assert!(super::count_solutions(&cells, 2) > 1, "{}", puzzle.name);
+            let constraints = puzzle.constraints();
+            let count = count_solutions_with(&cells, Regions::default(), &constraints, 2);
+            assert_eq!(count, 1, "{}", puzzle.name);
+        }
+        assert_eq!(super::count_solutions(&[0; 16], 10), 10);
+        assert_eq!(super::count_solutions(&[0; 16], 1000), 288);
+    }
+
+    #[test]
+    fn validate() {
+        let cells = crate::library::PUZZLES[1].cells();
+        assert_eq!(super::validate(&cells), Validity::Unique);
+        assert_eq!(super::validate(&[0; 16]), Validity::MultipleSolutions);
+        let mut clash = vec![0; 16];
+        clash[..2].copy_from_slice(&[1, 1]);
+        assert_eq!(super::validate(&clash), Validity::Inconsistent);
+        // consistent givens that leave r1c4 without a value
+        let mut stuck = vec![0; 16];
+        stuck[..3].copy_from_slice(&[1, 2, 3]);
+        stuck[15] = 4;
+        assert_eq!(super::validate(&stuck), Validity::Unsolvable);
+    }
+
+    #[test]
+    fn gives_up_past_the_budget() {
+        let cells = crate::library::PUZZLES[1].cells();
+        assert_eq!(solve_cells_within(&cells, 1), Err(OutOfSteps));
+        assert_eq!(
+            solve_cells_within(&cells, 100_000),
+            Ok(super::solve_cells(&cells))
+        );
+        assert_eq!(validate_within(&cells, 1), Err(OutOfSteps));
+        assert_eq!(validate_within(&cells, 100_000), Ok(Validity::Unique));
+    }
+
+    #[test]
+    fn steps_backtrack_to_a_solution() {
+        let cells = crate::library::PUZZLES[2].cells();
+        let mut solver = Solver::new(Game::new(cells.clone()).unwrap());
+        while !solver.game.is_correct() && !solver.exhausted {
+            solver.next();
+        }
+        assert_eq!(
+            solver.depth(),
+            cells.iter().filter(|value| **value == 0).count()
+        );
+        assert!(solver.backtracks() > 0);
+        assert!(solver.steps() > solver.depth());
+        #[cfg(feature = "tui")]
+        {
+            let heatmap = solver.heatmap();
+            assert_eq!(heatmap.len(), solver.depth());
+            assert!(heatmap
+                .keys()
+                .all(|position| solver.rewrites(*position) > 0));
+        }
+        assert_eq!(solver.rewrites((2, 0)), 0);
+        let solved: Vec<usize> = solver.game.get_rows().into_iter().flatten().collect();
+        assert_eq!(Some(solved), super::solve_cells(&cells));
+    }
+
+    #[test]
+    fn algorithms_agree() {
+        use crate::difficulty::Difficulty;
+        let classic = crate::library::PUZZLES
+            .iter()
+            .filter(|puzzle| puzzle.is_classic());
+        for puzzle in classic {
+            let cells = puzzle.cells();
+            let solution = super::solve_cells(&cells);
+            assert!(solution.is_some(), "{}", puzzle.name);
+            // naive backtracking takes too long on the hard ones to test
+            let algorithms = match puzzle.difficulty {
+                Difficulty::Easy | Difficulty::Medium => &Algorithm::ALL[..],
+                _ => &Algorithm::ALL[1..],
+            };
+            for algorithm in algorithms {
+                assert_eq!(algorithm.solve(&cells), solution, "{}", puzzle.name);
+            }
+        }
+        let mut clashing = vec![0; 16];
+        clashing[0] = 2;
+        clashing[5] = 2;
+        assert_eq!(solve_cells_naive(&clashing), None);
+    }
 }
@@ -0,0 +1,312 @@
+use crate::{
+    difficulty::Difficulty,
+    format::text,
+    game::Game,
+    grid::{canonical_form, Regions},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub const STATS_FILE: &str = "stats.toml";
+
+/// Solve times kept for the trend shown on the statistics screen
+pub const RECENT_SOLVES: usize = 30;
+
+/// Solves of one difficulty
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolveStats {
+    pub solved: usize,
+    pub best_ms: Option<u64>,
+    pub total_ms: u64,
+    pub hints_used: usize,
+    /// Games ended by reaching the mistake limit
+    #[serde(default)]
+    pub failed: usize,
+}
+
+impl SolveStats {
+    pub fn best(&self) -> Option<Duration> {
+        self.best_ms.map(Duration::from_millis)
+    }
+
+    pub fn average(&self) -> Option<Duration> {
+        (self.solved > 0).then(|| Duration::from_millis(self.total_ms / self.solved as u64))
+    }
+
+    fn add(&mut self, other: &SolveStats) {
+        self.solved += other.solved;
+        self.best_ms = match (self.best_ms, other.best_ms) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.total_ms += other.total_ms;
+        self.hints_used += other.hints_used;
+        self.failed += other.failed;
+    }
+}
+
+/// Days in a row with at least one puzzle solved
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyStreak {
+    pub current: usize,
+    pub best: usize,
+    /// Days since the Unix epoch of the last solve
+    pub last_day: Option<u64>,
+}
+
+/// Every game won, and every one lost to the mistake limit, kept across
+/// sessions
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    /// Keyed by lowercase difficulty name, or "unrated" for puzzles without
+    /// one
+    #[serde(default)]
+    difficulties: BTreeMap<String, SolveStats>,
+    #[serde(default)]
+    pub streak: DailyStreak,
+    /// Times of the last few solves of any difficulty, oldest first
+    #[serde(default)]
+    recent_ms: Vec<u64>,
+    /// Best time for each puzzle solved, keyed by its canonical form written
+    /// on one line, so a puzzle counts as the same however it's turned
+    #[serde(default)]
+    puzzle_best_ms: BTreeMap<String, u64>,
+}
+
+#[derive(Debug)]
+pub enum StatsError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Serialize(toml::ser::Error),
+}
+
+impl From<io::Error> for StatsError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// What a puzzle's best time is kept under. Grids without cages or
+/// constraints are keyed by their canonical form, so the same puzzle turned
+/// or relabelled counts as one; the rest are kept as written, along with
+/// their rules, which would otherwise have to be turned with the grid.
+fn puzzle_key(game: &Game) -> String {
+    let variant = game.variant_name().to_lowercase();
+    if game.cages().is_empty() && game.constraints().is_empty() {
+        let key = text::write_line(&canonical_form(&game.givens()));
+        // the extra regions look the same however the grid is turned
+        return match game.regions() == Regions::default() {
+            true => key,
+            false => format!("{variant} {key}"),
+        };
+    }
+    let rules = serde_json::to_string(&(game.cages(), game.constraints()))
+        .expect("cages and constraints serialize");
+    format!("{variant} {} {rules}", text::write_line(&game.givens()))
+}
+
+fn key(difficulty: Option<Difficulty>) -> String {
+    difficulty.map_or("unrated".to_string(), |difficulty| {
+        difficulty.to_string().to_lowercase()
+    })
+}
+
+/// Days since the Unix epoch, in UTC
+pub fn today() -> u64 {
+    let since = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    since.as_secs() / 86_400
+}
+
+impl Stats {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, StatsError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents).map_err(StatsError::Parse),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), StatsError> {
+        let contents = toml::to_string_pretty(self).map_err(StatsError::Serialize)?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Count a win on the puzzle `game` is played on taking `elapsed`, on
+    /// the day `today` as given by `today()`
+    pub fn record(
+        &mut self,
+        game: &Game,
+        difficulty: Option<Difficulty>,
+        elapsed: Duration,
+        hints_used: usize,
+        today: u64,
+    ) {
+        let elapsed = elapsed.as_millis() as u64;
+        let stats = self.difficulties.entry(key(difficulty)).or_default();
+        stats.add(&SolveStats {
+            solved: 1,
+            best_ms: Some(elapsed),
+            total_ms: elapsed,
+            hints_used,
+            failed: 0,
+        });
+        let streak = &mut self.streak;
+        streak.current = match streak.last_day {
+            Some(day) if day == today => streak.current,
+            Some(day) if day + 1 == today => streak.current + 1,
+            _ => 1,
+        };
+        streak.best = streak.best.max(streak.current);
+        streak.last_day = Some(today);
+        let best = self
+            .puzzle_best_ms
+            .entry(puzzle_key(game))
+            .or_insert(elapsed);
+        *best = (*best).min(elapsed);
+        self.recent_ms.push(elapsed);
+        let excess = self.recent_ms.len().saturating_sub(RECENT_SOLVES);
+        self.recent_ms.drain(..excess);
+    }
+
+    /// Count a game of `difficulty` ended by reaching the mistake limit
+    pub fn record_failure(&mut self, difficulty: Option<Difficulty>) {
+        self.difficulties.entry(key(difficulty)).or_default().failed += 1;
+    }
+
+    /// The best time on the puzzle `game` is played on, if it's been solved
+    pub fn puzzle_best(&self, game: &Game) -> Option<Duration> {
+        self.puzzle_best_ms
+            .get(&puzzle_key(game))
+            .copied()
+            .map(Duration::from_millis)
+    }
+
+    /// How many different puzzles have been solved
+    pub fn puzzles_solved(&self) -> usize {
+        self.puzzle_best_ms.len()
+    }
+
+    /// Times of the last few solves, oldest first
+    pub fn recent(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.recent_ms.iter().copied().map(Duration::from_millis)
+    }
+
+    pub fn for_difficulty(&self, difficulty: Option<Difficulty>) -> SolveStats {
+        self.difficulties
+            .get(&key(difficulty))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Every difficulty added together
+    pub fn total(&self) -> SolveStats {
+        let mut total = SolveStats::default();
+        for stats in self.difficulties.values() {
+            total.add(stats);
+        }
+        total
+    }
+
+    /// The current daily streak, or 0 if a day has gone by since the last
+    /// solve
+    pub fn current_streak(&self, today: u64) -> usize {
+        match self.streak.last_day {
+            Some(day) if day + 1 >= today => self.streak.current,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::PUZZLES;
+
+    #[test]
+    fn records_wins() {
+        let mut stats = Stats::default();
+        let easy = Some(Difficulty::Easy);
+        let (first, second) = (PUZZLES[0].game().unwrap(), PUZZLES[1].game().unwrap());
+        stats.record(&first, easy, Duration::from_secs(100), 1, 10);
+        stats.record(&first, easy, Duration::from_secs(60), 0, 10);
+        stats.record(&second, None, Duration::from_secs(200), 2, 11);
+
+        let solves = stats.for_difficulty(easy);
+        assert_eq!(solves.solved, 2);
+        assert_eq!(solves.best(), Some(Duration::from_secs(60)));
+        assert_eq!(solves.average(), Some(Duration::from_secs(80)));
+        assert_eq!(stats.for_difficulty(Some(Difficulty::Hard)).average(), None);
+        assert_eq!(stats.total().solved, 3);
+        assert_eq!(stats.total().hints_used, 3);
+        assert_eq!(stats.puzzles_solved(), 2);
+        assert_eq!(stats.puzzle_best(&first), Some(Duration::from_secs(60)));
+        let givens = first.givens();
+        let mirrored: Vec<usize> = givens.chunks(9).rev().flatten().copied().collect();
+        let mirrored = Game::new(mirrored).unwrap();
+        assert_eq!(stats.puzzle_best(&mirrored), Some(Duration::from_secs(60)));
+        assert_eq!(stats.puzzle_best(&PUZZLES[2].game().unwrap()), None);
+        // the same givens under other rules are another puzzle
+        let diagonal = Game::new(givens).unwrap().with_diagonals();
+        assert_eq!(stats.puzzle_best(&diagonal), None);
+
+        assert_eq!(stats.current_streak(11), 2);
+        assert_eq!(stats.current_streak(13), 0);
+        stats.record(&first, easy, Duration::from_secs(60), 0, 13);
+        assert_eq!((stats.streak.current, stats.streak.best), (1, 2));
+        let recent: Vec<u64> = stats.recent().map(|time| time.as_secs()).collect();
+        assert_eq!(recent, [100, 60, 200, 60]);
+        for _ in 0..RECENT_SOLVES {
+            stats.record(&second, easy, Duration::from_secs(90), 0, 13);
+        }
+        assert_eq!(stats.recent().count(), RECENT_SOLVES);
+        assert!(stats.recent().all(|time| time.as_secs() == 90));
+
+        let contents = toml::to_string_pretty(&stats).unwrap();
+        assert_eq!(toml::from_str::<Stats>(&contents).unwrap(), stats);
+    }
+
+    #[test]
+    fn keys_puzzles_by_their_rules() {
+        let killer = PUZZLES
+            .iter()
+            .find(|puzzle| puzzle.cages.is_some())
+            .unwrap();
+        let killer = killer.game().unwrap();
+        let cageless = Game::new(killer.givens()).unwrap();
+        let mut stats = Stats::default();
+        stats.record(&killer, None, Duration::from_secs(300), 0, 10);
+        assert_eq!(stats.puzzle_best(&killer), Some(Duration::from_secs(300)));
+        assert_eq!(stats.puzzle_best(&cageless), None);
+        let other = Game::new(killer.givens())
+            .unwrap()
+            .with_cages(killer.cages()[1..].to_vec());
+        assert_eq!(stats.puzzle_best(&other), None);
+    }
+
+    #[test]
+    fn records_failures() {
+        let mut stats = Stats::default();
+        let hard = Some(Difficulty::Hard);
+        stats.record_failure(hard);
+        stats.record_failure(hard);
+        stats.record_failure(None);
+        assert_eq!(stats.for_difficulty(hard).failed, 2);
+        assert_eq!(stats.for_difficulty(hard).solved, 0);
+        assert_eq!(stats.total().failed, 3);
+        assert_eq!(stats.current_streak(today()), 0);
+
+        // stats files from before failures were counted still load
+        let old: Stats =
+            toml::from_str("[difficulties.easy]\nsolved = 1\ntotal_ms = 1000\nhints_used = 0\n")
+                .unwrap();
+        assert_eq!(old.for_difficulty(Some(Difficulty::Easy)).failed, 0);
+    }
+}
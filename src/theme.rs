@@ -0,0 +1,311 @@
+use ratatui::style::{Color, Style, Stylize};
+use serde::{Deserialize, Serialize};
+use std::env;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub given: Color,
+    pub entry: Color,
+    pub selected: Color,
+    pub conflict: Color,
+    pub conflict_text: Color,
+    pub key_hint: Color,
+    pub muted: Color,
+    /// Background of the extra regions in variants like X-Sudoku
+    pub diagonal: Color,
+    /// Background of the cells a hint is drawn from
+    pub hint: Color,
+    /// Background of the filled cells whose values a hint rests on
+    pub hint_reason: Color,
+    /// Background of the empty cells a hint rules its value out of
+    pub hint_excluded: Color,
+    /// Entries the other player made on a shared board
+    pub partner: Color,
+}
+
+impl Theme {
+    pub const DARK: Theme = Theme {
+        given: Color::White,
+        entry: Color::Blue,
+        selected: Color::DarkGray,
+        conflict: Color::Red,
+        conflict_text: Color::White,
+        key_hint: Color::Blue,
+        muted: Color::DarkGray,
+        diagonal: Color::Rgb(0x30, 0x30, 0x30),
+        hint: Color::Rgb(0x1c, 0x2b, 0x45),
+        hint_reason: Color::Rgb(0x45, 0x3a, 0x10),
+        hint_excluded: Color::Rgb(0x45, 0x1c, 0x1c),
+        partner: Color::Yellow,
+    };
+
+    pub const LIGHT: Theme = Theme {
+        given: Color::Black,
+        entry: Color::Blue,
+        selected: Color::Gray,
+        conflict: Color::LightRed,
+        conflict_text: Color::Black,
+        key_hint: Color::Magenta,
+        muted: Color::Gray,
+        diagonal: Color::Rgb(0xe4, 0xe4, 0xe4),
+        hint: Color::Rgb(0xd7, 0xe8, 0xff),
+        hint_reason: Color::Rgb(0xff, 0xf0, 0xc0),
+        hint_excluded: Color::Rgb(0xff, 0xdc, 0xdc),
+        partner: Color::Rgb(0xb0, 0x60, 0x00),
+    };
+
+    pub const SOLARIZED: Theme = Theme {
+        given: Color::Rgb(0x93, 0xa1, 0xa1),
+        entry: Color::Rgb(0x26, 0x8b, 0xd2),
+        selected: Color::Rgb(0x07, 0x36, 0x42),
+        conflict: Color::Rgb(0xdc, 0x32, 0x2f),
+        conflict_text: Color::Rgb(0xfd, 0xf6, 0xe3),
+        key_hint: Color::Rgb(0xb5, 0x89, 0x00),
+        muted: Color::Rgb(0x58, 0x6e, 0x75),
+        diagonal: Color::Rgb(0x0a, 0x2f, 0x3a),
+        hint: Color::Rgb(0x1d, 0x3b, 0x2a),
+        hint_reason: Color::Rgb(0x3b, 0x35, 0x0a),
+        hint_excluded: Color::Rgb(0x3f, 0x1d, 0x1d),
+        partner: Color::Rgb(0xcb, 0x4b, 0x16),
+    };
+
+    /// Only the eight basic colors, bright against each other
+    pub const HIGH_CONTRAST: Theme = Theme {
+        given: Color::White,
+        entry: Color::Cyan,
+        selected: Color::Blue,
+        conflict: Color::Red,
+        conflict_text: Color::White,
+        key_hint: Color::Yellow,
+        muted: Color::Blue,
+        diagonal: Color::Magenta,
+        hint: Color::Green,
+        hint_reason: Color::Cyan,
+        hint_excluded: Color::Magenta,
+        partner: Color::Yellow,
+    };
+
+    /// The terminal's own colors, telling cells apart by bold, underline
+    /// and reverse instead
+    pub const MONOCHROME: Theme = Theme {
+        given: Color::Reset,
+        entry: Color::Reset,
+        selected: Color::Reset,
+        conflict: Color::Reset,
+        conflict_text: Color::Reset,
+        key_hint: Color::Reset,
+        hint_reason: Color::Reset,
+        hint_excluded: Color::Reset,
+        muted: Color::Reset,
+        diagonal: Color::Reset,
+        hint: Color::Reset,
+        partner: Color::Reset,
+    };
+
+    pub const BUILTIN: [(&'static str, Theme); 5] = [
+        ("dark", Theme::DARK),
+        ("light", Theme::LIGHT),
+        ("solarized", Theme::SOLARIZED),
+        ("high contrast", Theme::HIGH_CONTRAST),
+        ("monochrome", Theme::MONOCHROME),
+    ];
+
+    pub fn builtin(name: &str) -> Option<Theme> {
+        Self::BUILTIN
+            .iter()
+            .find(|(builtin_name, _)| *builtin_name == name)
+            .map(|(_, theme)| *theme)
+    }
+
+    fn colors(&self) -> [Color; 12] {
+        [
+            self.given,
+            self.entry,
+            self.selected,
+            self.conflict,
+            self.conflict_text,
+            self.key_hint,
+            self.muted,
+            self.diagonal,
+            self.hint,
+            self.hint_reason,
+            self.hint_excluded,
+            self.partner,
+        ]
+    }
+
+    /// This theme if the terminal can show it, or else the built-in theme
+    /// made for what it can show
+    pub fn for_terminal(self, support: ColorSupport) -> Theme {
+        let basic = |color: &Color| !matches!(color, Color::Rgb(..) | Color::Indexed(_));
+        match support {
+            ColorSupport::Full => self,
+            ColorSupport::Basic if self.colors().iter().all(basic) => self,
+            ColorSupport::Basic => Theme::HIGH_CONTRAST,
+            ColorSupport::None => Theme::MONOCHROME,
+        }
+    }
+}
+
+/// How many colors the terminal can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSupport {
+    /// 256 colors or more
+    Full,
+    /// The eight basic colors and their bright versions
+    Basic,
+    None,
+}
+
+impl ColorSupport {
+    /// What the terminal reports through `NO_COLOR`, `COLORTERM` and `TERM`
+    pub fn detect() -> Self {
+        let var = |name| env::var(name).ok().filter(|value| !value.is_empty());
+        Self::from_env(var("NO_COLOR").is_some(), var("COLORTERM"), var("TERM"))
+    }
+
+    fn from_env(no_color: bool, colorterm: Option<String>, term: Option<String>) -> Self {
+        if no_color {
+            return Self::None;
+        }
+        if colorterm.is_some() {
+            return Self::Full;
+        }
+        match term.as_deref() {
+            // nothing to go on, as on Windows consoles, which show every color
+            None => Self::Full,
+            Some("dumb") => Self::None,
+            Some(term) if term.contains("256") || term.contains("direct") => Self::Full,
+            Some(term) if term.ends_with("-m") || term.ends_with("-mono") => Self::None,
+            Some(_) => Self::Basic,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Appearance {
+    pub theme: Theme,
+    pub colorblind: bool,
+    /// Draw with plain ASCII for terminals without box-drawing characters
+    pub ascii: bool,
+    /// Mark rejected cells until the next input instead of flashing them
+    pub reduced_motion: bool,
+}
+
+impl Appearance {
+    pub fn key_hint_style(&self) -> Style {
+        if self.colorblind {
+            Style::new().bold().underlined()
+        } else {
+            Style::new().fg(self.theme.key_hint).bold()
+        }
+    }
+
+    pub fn muted_style(&self) -> Style {
+        if self.colorblind {
+            Style::new().dim().crossed_out()
+        } else {
+            Style::new().fg(self.theme.muted)
+        }
+    }
+
+    /// Entries the other player made on a shared board
+    pub fn partner_style(&self) -> Style {
+        if self.colorblind {
+            Style::new().italic()
+        } else {
+            Style::new().fg(self.theme.partner)
+        }
+    }
+
+    pub fn selected_style(&self) -> Style {
+        if self.colorblind {
+            Style::new().reversed()
+        } else {
+            Style::new().bg(self.theme.selected)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin() {
+        assert_eq!(Theme::builtin("light"), Some(Theme::LIGHT));
+        assert_eq!(Theme::builtin("solarized"), Some(Theme::SOLARIZED));
+        assert_eq!(Theme::builtin("unknown"), None);
+    }
+
+    #[test]
+    fn color_support() {
+        let term = |term: &str| Some(term.to_string());
+        assert_eq!(
+            ColorSupport::from_env(false, None, term("xterm-256color")),
+            ColorSupport::Full
+        );
+        assert_eq!(
+            ColorSupport::from_env(false, term("truecolor"), term("xterm")),
+            ColorSupport::Full
+        );
+        assert_eq!(
+            ColorSupport::from_env(false, None, term("linux")),
+            ColorSupport::Basic
+        );
+        assert_eq!(
+            ColorSupport::from_env(false, None, term("vt100-mono")),
+            ColorSupport::None
+        );
+        assert_eq!(
+            ColorSupport::from_env(true, None, term("xterm-256color")),
+            ColorSupport::None
+        );
+    }
+
+    #[test]
+    fn theme_for_terminal() {
+        assert_eq!(
+            Theme::SOLARIZED.for_terminal(ColorSupport::Full),
+            Theme::SOLARIZED
+        );
+        assert_eq!(
+            Theme::SOLARIZED.for_terminal(ColorSupport::Basic),
+            Theme::HIGH_CONTRAST
+        );
+        assert_eq!(
+            Theme::HIGH_CONTRAST.for_terminal(ColorSupport::Basic),
+            Theme::HIGH_CONTRAST
+        );
+        assert_eq!(
+            Theme::DARK.for_terminal(ColorSupport::None),
+            Theme::MONOCHROME
+        );
+    }
+
+    #[test]
+    fn deserialize_partial_theme() {
+        let theme: Theme = toml::from_str(
+            r##"
+            entry = "green"
+            conflict = "#ff00ff"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(
+            theme,
+            Theme {
+                entry: Color::Green,
+                conflict: Color::Rgb(0xff, 0x00, 0xff),
+                ..Theme::DARK
+            }
+        );
+    }
+}
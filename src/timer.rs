@@ -0,0 +1,104 @@
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Timer {
+    elapsed: Duration,
+    running_since: Option<Instant>,
+}
+
+impl Timer {
+    pub fn new() -> Self {
+        Self {
+            elapsed: Duration::ZERO,
+            running_since: None,
+        }
+    }
+
+    pub fn start() -> Self {
+        let mut timer = Self::new();
+        timer.resume();
+        timer
+    }
+
+    /// A running timer that has already counted `elapsed`
+    pub fn resumed_from(elapsed: Duration) -> Self {
+        Self {
+            elapsed,
+            running_since: Some(Instant::now()),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    pub fn pause(&mut self) {
+        if let Some(since) = self.running_since.take() {
+            self.elapsed += since.elapsed();
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.running_since.is_none() {
+            self.running_since = Some(Instant::now());
+        }
+    }
+
+    /// Take `duration` back off the time counted so far
+    pub fn rewind(&mut self, duration: Duration) {
+        self.elapsed = self.elapsed().saturating_sub(duration);
+        if self.running_since.is_some() {
+            self.running_since = Some(Instant::now());
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match self.running_since {
+            Some(since) => self.elapsed + since.elapsed(),
+            None => self.elapsed,
+        }
+    }
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes:02}:{seconds:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pause_and_resume() {
+        let mut timer = Timer::new();
+        assert!(!timer.is_running());
+        assert_eq!(timer.elapsed(), Duration::ZERO);
+        timer.resume();
+        assert!(timer.is_running());
+        std::thread::sleep(Duration::from_millis(5));
+        timer.pause();
+        let paused_at = timer.elapsed();
+        assert!(paused_at >= Duration::from_millis(5));
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(timer.elapsed(), paused_at);
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(format_duration(Duration::from_secs(0)), "00:00");
+        assert_eq!(format_duration(Duration::from_secs(461)), "07:41");
+        assert_eq!(format_duration(Duration::from_secs(3725)), "1:02:05");
+    }
+}
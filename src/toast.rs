@@ -0,0 +1,65 @@
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use std::time::{Duration, Instant};
+
+/// How long a toast stays up before it dismisses itself
+pub const TOAST_DURATION: Duration = Duration::from_secs(2);
+
+/// A short message shown in the corner of the screen for a moment
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub message: String,
+    shown_at: Instant,
+}
+
+impl Toast {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            shown_at: Instant::now(),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.shown_at.elapsed() >= TOAST_DURATION
+    }
+}
+
+impl Widget for &Toast {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = (self.message.chars().count() as u16 + 4).min(area.width);
+        let area = Rect::new(
+            area.right().saturating_sub(width),
+            area.y,
+            width,
+            3.min(area.height),
+        );
+        Clear.render(area, buf);
+        Paragraph::new(self.message.as_str())
+            .centered()
+            .block(Block::default().borders(Borders::ALL))
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_in_the_top_right_corner() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 12, 4));
+        Toast::new("Saved").render(buf.area, &mut buf);
+        assert_eq!(
+            buf,
+            Buffer::with_lines(vec![
+                "   ┌───────┐",
+                "   │ Saved │",
+                "   └───────┘",
+                "            ",
+            ])
+        );
+    }
+}
@@ -1,6 +1,10 @@
 use std::io::{self, stdout, Stdout};
 
-use crossterm::{execute, terminal::*};
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::*,
+};
 use ratatui::prelude::*;
 
 /// A type alias for the terminal type used in this application
@@ -8,14 +12,66 @@ pub type Tui = Terminal<CrosstermBackend<Stdout>>;
 
 /// Initialize the terminal
 pub fn init() -> io::Result<Tui> {
-    execute!(stdout(), EnterAlternateScreen)?;
+    execute!(stdout(), EnterAlternateScreen, EnableMouseCapture)?;
     enable_raw_mode()?;
     Terminal::new(CrosstermBackend::new(stdout()))
 }
 
 /// Restore the terminal to its original state
 pub fn restore() -> io::Result<()> {
-    execute!(stdout(), LeaveAlternateScreen)?;
+    execute!(stdout(), DisableMouseCapture, LeaveAlternateScreen)?;
     disable_raw_mode()?;
     Ok(())
 }
+
+/// A rect of the given size centered within `area`, clamped to fit inside it
+pub fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// Replace every non-ASCII symbol in `buf` with a plain ASCII stand-in, for
+/// terminals that can't draw box-drawing characters
+pub fn to_ascii(buf: &mut Buffer) {
+    for cell in buf.content.iter_mut() {
+        if !cell.symbol().is_ascii() {
+            let symbol = ascii_symbol(cell.symbol());
+            cell.set_symbol(symbol);
+        }
+    }
+}
+
+fn ascii_symbol(symbol: &str) -> &'static str {
+    match symbol {
+        "┌" | "┐" | "└" | "┘" | "├" | "┤" | "┬" | "┴" | "┼" => "+",
+        "─" | "┄" | "╶" | "╴" => "-",
+        "│" | "╷" | "╵" => "|",
+        "┆" => ":",
+        "·" => ".",
+        "✓" => "*",
+        "█" => "#",
+        "░" => ".",
+        "▁" | "▂" | "▃" => "_",
+        "▄" | "▅" => "=",
+        "▆" | "▇" => "#",
+        "←" => "<",
+        "→" => ">",
+        "⁰" => "0",
+        "¹" => "1",
+        "²" => "2",
+        "³" => "3",
+        "⁴" => "4",
+        "⁵" => "5",
+        "⁶" => "6",
+        "⁷" => "7",
+        "⁸" => "8",
+        "⁹" => "9",
+        _ => "?",
+    }
+}
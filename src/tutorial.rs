@@ -0,0 +1,247 @@
+//! A guided first game: the rules explained a region at a time, then a very
+//! easy puzzle solved one deduction at a time, with the player entering
+//! each value and mistakes explained rather than placed
+
+use crate::{
+    game::Game,
+    grid::{value_symbol, GridPosition},
+    hint::{find_hint, Hint},
+};
+
+/// Only a few cells short of solved, each one a single
+#[rustfmt::skip]
+pub const PUZZLE: [usize; 81] = [
+    4, 6, 7, 1, 0, 0, 8, 0, 5, // row 0
+    9, 1, 2, 8, 3, 5, 6, 0, 7, // row 1
+    0, 8, 5, 6, 4, 7, 1, 9, 2, // row 2
+    2, 9, 6, 3, 5, 1, 4, 7, 0, // row 3
+    7, 0, 8, 9, 2, 0, 3, 5, 1, // row 4
+    5, 3, 1, 4, 0, 8, 9, 2, 6, // row 5
+    0, 7, 3, 0, 6, 4, 5, 1, 0, // row 6
+    6, 2, 4, 5, 1, 9, 7, 8, 3, // row 7
+    1, 5, 9, 7, 8, 3, 0, 6, 4, // row 8
+];
+
+/// Cells the script points at while explaining
+#[derive(Debug, Clone, Copy)]
+enum Region {
+    Row(usize),
+    Column(usize),
+    Box(usize),
+    Nothing,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    /// Text read before moving on
+    Explain(&'static str, Region),
+    /// Hints to follow until the puzzle is solved
+    Solve,
+}
+
+const SCRIPT: &[Step] = &[
+    Step::Explain(
+        "Welcome to sudoku! Every row holds each of the numbers 1 to 9 exactly once, like this one.",
+        Region::Row(1),
+    ),
+    Step::Explain(
+        "Every column holds each of them exactly once too.",
+        Region::Column(3),
+    ),
+    Step::Explain(
+        "And so does every 3×3 box.",
+        Region::Box(4),
+    ),
+    Step::Explain(
+        "Some cells are empty. You'll fill them in one at a time, each time working out the \
+         only number that can go there.",
+        Region::Nothing,
+    ),
+    Step::Solve,
+    Step::Explain(
+        "Solved! Every sudoku is finished this way, one deduction at a time.",
+        Region::Nothing,
+    ),
+];
+
+/// What the tutorial shows over the board
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overlay {
+    pub text: String,
+    /// Cells to highlight
+    pub region: Vec<GridPosition>,
+    /// The cell to fill in next, if the player is meant to enter a value
+    pub target: Option<GridPosition>,
+}
+
+pub struct Tutorial {
+    pub game: Game,
+    step: usize,
+    /// The deduction the player is being walked through
+    hint: Option<Hint>,
+    /// Why the player's last entry was turned down
+    correction: Option<String>,
+}
+
+impl Default for Tutorial {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tutorial {
+    pub fn new() -> Self {
+        Self {
+            game: Game::new(PUZZLE.to_vec()).unwrap(),
+            step: 0,
+            hint: None,
+            correction: None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.step >= SCRIPT.len()
+    }
+
+    /// Whether the player is meant to enter a value rather than read on
+    pub fn is_waiting_for_entry(&self) -> bool {
+        self.hint.is_some()
+    }
+
+    pub fn overlay(&self) -> Overlay {
+        if let Some(hint) = &self.hint {
+            let text = match &self.correction {
+                Some(correction) => correction.clone(),
+                None => format!(
+                    "{}. Move to the marked cell and enter it.",
+                    hint.explanation
+                ),
+            };
+            return Overlay {
+                text,
+                region: hint.region.clone(),
+                target: Some(hint.position),
+            };
+        }
+        match SCRIPT.get(self.step) {
+            Some(Step::Explain(text, region)) => Overlay {
+                text: text.to_string(),
+                region: self.cells(*region),
+                target: None,
+            },
+            _ => Overlay {
+                text: String::new(),
+                region: vec![],
+                target: None,
+            },
+        }
+    }
+
+    /// Move on from an explanation. Does nothing while an entry is awaited.
+    pub fn advance(&mut self) {
+        if self.hint.is_none() && !self.is_finished() {
+            self.step += 1;
+            self.next_hint();
+        }
+    }
+
+    /// Enter `value` in the selected cell if it's the one the hint leads to,
+    /// or explain what's wrong with it. Returns whether it was placed.
+    pub fn enter(&mut self, value: usize) -> bool {
+        let Some(hint) = &self.hint else {
+            return false;
+        };
+        let position = self.game.selected;
+        if position != hint.position {
+            self.correction = Some(match self.game.get_cell(position) {
+                Ok(0) => "That cell can wait. Fill in the marked cell first.".to_string(),
+                _ => "That cell is already filled. Fill in the marked cell instead.".to_string(),
+            });
+            return false;
+        }
+        if value != hint.value {
+            let ruled_out = !self
+                .game
+                .candidates_for_cell(position)
+                .unwrap_or_default()
+                .contains(&value);
+            let symbol = value_symbol(value);
+            self.correction = Some(match ruled_out {
+                true => format!(
+                    "Not {symbol}: there's already a {symbol} in this cell's row, column or box."
+                ),
+                false => format!(
+                    "{symbol} isn't ruled out here, but it isn't the only number left either. \
+                     Look at the highlighted cells again."
+                ),
+            });
+            return false;
+        }
+        self.game.add_entry(position, value).unwrap();
+        self.correction = None;
+        self.next_hint();
+        true
+    }
+
+    /// Find the next deduction during the solving step, or move past it
+    /// once the puzzle is done
+    fn next_hint(&mut self) {
+        self.hint = None;
+        if !matches!(SCRIPT.get(self.step), Some(Step::Solve)) {
+            return;
+        }
+        match find_hint(&self.game) {
+            Some(hint) if !self.game.is_correct() => self.hint = Some(hint),
+            _ => self.step += 1,
+        }
+    }
+
+    fn cells(&self, region: Region) -> Vec<GridPosition> {
+        let (size, box_size) = (self.game.size(), self.game.box_size());
+        match region {
+            Region::Row(y) => (0..size).map(|x| (x, y)).collect(),
+            Region::Column(x) => (0..size).map(|y| (x, y)).collect(),
+            Region::Box(square) => {
+                let (left, top) = (square % box_size * box_size, square / box_size * box_size);
+                (0..size)
+                    .map(|i| (left + i % box_size, top + i / box_size))
+                    .collect()
+            }
+            Region::Nothing => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walks_through_the_puzzle() {
+        let mut tutorial = Tutorial::new();
+        assert_eq!(tutorial.overlay().region.len(), 9);
+        while !tutorial.is_waiting_for_entry() {
+            tutorial.advance();
+        }
+        let target = tutorial.overlay().target.unwrap();
+
+        // the wrong cell, then the wrong value, are explained and not placed
+        tutorial.game.selected = (0, 0);
+        assert!(!tutorial.enter(3));
+        assert!(tutorial.overlay().text.contains("already filled"));
+        tutorial.game.selected = target;
+        let solution = crate::solver::solve_cells(&PUZZLE).unwrap();
+        let answer = solution[target.1 * 9 + target.0];
+        assert!(!tutorial.enter(answer % 9 + 1));
+        assert_eq!(tutorial.game.get_cell(target), Ok(0));
+
+        while let Some(target) = tutorial.overlay().target {
+            tutorial.game.selected = target;
+            assert!(tutorial.enter(solution[target.1 * 9 + target.0]));
+        }
+        assert!(tutorial.game.is_correct());
+        assert!(tutorial.overlay().text.starts_with("Solved"));
+        tutorial.advance();
+        assert!(tutorial.is_finished());
+    }
+}
@@ -0,0 +1,74 @@
+use crate::grid::Regions;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Variant {
+    #[default]
+    Classic,
+    Mini,
+    /// X-Sudoku, where both main diagonals also hold every value once
+    Diagonal,
+    /// Four shaded windows that also hold every value once
+    Windoku,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 4] = [
+        Variant::Classic,
+        Variant::Mini,
+        Variant::Diagonal,
+        Variant::Windoku,
+    ];
+
+    pub fn size(self) -> usize {
+        match self {
+            Variant::Classic | Variant::Diagonal | Variant::Windoku => 9,
+            Variant::Mini => 4,
+        }
+    }
+
+    /// The name of the rule set, without the grid size
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Classic => "Classic",
+            Variant::Mini => "Mini",
+            Variant::Diagonal => "X-Sudoku",
+            Variant::Windoku => "Windoku",
+        }
+    }
+
+    /// What the rule set adds to a classic sudoku, in a line
+    pub fn description(self) -> &'static str {
+        match self {
+            Variant::Classic => "Rows, columns and boxes",
+            Variant::Mini => "A quick 4x4 grid",
+            Variant::Diagonal => "Both main diagonals hold every digit too",
+            Variant::Windoku => "Four shaded windows hold every digit too",
+        }
+    }
+
+    /// The variant played on a grid `size` cells wide with `regions`
+    pub fn find(size: usize, regions: Regions) -> Option<Variant> {
+        Variant::ALL
+            .into_iter()
+            .find(|variant| variant.size() == size && variant.regions() == regions)
+    }
+
+    /// The subsections the variant adds to the rows, columns and boxes
+    pub fn regions(self) -> Regions {
+        match self {
+            Variant::Classic | Variant::Mini => Regions::default(),
+            Variant::Diagonal => Regions::DIAGONAL,
+            Variant::Windoku => Regions::WINDOKU,
+        }
+    }
+}
+
+impl Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let size = self.size();
+        write!(f, "{} {size}x{size}", self.name())
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{
+    difficulty::Difficulty,
+    format::lenient,
+    generator,
+    grid::{Grid, GridPosition},
+    hint, logic,
+    rng::Rng,
+    solver,
+    variant::Variant,
+};
+use wasm_bindgen::prelude::*;
+
+// Grids cross over to JavaScript as a `Uint8Array` of every cell, row by row,
+// with 0 for an empty cell, and positions as indices into it
+
+fn from_js(cells: &[u8]) -> Vec<usize> {
+    cells.iter().map(|&value| value as usize).collect()
+}
+
+fn to_js(cells: Vec<usize>) -> Vec<u8> {
+    cells.into_iter().map(|value| value as u8).collect()
+}
+
+fn index(position: GridPosition, size: usize) -> u32 {
+    (position.1 * size + position.0) as u32
+}
+
+/// Read a grid pasted in any of the usual shapes
+#[wasm_bindgen]
+pub fn parse(text: &str) -> Result<Vec<u8>, JsError> {
+    lenient::parse(text)
+        .map(to_js)
+        .map_err(|error| JsError::new(&error.to_string()))
+}
+
+/// The first solution, or `undefined` if there is none
+#[wasm_bindgen]
+pub fn solve(cells: &[u8]) -> Option<Vec<u8>> {
+    solver::solve_cells(&from_js(cells)).map(to_js)
+}
+
+/// The number of solutions, counting no further than `limit`
+#[wasm_bindgen(js_name = countSolutions)]
+pub fn count_solutions(cells: &[u8], limit: usize) -> usize {
+    solver::count_solutions(&from_js(cells), limit)
+}
+
+#[wasm_bindgen]
+pub struct Puzzle {
+    givens: Vec<u8>,
+    solution: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Puzzle {
+    #[wasm_bindgen(getter)]
+    pub fn givens(&self) -> Vec<u8> {
+        self.givens.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn solution(&self) -> Vec<u8> {
+        self.solution.clone()
+    }
+}
+
+/// A new puzzle with a unique solution, 4 or 9 cells a side. There's no
+/// clock to seed from in the browser, so the seed comes from the caller.
+#[wasm_bindgen]
+pub fn generate(size: usize, difficulty: &str, seed: u32) -> Result<Puzzle, JsError> {
+    let variant = Variant::ALL
+        .into_iter()
+        .find(|variant| variant.size() == size)
+        .ok_or_else(|| JsError::new(&format!("no {size}x{size} puzzles")))?;
+    let difficulty: Difficulty = difficulty
+        .parse()
+        .map_err(|error: String| JsError::new(&error))?;
+    let generated = generator::generate(variant, difficulty, &mut Rng::new(seed as u64));
+    Ok(Puzzle {
+        givens: to_js(generated.givens),
+        solution: to_js(generated.solution),
+    })
+}
+
+#[wasm_bindgen]
+pub struct Rating {
+    difficulty: String,
+    techniques: Vec<String>,
+    solved: bool,
+}
+
+#[wasm_bindgen]
+impl Rating {
+    /// `"easy"`, `"medium"`, `"hard"` or `"expert"`
+    #[wasm_bindgen(getter)]
+    pub fn difficulty(&self) -> String {
+        self.difficulty.clone()
+    }
+
+    /// Names of the techniques used, simplest first
+    #[wasm_bindgen(getter)]
+    pub fn techniques(&self) -> Vec<String> {
+        self.techniques.clone()
+    }
+
+    /// False if solving it takes guessing
+    #[wasm_bindgen(getter)]
+    pub fn solved(&self) -> bool {
+        self.solved
+    }
+}
+
+/// Grade a puzzle by the techniques it takes, or `undefined` if its givens
+/// clash
+#[wasm_bindgen]
+pub fn rate(cells: &[u8]) -> Option<Rating> {
+    let rating = logic::rate(&from_js(cells))?;
+    Some(Rating {
+        difficulty: rating.difficulty.to_string().to_lowercase(),
+        techniques: rating
+            .techniques
+            .iter()
+            .map(|technique| technique.to_string())
+            .collect(),
+        solved: rating.solved,
+    })
+}
+
+#[wasm_bindgen]
+pub struct Hint {
+    cell: u32,
+    value: u8,
+    region: Vec<u32>,
+    explanation: String,
+}
+
+#[wasm_bindgen]
+impl Hint {
+    #[wasm_bindgen(getter)]
+    pub fn cell(&self) -> u32 {
+        self.cell
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+
+    /// The cells the deduction is drawn from
+    #[wasm_bindgen(getter)]
+    pub fn region(&self) -> Vec<u32> {
+        self.region.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn explanation(&self) -> String {
+        self.explanation.clone()
+    }
+}
+
+/// The next value that can be worked out from the grid, or `undefined` if
+/// none can without harder techniques
+#[wasm_bindgen]
+pub fn hint(cells: &[u8]) -> Result<Option<Hint>, JsError> {
+    let grid = Grid::new(from_js(cells)).map_err(|error| JsError::new(&format!("{error:?}")))?;
+    let size = grid.size();
+    Ok(hint::find_grid_hint(&grid).map(|hint| Hint {
+        cell: index(hint.position, size),
+        value: hint.value as u8,
+        region: hint
+            .region
+            .into_iter()
+            .map(|position| index(position, size))
+            .collect(),
+        explanation: hint.explanation,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{format::text, library::PUZZLES};
+
+    #[test]
+    fn runs_the_engine() {
+        let cells = to_js(PUZZLES[1].cells());
+        assert_eq!(
+            parse(&text::write_line(&PUZZLES[1].cells())).unwrap(),
+            cells
+        );
+        let solution = solve(&cells).unwrap();
+        assert!(!solution.contains(&0));
+        assert_eq!(count_solutions(&cells, 2), 1);
+        assert_eq!(rate(&cells).unwrap().difficulty(), "easy");
+
+        let hint = hint(&cells).unwrap().unwrap();
+        assert_eq!(solution[hint.cell() as usize], hint.value());
+
+        let puzzle = generate(4, "easy", 7).unwrap();
+        assert_eq!(solve(&puzzle.givens()), Some(puzzle.solution()));
+    }
+}